@@ -7,13 +7,72 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Ident, Type};
 
-use crate::parse::{FieldValidation, MessageOverride, StructValidation, ValidatorAttr};
+use crate::parse::{
+    unquote, ConfigAttrs, FieldValidation, MessageOverride, StructValidation, ValidatorAttr,
+};
+use crate::units::UnitKind;
+
+/// A field that contributes key aliases: its own `#[config(alias = ...)]`
+/// entries, or those of its nested type.
+pub struct FieldAliases {
+    /// The field's key in configuration files
+    pub key: String,
+    pub ty: Type,
+    pub config: ConfigAttrs,
+    pub nested: bool,
+    pub flatten: bool,
+}
+
+/// Generate the `aliases()` method, if any field has aliases or is nested.
+pub fn generate_aliases_fn(fields: &[FieldAliases]) -> Option<TokenStream> {
+    if fields.is_empty() {
+        return None;
+    }
+
+    let entries = fields.iter().map(|field| {
+        let key = &field.key;
+        let ty = &field.ty;
+        let deprecated_since = field
+            .config
+            .deprecated_since
+            .as_ref()
+            .map(|version| quote! { .deprecated_since(#version) });
+        let own = field.config.aliases.iter().map(|old| {
+            quote! {
+                aliases.push(::premortem::KeyAlias::new(#old, #key) #deprecated_since);
+            }
+        });
+        let prefix = if field.flatten { "" } else { key.as_str() };
+        let nested = field.nested.then(|| {
+            quote! {
+                aliases.extend(
+                    <#ty as ::premortem::Validate>::aliases()
+                        .into_iter()
+                        .map(|alias| alias.with_prefix(#prefix)),
+                );
+            }
+        });
+        quote! {
+            #(#own)*
+            #nested
+        }
+    });
+
+    Some(quote! {
+        fn aliases() -> ::std::vec::Vec<::premortem::KeyAlias> {
+            let mut aliases = ::std::vec::Vec::new();
+            #(#entries)*
+            aliases
+        }
+    })
+}
 
 /// Generate the complete `Validate` impl for a struct.
 pub fn generate_validate_impl(
     struct_name: &Ident,
     fields: &[(Ident, Type, FieldValidation)],
     struct_validation: &StructValidation,
+    aliases_fn: Option<TokenStream>,
 ) -> TokenStream {
     let field_validations = generate_field_validations(fields);
     let struct_custom = generate_struct_custom(struct_validation);
@@ -26,6 +85,8 @@ pub fn generate_validate_impl(
                     fn validate(&self) -> ::premortem::ConfigValidation<()> {
                         #custom_call
                     }
+
+                    #aliases_fn
                 }
             };
         } else {
@@ -34,6 +95,8 @@ pub fn generate_validate_impl(
                     fn validate(&self) -> ::premortem::ConfigValidation<()> {
                         ::stillwater::Validation::Success(())
                     }
+
+                    #aliases_fn
                 }
             };
         }
@@ -83,10 +146,23 @@ pub fn generate_validate_impl(
             fn validate(&self) -> ::premortem::ConfigValidation<()> {
                 #body
             }
+
+            #aliases_fn
         }
     }
 }
 
+/// How a field's value is read and whether it may appear in errors.
+#[derive(Debug, Clone, Copy)]
+struct FieldAccess {
+    /// Leave the value out of error messages (`#[sensitive]` or a `Secret`).
+    sensitive: bool,
+    /// Validate the value wrapped by a `Secret`.
+    secret: bool,
+    /// How string range bounds valid as both a duration and a size are read.
+    units: Option<UnitKind>,
+}
+
 /// Generate validation expressions for all fields.
 fn generate_field_validations(fields: &[(Ident, Type, FieldValidation)]) -> Vec<TokenStream> {
     fields
@@ -111,7 +187,11 @@ fn generate_field_validations(fields: &[(Ident, Type, FieldValidation)]) -> Vec<
                 name,
                 &validation.validators,
                 &validation.message,
-                validation.sensitive,
+                FieldAccess {
+                    sensitive: validation.sensitive,
+                    secret: validation.secret,
+                    units: UnitKind::of_type(ty),
+                },
             ))
         })
         .collect()
@@ -138,7 +218,7 @@ fn generate_validators_for_field(
     name: &Ident,
     validators: &[ValidatorAttr],
     message: &MessageOverride,
-    sensitive: bool,
+    access: FieldAccess,
 ) -> TokenStream {
     let field_name = name.to_string();
 
@@ -161,12 +241,12 @@ fn generate_validators_for_field(
 
     // Generate regular validators
     for v in &regular_validators {
-        validation_exprs.push(generate_validator_expr(v, &field_name, message, sensitive));
+        validation_exprs.push(generate_validator_expr(v, &field_name, message, access));
     }
 
     // Generate conditional validators
     for (condition, inner) in conditional_validators {
-        let inner_expr = generate_validator_expr(&inner, &field_name, message, sensitive);
+        let inner_expr = generate_validator_expr(&inner, &field_name, message, access);
         let cond_tokens: TokenStream = condition.parse().unwrap_or_else(|_| quote! { false });
 
         validation_exprs.push(quote! {
@@ -201,75 +281,78 @@ fn generate_validator_expr(
     validator: &ValidatorAttr,
     field_name: &str,
     message: &MessageOverride,
-    sensitive: bool,
+    access: FieldAccess,
 ) -> TokenStream {
     let custom_msg = message.0.as_ref();
 
     match validator {
         ValidatorAttr::NonEmpty => {
-            generate_simple_validator(field_name, quote! { NonEmpty }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { NonEmpty }, custom_msg, access)
         }
         ValidatorAttr::MinLength(n) => {
-            generate_simple_validator(field_name, quote! { MinLength(#n) }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { MinLength(#n) }, custom_msg, access)
         }
         ValidatorAttr::MaxLength(n) => {
-            generate_simple_validator(field_name, quote! { MaxLength(#n) }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { MaxLength(#n) }, custom_msg, access)
         }
         ValidatorAttr::Length(min, max) => generate_simple_validator(
             field_name,
             quote! { Length(#min..=#max) },
             custom_msg,
-            sensitive,
+            access,
         ),
         ValidatorAttr::Pattern(pat) => generate_simple_validator(
             field_name,
             quote! { Pattern::new(#pat) },
             custom_msg,
-            sensitive,
+            access,
         ),
         ValidatorAttr::Email => {
-            generate_simple_validator(field_name, quote! { Email }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { Email }, custom_msg, access)
         }
         ValidatorAttr::Url => {
-            generate_simple_validator(field_name, quote! { Url }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { Url }, custom_msg, access)
         }
-        ValidatorAttr::Ip => generate_ip_validator(field_name, custom_msg, sensitive),
-        ValidatorAttr::Uuid => generate_uuid_validator(field_name, custom_msg, sensitive),
+        ValidatorAttr::Ip => generate_ip_validator(field_name, custom_msg, access),
+        ValidatorAttr::Uuid => generate_uuid_validator(field_name, custom_msg, access),
 
         ValidatorAttr::Range(min, max) => {
-            let min_tokens: TokenStream = min.parse().unwrap_or_else(|_| quote! { 0 });
-            let max_tokens: TokenStream = max.parse().unwrap_or_else(|_| quote! { 0 });
-            generate_simple_validator(
-                field_name,
-                quote! { Range(#min_tokens..=#max_tokens) },
-                custom_msg,
-                sensitive,
-            )
+            let validator = if min.starts_with('"') {
+                match unit_range(unquote(min), unquote(max), access.units) {
+                    Ok(validator) => validator,
+                    Err(message) => return quote! { ::core::compile_error!(#message) },
+                }
+            } else {
+                let min_tokens: TokenStream = min.parse().unwrap_or_else(|_| quote! { 0 });
+                let max_tokens: TokenStream = max.parse().unwrap_or_else(|_| quote! { 0 });
+                quote! { Range(#min_tokens..=#max_tokens) }
+            };
+            generate_simple_validator(field_name, validator, custom_msg, access)
         }
         ValidatorAttr::Positive => {
-            generate_simple_validator(field_name, quote! { Positive }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { Positive }, custom_msg, access)
         }
         ValidatorAttr::Negative => {
-            generate_simple_validator(field_name, quote! { Negative }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { Negative }, custom_msg, access)
         }
         ValidatorAttr::NonZero => {
-            generate_simple_validator(field_name, quote! { NonZero }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { NonZero }, custom_msg, access)
         }
 
         ValidatorAttr::FileExists => {
-            generate_simple_validator(field_name, quote! { FileExists }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { FileExists }, custom_msg, access)
         }
         ValidatorAttr::DirExists => {
-            generate_simple_validator(field_name, quote! { DirExists }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { DirExists }, custom_msg, access)
         }
         ValidatorAttr::ParentExists => {
-            generate_simple_validator(field_name, quote! { ParentExists }, custom_msg, sensitive)
+            generate_simple_validator(field_name, quote! { ParentExists }, custom_msg, access)
         }
         ValidatorAttr::Extension(ext) => generate_simple_validator(
             field_name,
             quote! { Extension::new(#ext) },
             custom_msg,
-            sensitive,
+            access,
         ),
 
         ValidatorAttr::Each(inner) => {
@@ -285,7 +368,7 @@ fn generate_validator_expr(
                 field_name,
                 quote! { Each(#inner_validator) },
                 custom_msg,
-                sensitive,
+                access,
             )
         }
 
@@ -296,6 +379,13 @@ fn generate_validator_expr(
             }
         }
 
+        ValidatorAttr::Warn(inner) => {
+            let inner_expr = generate_validator_expr(inner, field_name, message, access);
+            quote! {
+                ::premortem::validate::warn_on_failure(#inner_expr)
+            }
+        }
+
         // These are handled elsewhere
         ValidatorAttr::Nested | ValidatorAttr::Skip | ValidatorAttr::When(_, _) => {
             quote! { ::stillwater::Validation::Success(()) }
@@ -303,27 +393,70 @@ fn generate_validator_expr(
     }
 }
 
+/// Build a `DurationRange` or `SizeRange` from string bounds, parsed now so
+/// the generated validator holds the values.
+///
+/// Bounds valid as both kinds, like `"0"..="5m"`, follow the field's type.
+fn unit_range(min: &str, max: &str, units: Option<UnitKind>) -> Result<TokenStream, String> {
+    let kinds = UnitKind::shared_by(min, max);
+    let kind = match (kinds.as_slice(), units) {
+        ([kind], _) => *kind,
+        ([_, _], Some(kind)) => kind,
+        _ => {
+            return Err(format!(
+                "range bounds \"{}\" and \"{}\" are valid as both durations and sizes; \
+                 add units that tell them apart",
+                min, max
+            ))
+        }
+    };
+    // Checked while parsing the attribute
+    let (min, max) = (kind.parse(min).unwrap_or(0), kind.parse(max).unwrap_or(0));
+    Ok(match kind {
+        UnitKind::Duration => {
+            let duration = |nanos: u128| {
+                let (secs, subsec) = (
+                    (nanos / 1_000_000_000) as u64,
+                    (nanos % 1_000_000_000) as u32,
+                );
+                quote! { ::premortem::Duration::from(::std::time::Duration::new(#secs, #subsec)) }
+            };
+            let (min, max) = (duration(min), duration(max));
+            quote! { DurationRange(#min..=#max) }
+        }
+        UnitKind::Size => {
+            let (min, max) = (min as u64, max as u64);
+            quote! { SizeRange(::premortem::ByteSize::new(#min)..=::premortem::ByteSize::new(#max)) }
+        }
+    })
+}
+
 /// Generate a simple validator call using validate_field.
 /// Attaches source locations from the validation context.
 fn generate_simple_validator(
     field_name: &str,
     validator: TokenStream,
     custom_msg: Option<&String>,
-    sensitive: bool,
+    access: FieldAccess,
 ) -> TokenStream {
     let field_ident: TokenStream = format!("self.{}", field_name)
         .parse()
         .unwrap_or_else(|_| quote! { self.field });
+    let field_ref = if access.secret {
+        quote! { #field_ident.expose_secret() }
+    } else {
+        quote! { &#field_ident }
+    };
 
     if let Some(msg) = custom_msg {
         // With custom message
-        if sensitive {
+        if access.sensitive {
             quote! {
                 {
                     use ::premortem::validators::*;
                     use ::premortem::Validator;
                     let source_location = ::premortem::validate::current_source_location(#field_name);
-                    let result = (#validator).validate(&#field_ident, #field_name);
+                    let result = (#validator).validate(#field_ref, #field_name);
                     result.map_err(|errors| {
                         ::premortem::ConfigErrors::from_nonempty(
                             errors.0.map(|e| {
@@ -349,7 +482,7 @@ fn generate_simple_validator(
                     use ::premortem::validators::*;
                     use ::premortem::Validator;
                     let source_location = ::premortem::validate::current_source_location(#field_name);
-                    let result = (#validator).validate(&#field_ident, #field_name);
+                    let result = (#validator).validate(#field_ref, #field_name);
                     result.map_err(|errors| {
                         ::premortem::ConfigErrors::from_nonempty(
                             errors.0.map(|e| {
@@ -370,14 +503,14 @@ fn generate_simple_validator(
                 }
             }
         }
-    } else if sensitive {
+    } else if access.sensitive {
         // Sensitive without custom message - redact value
         quote! {
             {
                 use ::premortem::validators::*;
                 use ::premortem::Validator;
                 let source_location = ::premortem::validate::current_source_location(#field_name);
-                let result = (#validator).validate(&#field_ident, #field_name);
+                let result = (#validator).validate(#field_ref, #field_name);
                 result.map_err(|errors| {
                     ::premortem::ConfigErrors::from_nonempty(
                         errors.0.map(|e| {
@@ -404,7 +537,7 @@ fn generate_simple_validator(
                 use ::premortem::validators::*;
                 use ::premortem::Validator;
                 let source_location = ::premortem::validate::current_source_location(#field_name);
-                let result = (#validator).validate(&#field_ident, #field_name);
+                let result = (#validator).validate(#field_ref, #field_name);
                 result.map_err(|errors| {
                     ::premortem::ConfigErrors::from_nonempty(
                         errors.0.map(|e| {
@@ -431,7 +564,7 @@ fn generate_simple_validator(
 fn generate_ip_validator(
     field_name: &str,
     custom_msg: Option<&String>,
-    sensitive: bool,
+    access: FieldAccess,
 ) -> TokenStream {
     // Simple IP pattern - for strict validation, user should use custom validator
     let pattern = r"^(\d{1,3}\.){3}\d{1,3}$|^([0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}$";
@@ -443,7 +576,7 @@ fn generate_ip_validator(
         field_name,
         quote! { Pattern::new(#pattern) },
         Some(&msg),
-        sensitive,
+        access,
     )
 }
 
@@ -451,7 +584,7 @@ fn generate_ip_validator(
 fn generate_uuid_validator(
     field_name: &str,
     custom_msg: Option<&String>,
-    sensitive: bool,
+    access: FieldAccess,
 ) -> TokenStream {
     let pattern = r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$";
     let msg = custom_msg
@@ -462,7 +595,7 @@ fn generate_uuid_validator(
         field_name,
         quote! { Pattern::new(#pattern) },
         Some(&msg),
-        sensitive,
+        access,
     )
 }
 
@@ -2,7 +2,8 @@
 //!
 //! This crate provides the `#[derive(Validate)]` macro that generates
 //! `Validate` trait implementations using stillwater's `Validation::all()`
-//! pattern for error accumulation.
+//! pattern for error accumulation, and `#[derive(ConfigSchema)]`, which
+//! describes a config struct as a JSON Schema using the same attributes.
 //!
 //! # Basic Usage
 //!
@@ -69,6 +70,22 @@
 //! }
 //! ```
 //!
+//! # Renamed Keys
+//!
+//! Use `#[config(alias = "...")]` to keep accepting a field's old name. Values
+//! under the old key are moved to the field with a deprecation warning:
+//!
+//! ```ignore
+//! #[derive(Validate)]
+//! struct DatabaseConfig {
+//!     #[config(alias = "url", deprecated_since = "0.7")]
+//!     connection_url: String,
+//! }
+//! ```
+//!
+//! Aliases are relative to the struct; `nested` fields include the aliases of
+//! their type below the field's key.
+//!
 //! # Struct-Level Validation
 //!
 //! Use `#[validate(custom = "fn_name")]` on the struct for cross-field validation:
@@ -97,6 +114,8 @@ extern crate proc_macro;
 
 mod codegen;
 mod parse;
+mod schema;
+mod units;
 mod validate;
 mod validators;
 
@@ -131,7 +150,7 @@ use syn::{parse_macro_input, DeriveInput};
 ///     tls: Option<TlsConfig>,
 /// }
 /// ```
-#[proc_macro_derive(Validate, attributes(validate, sensitive))]
+#[proc_macro_derive(Validate, attributes(validate, sensitive, config))]
 pub fn derive_validate(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -140,3 +159,40 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
         Err(err) => err.to_compile_error().into(),
     }
 }
+
+/// Derive the `ConfigSchema` trait for a struct or an enum of unit variants.
+///
+/// The generated implementation describes the struct as a JSON Schema
+/// (draft 2020-12). `#[validate(...)]` attributes become schema keywords,
+/// `///` doc comments become descriptions, `#[sensitive]` fields are marked
+/// `writeOnly`, and serde's `rename`, `rename_all`, `default`, `skip`,
+/// `flatten`, and `deny_unknown_fields` are honored. An enum whose variants
+/// have no fields becomes a string `enum` of its variant names, and an
+/// `Option<T>` field also accepts `null`.
+///
+/// # Example
+///
+/// ```ignore
+/// use premortem::prelude::*;
+///
+/// #[derive(Deserialize, DeriveValidate, DeriveConfigSchema)]
+/// struct DatabaseConfig {
+///     /// Hostname of the primary
+///     #[validate(non_empty)]
+///     host: String,
+///
+///     #[validate(range(1..=65535))]
+///     port: u16,
+/// }
+///
+/// let schema = Config::<DatabaseConfig>::json_schema();
+/// ```
+#[proc_macro_derive(ConfigSchema, attributes(validate, sensitive, serde, config))]
+pub fn derive_config_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match schema::derive_config_schema(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -1,15 +1,18 @@
 //! Attribute parsing for the Validate derive macro.
 //!
 //! This module handles parsing of `#[validate(...)]` and `#[sensitive]` attributes
-//! on struct fields and structs themselves.
+//! on struct fields and structs themselves, plus the `#[config(...)]` and
+//! `#[serde(...)]` attributes that affect configuration keys.
 
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Error, Expr, ExprLit, ExprRange, Ident, Lit, LitStr, Result, Token,
+    Attribute, Error, Expr, ExprLit, ExprRange, Ident, Lit, LitStr, Result, Token, Type,
 };
 
+use crate::units::UnitKind;
+
 /// A parsed field attribute representing a single validation rule.
 #[derive(Debug, Clone)]
 pub enum ValidatorAttr {
@@ -44,6 +47,7 @@ pub enum ValidatorAttr {
     Skip,
     Custom(String),                   // function name
     When(String, Box<ValidatorAttr>), // condition, validator
+    Warn(Box<ValidatorAttr>),         // severity = "warn"
 }
 
 /// Custom error message override.
@@ -56,6 +60,8 @@ pub struct FieldValidation {
     pub validators: Vec<ValidatorAttr>,
     pub message: MessageOverride,
     pub sensitive: bool,
+    /// The field is a `Secret<T>`; validators check the wrapped value.
+    pub secret: bool,
 }
 
 /// Struct-level validation configuration.
@@ -285,10 +291,14 @@ enum ValidatorContent {
 }
 
 /// Parse a `#[validate(...)]` attribute into validators and optional message.
+///
+/// With `severity = "warn"`, every validator in the attribute is wrapped in
+/// `ValidatorAttr::Warn`, inside any `when` condition.
 pub fn parse_validate_attr(attr: &Attribute) -> Result<(Vec<ValidatorAttr>, MessageOverride)> {
     let mut validators = Vec::new();
     let mut message = MessageOverride::default();
     let mut when_condition: Option<String> = None;
+    let mut warn = false;
 
     let nested = attr.parse_args_with(Punctuated::<ValidateItem, Token![,]>::parse_terminated)?;
 
@@ -308,12 +318,49 @@ pub fn parse_validate_attr(attr: &Attribute) -> Result<(Vec<ValidatorAttr>, Mess
             ValidateItem::When(cond) => {
                 when_condition = Some(cond);
             }
+            ValidateItem::Severity(severity) => {
+                warn = match severity.value().as_str() {
+                    "warn" | "warning" => true,
+                    "error" => false,
+                    other => {
+                        return Err(Error::new(
+                            severity.span(),
+                            format!(
+                                "unknown severity '{}'; expected \"warn\" or \"error\"",
+                                other
+                            ),
+                        ))
+                    }
+                };
+            }
         }
     }
 
+    if warn {
+        validators = validators
+            .into_iter()
+            .map(|validator| with_warn_severity(validator, attr))
+            .collect::<Result<_>>()?;
+    }
+
     Ok((validators, message))
 }
 
+/// Wrap a validator so its failures become warnings.
+fn with_warn_severity(validator: ValidatorAttr, attr: &Attribute) -> Result<ValidatorAttr> {
+    match validator {
+        ValidatorAttr::When(cond, inner) => Ok(ValidatorAttr::When(
+            cond,
+            Box::new(with_warn_severity(*inner, attr)?),
+        )),
+        ValidatorAttr::Nested | ValidatorAttr::Skip => Err(Error::new(
+            attr.meta.span(),
+            format!("severity cannot be used with '{}'", validator.name()),
+        )),
+        other => Ok(ValidatorAttr::Warn(Box::new(other))),
+    }
+}
+
 /// Parse struct-level `#[validate(custom = "fn_name")]` attribute.
 pub fn parse_struct_validate_attr(attr: &Attribute) -> Result<StructValidation> {
     let mut validation = StructValidation::default();
@@ -354,6 +401,12 @@ pub fn parse_struct_validate_attr(attr: &Attribute) -> Result<StructValidation>
                     "when is not supported at struct level",
                 ));
             }
+            ValidateItem::Severity(_) => {
+                return Err(Error::new(
+                    attr.meta.span(),
+                    "severity is not supported at struct level",
+                ));
+            }
         }
     }
 
@@ -368,6 +421,7 @@ enum ValidateItem {
     },
     Message(String),
     When(String),
+    Severity(LitStr),
 }
 
 impl Parse for ValidateItem {
@@ -383,6 +437,7 @@ impl Parse for ValidateItem {
             return match name.to_string().as_str() {
                 "message" => Ok(ValidateItem::Message(value)),
                 "when" => Ok(ValidateItem::When(value)),
+                "severity" => Ok(ValidateItem::Severity(lit)),
                 "custom" => Ok(ValidateItem::Validator {
                     name,
                     content: Some(ValidatorContent::NameValue(value)),
@@ -520,23 +575,34 @@ fn parse_range_expr(expr: &Expr) -> Result<(usize, usize)> {
     }
 }
 
-/// Parse a range expression into (min, max) strings for numeric ranges.
+/// Parse a range expression into (min, max) strings for numeric ranges, or
+/// quoted strings for unit ranges like `"1s"..="5m"`.
 fn parse_range_strings(expr: &Expr) -> Result<(String, String)> {
     match expr {
         Expr::Range(ExprRange {
             start, end, limits, ..
         }) => {
-            let start_str = start
+            let end_str = end
                 .as_ref()
                 .map(|e| expr_to_string(e))
                 .transpose()?
-                .unwrap_or_else(|| "0".to_string());
+                .ok_or_else(|| Error::new(expr.span(), "range must have an end value"))?;
 
-            let end_str = end
+            let is_unit = end_str.starts_with('"');
+            let start_str = start
                 .as_ref()
                 .map(|e| expr_to_string(e))
                 .transpose()?
-                .ok_or_else(|| Error::new(expr.span(), "range must have an end value"))?;
+                .unwrap_or_else(|| if is_unit { "\"0\"" } else { "0" }.to_string());
+            if start_str.starts_with('"') != is_unit {
+                return Err(Error::new(
+                    expr.span(),
+                    "range bounds must both be numbers or both be strings",
+                ));
+            }
+            if is_unit {
+                check_unit_bounds(expr, &start_str, &end_str)?;
+            }
 
             // For half-open ranges, we'd need to adjust, but for simplicity
             // we require inclusive ranges for numeric validators
@@ -555,9 +621,43 @@ fn parse_range_strings(expr: &Expr) -> Result<(String, String)> {
     }
 }
 
-/// Convert an expression to its string representation.
+/// Check that quoted range bounds are both durations or both sizes.
+fn check_unit_bounds(expr: &Expr, min: &str, max: &str) -> Result<()> {
+    let (min, max) = (unquote(min), unquote(max));
+    for bound in [min, max] {
+        if UnitKind::Duration.parse(bound).is_none() && UnitKind::Size.parse(bound).is_none() {
+            return Err(Error::new(
+                expr.span(),
+                format!(
+                    "invalid range bound \"{}\": expected a duration like \"30s\" or a size like \"512MiB\"",
+                    bound
+                ),
+            ));
+        }
+    }
+    if UnitKind::shared_by(min, max).is_empty() {
+        return Err(Error::new(
+            expr.span(),
+            "range bounds must both be durations or both be sizes",
+        ));
+    }
+    Ok(())
+}
+
+/// Strip the quotes `expr_to_string` keeps around a string literal.
+pub fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Convert an expression to its string representation, keeping string
+/// literals quoted.
 fn expr_to_string(expr: &Expr) -> Result<String> {
     match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(format!("{:?}", lit.value())),
         Expr::Lit(ExprLit {
             lit: Lit::Int(lit), ..
         }) => Ok(lit.to_string()),
@@ -573,10 +673,191 @@ fn expr_to_string(expr: &Expr) -> Result<String> {
                 _ => Err(Error::new(expr.span(), "unsupported unary operator")),
             }
         }
-        _ => Err(Error::new(expr.span(), "expected numeric literal in range")),
+        _ => Err(Error::new(
+            expr.span(),
+            "expected numeric or string literal in range",
+        )),
+    }
+}
+
+/// Serde attributes that change how a struct or field appears in config files.
+#[derive(Debug, Clone, Default)]
+pub struct SerdeAttrs {
+    /// `rename = "..."`
+    pub rename: Option<String>,
+    /// `rename_all = "..."` (struct level)
+    pub rename_all: Option<String>,
+    /// `default` or `default = "..."`
+    pub default: bool,
+    /// `skip` or `skip_deserializing`
+    pub skip: bool,
+    /// `flatten`
+    pub flatten: bool,
+    /// `deny_unknown_fields` (struct level)
+    pub deny_unknown_fields: bool,
+}
+
+/// Parse the `#[serde(...)]` attributes relevant to configuration keys.
+///
+/// Other serde options are accepted and ignored.
+pub fn parse_serde_attrs(attrs: &[Attribute]) -> Result<SerdeAttrs> {
+    let mut serde = SerdeAttrs::default();
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            let name = meta
+                .path
+                .get_ident()
+                .map(|i| i.to_string())
+                .unwrap_or_default();
+            match name.as_str() {
+                "rename" if meta.input.peek(Token![=]) => {
+                    serde.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                }
+                "rename_all" if meta.input.peek(Token![=]) => {
+                    serde.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+                }
+                "default" => {
+                    serde.default = true;
+                    skip_meta_value(&meta)?;
+                }
+                "skip" | "skip_deserializing" => serde.skip = true,
+                "flatten" => serde.flatten = true,
+                "deny_unknown_fields" => serde.deny_unknown_fields = true,
+                _ => skip_meta_value(&meta)?,
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(serde)
+}
+
+/// Consume the value of a serde option we don't interpret (`= ...` or `(...)`).
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_meta_value(&inner))?;
+    }
+    Ok(())
+}
+
+/// Apply a serde `rename_all` rule to a snake_case field name.
+pub fn apply_rename_rule(name: &str, rule: &str) -> String {
+    let words: Vec<&str> = name.split('_').filter(|w| !w.is_empty()).collect();
+    let capitalize = |w: &str| {
+        let mut chars = w.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => words.iter().map(|w| capitalize(w)).collect(),
+        "camelCase" => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.to_string() } else { capitalize(w) })
+            .collect(),
+        "SCREAMING_SNAKE_CASE" => name.to_uppercase(),
+        "kebab-case" => words.join("-"),
+        "SCREAMING-KEBAB-CASE" => words.join("-").to_uppercase(),
+        _ => name.to_string(),
     }
 }
 
+/// Apply a serde `rename_all` rule to a PascalCase enum variant name.
+pub fn apply_variant_rename_rule(name: &str, rule: &str) -> String {
+    match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => name.to_string(),
+        _ => {
+            let mut snake = String::new();
+            for (i, c) in name.chars().enumerate() {
+                if c.is_uppercase() && i > 0 {
+                    snake.push('_');
+                }
+                snake.extend(c.to_lowercase());
+            }
+            apply_rename_rule(&snake, rule)
+        }
+    }
+}
+
+/// Collect `///` doc comments into a description.
+///
+/// Lines are joined into paragraphs; blank lines separate paragraphs.
+pub fn parse_doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }),
+                ..
+            }) => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let description = lines
+        .split(|line| line.is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    (!description.is_empty()).then_some(description)
+}
+
+/// Options from `#[config(...)]` on a field.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigAttrs {
+    /// `alias = "..."`, repeatable
+    pub aliases: Vec<String>,
+    /// `deprecated_since = "..."`
+    pub deprecated_since: Option<String>,
+}
+
+/// Parse the `#[config(...)]` attributes of a field.
+pub fn parse_config_attrs(attrs: &[Attribute]) -> Result<ConfigAttrs> {
+    let mut config = ConfigAttrs::default();
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("alias") {
+                let alias = meta.value()?.parse::<LitStr>()?;
+                if alias.value().is_empty() {
+                    return Err(Error::new(alias.span(), "alias cannot be empty"));
+                }
+                config.aliases.push(alias.value());
+            } else if meta.path.is_ident("deprecated_since") {
+                config.deprecated_since = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `alias` or `deprecated_since`"));
+            }
+            Ok(())
+        })?;
+
+        if config.deprecated_since.is_some() && config.aliases.is_empty() {
+            return Err(Error::new_spanned(
+                attr,
+                "`deprecated_since` requires an `alias`",
+            ));
+        }
+    }
+
+    Ok(config)
+}
+
 /// Check if an attribute is a `#[validate(...)]` attribute.
 pub fn is_validate_attr(attr: &Attribute) -> bool {
     attr.path().is_ident("validate")
@@ -587,10 +868,180 @@ pub fn is_sensitive_attr(attr: &Attribute) -> bool {
     attr.path().is_ident("sensitive")
 }
 
+/// Check if a type is `Secret<T>`.
+pub fn is_secret_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            return segment.ident == "Secret";
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_severity() {
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(positive, range(1..=200), severity = "warn")]
+            pool_size: i64
+        };
+        let (validators, _) = parse_validate_attr(&field.attrs[0]).unwrap();
+        assert!(matches!(&validators[0], ValidatorAttr::Warn(inner) if inner.name() == "positive"));
+        assert!(matches!(&validators[1], ValidatorAttr::Warn(inner) if inner.name() == "range"));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(when = "self.enabled", range(1..=200), severity = "warn")]
+            pool_size: i64
+        };
+        let (validators, _) = parse_validate_attr(&field.attrs[0]).unwrap();
+        assert!(matches!(
+            &validators[0],
+            ValidatorAttr::When(_, inner) if matches!(inner.as_ref(), ValidatorAttr::Warn(_))
+        ));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(positive, severity = "info")]
+            pool_size: i64
+        };
+        assert!(parse_validate_attr(&field.attrs[0]).is_err());
+
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(nested, severity = "warn")]
+            pool: Pool
+        };
+        assert!(parse_validate_attr(&field.attrs[0]).is_err());
+    }
+
+    #[test]
+    fn test_parse_unit_range() {
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(range("1s"..="5m"))]
+            ttl: Duration
+        };
+        let (validators, _) = parse_validate_attr(&field.attrs[0]).unwrap();
+        assert!(matches!(
+            &validators[0],
+            ValidatorAttr::Range(min, max) if min == "\"1s\"" && max == "\"5m\""
+        ));
+        assert_eq!(validators[0].describe(), "range(\"1s\"..=\"5m\")");
+
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(range(..="4GiB"))]
+            cache: ByteSize
+        };
+        let (validators, _) = parse_validate_attr(&field.attrs[0]).unwrap();
+        assert!(matches!(&validators[0], ValidatorAttr::Range(min, _) if min == "\"0\""));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[validate(range(1..="5m"))]
+            ttl: Duration
+        };
+        assert!(parse_validate_attr(&field.attrs[0]).is_err());
+
+        let err = |attr: syn::Attribute| parse_validate_attr(&attr).unwrap_err().to_string();
+        assert!(
+            err(syn::parse_quote!(#[validate(range("1s"..="5 minutes"))]))
+                .contains("invalid range bound \"5 minutes\"")
+        );
+        assert!(err(syn::parse_quote!(#[validate(range("1s"..="4GiB"))]))
+            .contains("both be durations or both be sizes"));
+    }
+
+    #[test]
+    fn test_parse_config_attrs() {
+        let field: syn::Field = syn::parse_quote! {
+            #[config(alias = "db_url", deprecated_since = "0.7")]
+            #[config(alias = "url")]
+            connection_url: String
+        };
+        let config = parse_config_attrs(&field.attrs).unwrap();
+        assert_eq!(config.aliases, vec!["db_url", "url"]);
+        assert_eq!(config.deprecated_since.as_deref(), Some("0.7"));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[config(deprecated_since = "0.7")]
+            connection_url: String
+        };
+        assert!(parse_config_attrs(&field.attrs).is_err());
+
+        let field: syn::Field = syn::parse_quote! {
+            #[config(rename = "x")]
+            connection_url: String
+        };
+        assert!(parse_config_attrs(&field.attrs).is_err());
+    }
+
+    #[test]
+    fn test_parse_serde_attrs() {
+        let field: syn::Field = syn::parse_quote! {
+            #[serde(rename = "max-conns", default = "default_conns", with = "humantime")]
+            max_connections: u32
+        };
+        let serde = parse_serde_attrs(&field.attrs).unwrap();
+        assert_eq!(serde.rename.as_deref(), Some("max-conns"));
+        assert!(serde.default);
+        assert!(!serde.skip);
+    }
+
+    #[test]
+    fn test_apply_rename_rule() {
+        assert_eq!(
+            apply_rename_rule("max_pool_size", "camelCase"),
+            "maxPoolSize"
+        );
+        assert_eq!(
+            apply_rename_rule("max_pool_size", "kebab-case"),
+            "max-pool-size"
+        );
+        assert_eq!(
+            apply_rename_rule("max_pool_size", "PascalCase"),
+            "MaxPoolSize"
+        );
+        assert_eq!(apply_rename_rule("host", "SCREAMING_SNAKE_CASE"), "HOST");
+    }
+
+    #[test]
+    fn test_apply_variant_rename_rule() {
+        assert_eq!(
+            apply_variant_rename_rule("ReadOnly", "snake_case"),
+            "read_only"
+        );
+        assert_eq!(
+            apply_variant_rename_rule("ReadOnly", "kebab-case"),
+            "read-only"
+        );
+        assert_eq!(
+            apply_variant_rename_rule("ReadOnly", "camelCase"),
+            "readOnly"
+        );
+        assert_eq!(
+            apply_variant_rename_rule("ReadOnly", "lowercase"),
+            "readonly"
+        );
+        assert_eq!(
+            apply_variant_rename_rule("ReadOnly", "SCREAMING_SNAKE_CASE"),
+            "READ_ONLY"
+        );
+    }
+
+    #[test]
+    fn test_parse_doc_comment() {
+        let field: syn::Field = syn::parse_quote! {
+            /// Database host.
+            /// Used for all connections.
+            ///
+            /// Second paragraph.
+            host: String
+        };
+        assert_eq!(
+            parse_doc_comment(&field.attrs).as_deref(),
+            Some("Database host. Used for all connections.\n\nSecond paragraph.")
+        );
+    }
+
     #[test]
     fn test_is_validator_name() {
         assert!(is_validator_name("non_empty"));
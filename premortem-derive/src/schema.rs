@@ -0,0 +1,301 @@
+//! ConfigSchema derive implementation.
+//!
+//! Generates a `ConfigSchema` impl that describes a struct as a JSON Schema,
+//! translating `#[validate(...)]` attributes into schema keywords. Enums of
+//! unit variants are described as a string `enum`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DataEnum, DeriveInput, Error, Fields, Result};
+
+use crate::parse::{
+    apply_rename_rule, apply_variant_rename_rule, is_secret_type, is_sensitive_attr,
+    is_validate_attr, parse_doc_comment, parse_serde_attrs, parse_validate_attr, ValidatorAttr,
+};
+
+/// Derive the `ConfigSchema` trait for a struct or an enum of unit variants.
+pub fn derive_config_schema(input: DeriveInput) -> Result<TokenStream> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(data) => return derive_enum_schema(&input, data),
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input,
+                "ConfigSchema can only be derived for structs and enums",
+            ));
+        }
+    };
+
+    let struct_serde = parse_serde_attrs(&input.attrs)?;
+    let fields = match &data.fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unnamed(_) => {
+            return Err(Error::new_spanned(
+                &input,
+                "ConfigSchema does not support tuple structs; use named fields",
+            ));
+        }
+        Fields::Unit => &syn::punctuated::Punctuated::new(),
+    };
+
+    let mut properties = Vec::new();
+    let mut field_infos = Vec::new();
+    for field in fields {
+        let serde = parse_serde_attrs(&field.attrs)?;
+        if serde.skip {
+            continue;
+        }
+
+        let ident = field
+            .ident
+            .as_ref()
+            .ok_or_else(|| Error::new_spanned(field, "field must have a name"))?;
+        let ty = &field.ty;
+        let name = match (&serde.rename, &struct_serde.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => apply_rename_rule(&ident.to_string(), rule),
+            (None, None) => ident.to_string(),
+        };
+
+        let mut constraints = Vec::new();
+        let mut validator_docs = Vec::new();
+        for attr in field.attrs.iter().filter(|a| is_validate_attr(a)) {
+            let (validators, _) = parse_validate_attr(attr)?;
+            constraints.extend(validators.iter().filter_map(constraint_tokens));
+            validator_docs.extend(validators.iter().map(|v| v.describe()));
+        }
+        let sensitive = field.attrs.iter().any(is_sensitive_attr) || is_secret_type(ty);
+        let description = option_tokens(parse_doc_comment(&field.attrs));
+        let has_default = serde.default || struct_serde.default;
+        let flatten = serde.flatten;
+        let rust_type = type_name(ty);
+
+        field_infos.push(quote! {
+            ::premortem::schema::FieldInfo {
+                name: #name,
+                rust_type: #rust_type,
+                description: ::std::option::Option::or_else(
+                    #description,
+                    <#ty as ::premortem::schema::ConfigSchema>::description,
+                ),
+                validators: &[#(#validator_docs),*],
+                required: !#has_default && !<#ty as ::premortem::schema::ConfigSchema>::is_optional(),
+                has_default: #has_default,
+                sensitive: #sensitive,
+                flatten: #flatten,
+                children: <#ty as ::premortem::schema::ConfigSchema>::fields(),
+            }
+        });
+
+        properties.push(quote! {
+            ::premortem::schema::Property {
+                name: #name,
+                schema: ::premortem::schema::field::<#ty>(
+                    #description,
+                    &[#(#constraints),*],
+                    #sensitive,
+                ),
+                required: !#has_default && !<#ty as ::premortem::schema::ConfigSchema>::is_optional(),
+                flatten: #flatten,
+            }
+        });
+    }
+
+    let struct_name = &input.ident;
+    let title = struct_name.to_string();
+    let description = option_tokens(parse_doc_comment(&input.attrs));
+    let deny_unknown_fields = struct_serde.deny_unknown_fields;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::premortem::schema::ConfigSchema for #struct_name #ty_generics #where_clause {
+            fn json_schema() -> ::premortem::schema::JsonValue {
+                ::premortem::schema::object(
+                    #title,
+                    #description,
+                    ::std::vec![#(#properties),*],
+                    #deny_unknown_fields,
+                )
+            }
+
+            fn fields() -> ::std::vec::Vec<::premortem::schema::FieldInfo> {
+                ::std::vec![#(#field_infos),*]
+            }
+
+            fn description() -> ::std::option::Option<&'static str> {
+                #description
+            }
+        }
+    })
+}
+
+/// Describe an enum of unit variants as the strings serde accepts for it.
+fn derive_enum_schema(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream> {
+    let enum_serde = parse_serde_attrs(&input.attrs)?;
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "ConfigSchema can only be derived for enums whose variants have no fields",
+            ));
+        }
+        let serde = parse_serde_attrs(&variant.attrs)?;
+        if serde.skip {
+            continue;
+        }
+        variants.push(match (&serde.rename, &enum_serde.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => apply_variant_rename_rule(&variant.ident.to_string(), rule),
+            (None, None) => variant.ident.to_string(),
+        });
+    }
+
+    let enum_name = &input.ident;
+    let title = enum_name.to_string();
+    let description = option_tokens(parse_doc_comment(&input.attrs));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::premortem::schema::ConfigSchema for #enum_name #ty_generics #where_clause {
+            fn json_schema() -> ::premortem::schema::JsonValue {
+                ::premortem::schema::string_enum(#title, #description, &[#(#variants),*])
+            }
+
+            fn description() -> ::std::option::Option<&'static str> {
+                #description
+            }
+        }
+    })
+}
+
+/// Render a type the way it is written in source (`Vec<String>`, not `Vec < String >`).
+fn type_name(ty: &syn::Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" <", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// Tokens for an `Option<&str>` literal.
+fn option_tokens(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// The schema constraint for a validator, if it has one.
+fn constraint_tokens(validator: &ValidatorAttr) -> Option<TokenStream> {
+    let constraint = match validator {
+        ValidatorAttr::NonEmpty => quote! { NonEmpty },
+        ValidatorAttr::MinLength(n) => quote! { MinLength(#n) },
+        ValidatorAttr::MaxLength(n) => quote! { MaxLength(#n) },
+        ValidatorAttr::Length(min, max) => quote! { Length(#min, #max) },
+        ValidatorAttr::Pattern(pattern) => quote! { Pattern(#pattern) },
+        ValidatorAttr::Email => quote! { Email },
+        ValidatorAttr::Url => quote! { Url },
+        ValidatorAttr::Ip => quote! { Ip },
+        ValidatorAttr::Uuid => quote! { Uuid },
+        ValidatorAttr::Range(min, max) => {
+            let min = numeric_literal(min);
+            let max = numeric_literal(max);
+            quote! { Range(#min, #max) }
+        }
+        ValidatorAttr::Positive => quote! { Positive },
+        ValidatorAttr::Negative => quote! { Negative },
+        ValidatorAttr::NonZero => quote! { NonZero },
+        ValidatorAttr::Extension(ext) => quote! { Extension(#ext) },
+        ValidatorAttr::Each(inner) => {
+            let inner = constraint_tokens(inner)?;
+            quote! { Each(&#inner) }
+        }
+        // No schema equivalent
+        ValidatorAttr::FileExists
+        | ValidatorAttr::DirExists
+        | ValidatorAttr::ParentExists
+        | ValidatorAttr::Nested
+        | ValidatorAttr::Skip
+        | ValidatorAttr::Custom(_)
+        | ValidatorAttr::When(_, _)
+        // Advisory rules don't constrain the schema
+        | ValidatorAttr::Warn(_) => return None,
+    };
+    Some(quote! { ::premortem::schema::Constraint::#constraint })
+}
+
+/// Strip digit separators and type suffixes (`1_000u32` -> `1000`), or the
+/// quotes around a unit bound (`"1s"` -> `1s`).
+fn numeric_literal(literal: &str) -> String {
+    if let Some(unquoted) = literal.strip_prefix('"') {
+        return unquoted.trim_end_matches('"').to_string();
+    }
+    let digits: String = literal.chars().filter(|c| *c != '_').collect();
+    match digits.find(['i', 'u', 'f']) {
+        Some(suffix) => digits[..suffix].to_string(),
+        None => digits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_config_schema_basic() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            struct Config {
+                /// Host to bind
+                #[validate(non_empty)]
+                host_name: String,
+                #[serde(skip)]
+                cache: Vec<u8>,
+            }
+        };
+
+        let tokens = derive_config_schema(input).unwrap().to_string();
+        assert!(tokens.contains("\"hostName\""));
+        assert!(tokens.contains("\"Host to bind\""));
+        assert!(!tokens.contains("cache"));
+    }
+
+    #[test]
+    fn test_derive_config_schema_unit_enum() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "kebab-case")]
+            enum Mode { ReadOnly, #[serde(rename = "rw")] ReadWrite }
+        };
+        let tokens = derive_config_schema(input).unwrap().to_string();
+        assert!(tokens.contains("string_enum"));
+        assert!(tokens.contains("\"read-only\" , \"rw\""));
+
+        let input: DeriveInput = syn::parse_quote! {
+            enum Mode { Fixed(u32) }
+        };
+        let err = derive_config_schema(input).unwrap_err();
+        assert!(err.to_string().contains("variants have no fields"));
+    }
+
+    #[test]
+    fn test_type_name() {
+        let ty: syn::Type = syn::parse_quote!(Option<std::collections::HashMap<String, Vec<u8>>>);
+        assert_eq!(
+            type_name(&ty),
+            "Option<std::collections::HashMap<String, Vec<u8>>>"
+        );
+    }
+
+    #[test]
+    fn test_numeric_literal() {
+        assert_eq!(numeric_literal("65_535u16"), "65535");
+        assert_eq!(numeric_literal("-1"), "-1");
+        assert_eq!(numeric_literal("0.5f64"), "0.5");
+    }
+}
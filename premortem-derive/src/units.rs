@@ -0,0 +1,176 @@
+//! Duration and size bounds for `#[validate(range("1s"..="5m"))]`.
+//!
+//! String range bounds are parsed while the macro expands, with the same
+//! grammar as `premortem::Duration` and `premortem::ByteSize`, so a bad bound
+//! is a compile error and the generated validator holds the parsed values.
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Duration units in nanoseconds.
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("d", 86_400 * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("min", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ns", 1),
+];
+
+/// Size unit prefixes in bytes, matched without case and without the trailing `b`.
+const SIZE_UNITS: &[(&str, u128)] = &[
+    ("", 1),
+    ("k", 1_000),
+    ("ki", 1 << 10),
+    ("m", 1_000_000),
+    ("mi", 1 << 20),
+    ("g", 1_000_000_000),
+    ("gi", 1 << 30),
+    ("t", 1_000_000_000_000),
+    ("ti", 1 << 40),
+    ("p", 1_000_000_000_000_000),
+    ("pi", 1 << 50),
+];
+
+/// The type a string range bound is read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitKind {
+    /// `premortem::Duration` or `std::time::Duration`
+    Duration,
+    /// `premortem::ByteSize`
+    Size,
+}
+
+impl UnitKind {
+    /// The kind a field of type `ty` holds, judging by its name.
+    pub fn of_type(ty: &syn::Type) -> Option<Self> {
+        let name = quote::quote!(#ty).to_string();
+        if name.contains("ByteSize") {
+            Some(UnitKind::Size)
+        } else if name.contains("Duration") {
+            Some(UnitKind::Duration)
+        } else {
+            None
+        }
+    }
+
+    /// Pure function: parse `s` as this kind, in nanoseconds or bytes.
+    pub fn parse(self, s: &str) -> Option<u128> {
+        let amount = match self {
+            UnitKind::Duration => parse_quantities(s, |unit| {
+                DURATION_UNITS
+                    .iter()
+                    .find(|(name, _)| *name == unit)
+                    .map(|(_, nanos)| *nanos)
+            })?,
+            UnitKind::Size => parse_quantities(s, |unit| {
+                let unit = unit.to_ascii_lowercase();
+                let unit = unit.strip_suffix('b').unwrap_or(&unit);
+                SIZE_UNITS
+                    .iter()
+                    .find(|(name, _)| *name == unit)
+                    .map(|(_, bytes)| *bytes)
+            })?,
+        };
+        let max = match self {
+            UnitKind::Duration => u128::from(u64::MAX) * NANOS_PER_SEC + (NANOS_PER_SEC - 1),
+            UnitKind::Size => u128::from(u64::MAX),
+        };
+        (amount <= max).then_some(amount)
+    }
+
+    /// The kinds both `min` and `max` parse as.
+    pub fn shared_by(min: &str, max: &str) -> Vec<Self> {
+        [UnitKind::Duration, UnitKind::Size]
+            .into_iter()
+            .filter(|kind| kind.parse(min).is_some() && kind.parse(max).is_some())
+            .collect()
+    }
+}
+
+/// Pure function: sum quantities like `1h30m` or `1.5 GiB`, scaling each by
+/// its unit with `scale`. A number without a unit is allowed only on its own.
+fn parse_quantities(s: &str, scale: impl Fn(&str) -> Option<u128>) -> Option<u128> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total: u128 = 0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        let tail = tail.trim_start();
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        if number.is_empty()
+            || (unit.is_empty() && (rest.len() != s.trim().len() || !tail.is_empty()))
+        {
+            return None;
+        }
+
+        let multiplier = scale(unit)?;
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let whole: u128 = match whole {
+            "" => 0,
+            digits => digits.parse().ok()?,
+        };
+        if number == "." || fraction.contains('.') || fraction.len() > 18 {
+            return None;
+        }
+        let fraction_scale = 10u128.pow(fraction.len() as u32);
+        let fraction: u128 = fraction.parse().unwrap_or(0);
+
+        total = whole
+            .checked_mul(multiplier)?
+            .checked_add(fraction * multiplier / fraction_scale)?
+            .checked_add(total)?;
+        rest = tail.trim_start();
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_bounds() {
+        assert_eq!(
+            UnitKind::Duration.parse("1h30m"),
+            Some(5_400 * NANOS_PER_SEC)
+        );
+        assert_eq!(UnitKind::Duration.parse("250ms"), Some(250_000_000));
+        assert_eq!(UnitKind::Duration.parse("1.5s"), Some(1_500_000_000));
+        assert_eq!(UnitKind::Duration.parse("10"), Some(10 * NANOS_PER_SEC));
+        assert_eq!(UnitKind::Duration.parse("5GiB"), None);
+        assert_eq!(UnitKind::Duration.parse("5 seconds"), None);
+    }
+
+    #[test]
+    fn test_parse_size_bounds() {
+        assert_eq!(UnitKind::Size.parse("4GiB"), Some(4 << 30));
+        assert_eq!(UnitKind::Size.parse("10gb"), Some(10_000_000_000));
+        assert_eq!(UnitKind::Size.parse("1.5KiB"), Some(1_536));
+        assert_eq!(UnitKind::Size.parse("1s"), None);
+        assert_eq!(UnitKind::Size.parse("100000PiB"), None);
+    }
+
+    #[test]
+    fn test_shared_kinds() {
+        assert_eq!(UnitKind::shared_by("1s", "5m"), vec![UnitKind::Duration]);
+        assert_eq!(UnitKind::shared_by("0", "4GiB"), vec![UnitKind::Size]);
+        assert_eq!(
+            UnitKind::shared_by("0", "5m"),
+            vec![UnitKind::Duration, UnitKind::Size]
+        );
+        assert!(UnitKind::shared_by("1s", "4GiB").is_empty());
+    }
+}
@@ -5,10 +5,11 @@
 use proc_macro2::TokenStream;
 use syn::{Data, DeriveInput, Error, Fields, Result};
 
-use crate::codegen::generate_validate_impl;
+use crate::codegen::{generate_aliases_fn, generate_validate_impl, FieldAliases};
 use crate::parse::{
-    is_sensitive_attr, is_validate_attr, parse_struct_validate_attr, parse_validate_attr,
-    FieldValidation, StructValidation,
+    apply_rename_rule, is_secret_type, is_sensitive_attr, is_validate_attr, parse_config_attrs,
+    parse_serde_attrs, parse_struct_validate_attr, parse_validate_attr, FieldValidation,
+    StructValidation,
 };
 use crate::validators::validate_validator_combination;
 
@@ -35,15 +36,19 @@ pub fn derive_validate(input: DeriveInput) -> Result<TokenStream> {
     let struct_validation = parse_struct_attrs(&input.attrs)?;
 
     // Parse field attributes
-    let fields = match &data.fields {
-        Fields::Named(fields) => parse_named_fields(fields)?,
+    let (fields, aliases) = match &data.fields {
+        Fields::Named(named) => {
+            let fields = parse_named_fields(named)?;
+            let aliases = parse_field_aliases(named, &input.attrs, &fields)?;
+            (fields, aliases)
+        }
         Fields::Unnamed(_) => {
             return Err(Error::new_spanned(
                 &input,
                 "Validate does not support tuple structs; use named fields",
             ));
         }
-        Fields::Unit => Vec::new(),
+        Fields::Unit => (Vec::new(), Vec::new()),
     };
 
     // Generate the impl
@@ -51,9 +56,45 @@ pub fn derive_validate(input: DeriveInput) -> Result<TokenStream> {
         &input.ident,
         &fields,
         &struct_validation,
+        generate_aliases_fn(&aliases),
     ))
 }
 
+/// Collect `#[config(alias = ...)]` aliases and nested fields, keyed by the
+/// name each field has in configuration files.
+fn parse_field_aliases(
+    named: &syn::FieldsNamed,
+    struct_attrs: &[syn::Attribute],
+    fields: &[(syn::Ident, syn::Type, FieldValidation)],
+) -> Result<Vec<FieldAliases>> {
+    let struct_serde = parse_serde_attrs(struct_attrs)?;
+    let mut result = Vec::new();
+
+    for (field, (ident, ty, validation)) in named.named.iter().zip(fields) {
+        let config = parse_config_attrs(&field.attrs)?;
+        let serde = parse_serde_attrs(&field.attrs)?;
+        let nested = validation.validators.iter().any(|v| v.is_nested());
+        if serde.skip || (config.aliases.is_empty() && !nested) {
+            continue;
+        }
+
+        let key = match (&serde.rename, &struct_serde.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => apply_rename_rule(&ident.to_string(), rule),
+            (None, None) => ident.to_string(),
+        };
+        result.push(FieldAliases {
+            key,
+            ty: ty.clone(),
+            config,
+            nested,
+            flatten: serde.flatten,
+        });
+    }
+
+    Ok(result)
+}
+
 /// Parse struct-level `#[validate(...)]` attributes.
 fn parse_struct_attrs(attrs: &[syn::Attribute]) -> Result<StructValidation> {
     let mut validation = StructValidation::default();
@@ -91,6 +132,12 @@ fn parse_named_fields(
             }
         }
 
+        // Secrets are never shown in errors
+        if is_secret_type(&field.ty) {
+            validation.sensitive = true;
+            validation.secret = true;
+        }
+
         // Parse #[validate(...)] attributes
         for attr in &field.attrs {
             if is_validate_attr(attr) {
@@ -146,6 +193,26 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_derive_validate_with_aliases() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            struct Config {
+                #[config(alias = "db_url", deprecated_since = "0.7")]
+                connection_url: String,
+                #[validate(nested)]
+                pool: PoolConfig,
+            }
+        };
+
+        let tokens = derive_validate(input).unwrap().to_string();
+        assert!(tokens.contains("fn aliases"));
+        assert!(tokens.contains("\"db_url\""));
+        assert!(tokens.contains("\"connectionUrl\""));
+        assert!(tokens.contains("\"0.7\""));
+        assert!(tokens.contains("with_prefix (\"pool\")"));
+    }
+
     #[test]
     fn test_derive_validate_enum_fails() {
         let input: DeriveInput = syn::parse_quote! {
@@ -41,6 +41,26 @@ impl ValidatorAttr {
             ValidatorAttr::Skip => "skip",
             ValidatorAttr::Custom(_) => "custom",
             ValidatorAttr::When(_, _) => "when",
+            ValidatorAttr::Warn(inner) => inner.name(),
+        }
+    }
+
+    /// Render this validator as it is written in `#[validate(...)]`, for docs.
+    pub fn describe(&self) -> String {
+        match self {
+            ValidatorAttr::MinLength(n) | ValidatorAttr::MaxLength(n) => {
+                format!("{}({})", self.name(), n)
+            }
+            ValidatorAttr::Length(min, max) => format!("length({}..={})", min, max),
+            ValidatorAttr::Pattern(s) | ValidatorAttr::Extension(s) => {
+                format!("{}({:?})", self.name(), s)
+            }
+            ValidatorAttr::Range(min, max) => format!("range({}..={})", min, max),
+            ValidatorAttr::Each(inner) => format!("each({})", inner.describe()),
+            ValidatorAttr::Custom(f) => format!("custom({})", f),
+            ValidatorAttr::When(cond, inner) => format!("{} when {}", inner.describe(), cond),
+            ValidatorAttr::Warn(inner) => format!("{} (warning)", inner.describe()),
+            _ => self.name().to_string(),
         }
     }
 }
@@ -0,0 +1,271 @@
+//! Renamed and deprecated configuration keys.
+//!
+//! A [`KeyAlias`] maps an old path onto its replacement. Each source's values
+//! are rewritten before they are merged, so existing config files keep working
+//! after a rename: the value moves to the new path with its original
+//! `SourceLocation`, and a [`ConfigWarning::DeprecatedKey`] names the file and
+//! line to update.
+//!
+//! Aliases come from [`ConfigBuilder::alias`](crate::ConfigBuilder::alias) or
+//! from `#[config(alias = "...")]` on a derived field:
+//!
+//! ```ignore
+//! #[derive(Deserialize, DeriveValidate)]
+//! struct DatabaseConfig {
+//!     #[config(alias = "url", deprecated_since = "0.7")]
+//!     connection_url: String,
+//! }
+//!
+//! let config = Config::<AppConfig>::builder()
+//!     .source(Toml::file("config.toml"))
+//!     .alias("db.url", "database.connection_url")
+//!     .build()?;
+//! ```
+//!
+//! Derived aliases are relative to the struct they are declared in, and
+//! `#[validate(nested)]` fields mount them below the field's path.
+//!
+//! A source that sets both the old and the new path is an error; across
+//! sources the usual precedence applies.
+
+use crate::error::{ConfigError, ConfigWarning};
+use crate::source::{in_subtree, ConfigValues};
+
+/// An old configuration path that is read as a new one.
+///
+/// Aliasing a table moves everything beneath it: `db` -> `database` turns
+/// `db.host` into `database.host`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAlias {
+    /// The path as written in older configuration files.
+    pub old: String,
+    /// The path the value is read from now.
+    pub new: String,
+    /// Version in which the old path was deprecated, if known.
+    pub deprecated_since: Option<String>,
+}
+
+impl KeyAlias {
+    /// Read `old` as `new`.
+    pub fn new(old: impl Into<String>, new: impl Into<String>) -> Self {
+        Self {
+            old: old.into(),
+            new: new.into(),
+            deprecated_since: None,
+        }
+    }
+
+    /// Record the version in which the old path was deprecated.
+    pub fn deprecated_since(mut self, version: impl Into<String>) -> Self {
+        self.deprecated_since = Some(version.into());
+        self
+    }
+
+    /// The same alias with both paths moved under `prefix`.
+    ///
+    /// Used to mount the aliases of a nested struct at its field.
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        if prefix.is_empty() {
+            return self;
+        }
+        Self {
+            old: format!("{}.{}", prefix, self.old),
+            new: format!("{}.{}", prefix, self.new),
+            deprecated_since: self.deprecated_since,
+        }
+    }
+
+    /// The new path for `path`, if it is the old path or lies beneath it.
+    fn rewrite(&self, path: &str) -> Option<String> {
+        in_subtree(path, &self.old).then(|| format!("{}{}", self.new, &path[self.old.len()..]))
+    }
+}
+
+/// Pure function: move the values of one source from old paths to new ones.
+///
+/// Returns the rewritten values with a deprecation warning per old key used,
+/// or an error for each old key whose replacement the same source also sets.
+pub(crate) fn apply_aliases(
+    mut values: ConfigValues,
+    aliases: &[KeyAlias],
+) -> Result<(ConfigValues, Vec<ConfigWarning>), Vec<ConfigError>> {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+
+    for alias in aliases {
+        if !values.paths().any(|path| in_subtree(path, &alias.old)) {
+            continue;
+        }
+
+        let mut rewritten = ConfigValues::empty();
+        for (path, config_value) in values.iter() {
+            let Some(new_path) = alias.rewrite(path) else {
+                rewritten.insert(path.clone(), config_value.clone());
+                continue;
+            };
+            if path.ends_with(".__len") {
+                rewritten.insert(new_path, config_value.clone());
+                continue;
+            }
+
+            // Only a value the source already sets at this key's new path,
+            // above it or beneath it collides; other keys of the table may coexist.
+            let existing = values.iter().find(|(other, _)| {
+                !other.ends_with(".__len")
+                    && !in_subtree(other, &alias.old)
+                    && (in_subtree(other, &new_path) || in_subtree(&new_path, other))
+            });
+
+            match existing {
+                Some((other, existing)) => errors.push(ConfigError::ValidationError {
+                    path: path.clone(),
+                    source_location: Some(config_value.source.clone()),
+                    value: None,
+                    message: format!(
+                        "both '{}' and its replacement '{}' ({}) are set; remove '{}'",
+                        path, other, existing.source, path
+                    ),
+                }),
+                None => warnings.push(ConfigWarning::DeprecatedKey {
+                    path: path.clone(),
+                    source_location: Some(config_value.source.clone()),
+                    replacement: Some(new_path.clone()),
+                    message: alias
+                        .deprecated_since
+                        .as_ref()
+                        .map(|version| format!("deprecated since {}", version)),
+                }),
+            }
+            rewritten.insert(new_path, config_value.clone());
+        }
+        values = rewritten;
+    }
+
+    if errors.is_empty() {
+        Ok((values, warnings))
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SourceLocation;
+    use crate::value::{ConfigValue, Value};
+
+    fn values(entries: &[(&str, &str, u32)]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, value, line) in entries {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(
+                    Value::String(value.to_string()),
+                    SourceLocation::new("config.toml").with_line(*line),
+                ),
+            );
+        }
+        values
+    }
+
+    #[test]
+    fn test_alias_moves_value_and_keeps_location() {
+        let aliases = [KeyAlias::new("db.url", "database.connection_url").deprecated_since("0.7")];
+        let (rewritten, warnings) =
+            apply_aliases(values(&[("db.url", "postgres://", 3)]), &aliases).unwrap();
+
+        assert!(!rewritten.contains("db.url"));
+        let moved = rewritten.get("database.connection_url").unwrap();
+        assert_eq!(moved.source.line, Some(3));
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "[config.toml:3] 'db.url' is deprecated; use 'database.connection_url' instead \
+             (deprecated since 0.7)"
+        );
+    }
+
+    #[test]
+    fn test_alias_moves_whole_table() {
+        let aliases = [KeyAlias::new("db", "database")];
+        let (rewritten, warnings) = apply_aliases(
+            values(&[
+                ("db.host", "a", 1),
+                ("db.hosts[0]", "b", 2),
+                ("dbx", "c", 3),
+            ]),
+            &aliases,
+        )
+        .unwrap();
+
+        assert!(rewritten.contains("database.host"));
+        assert!(rewritten.contains("database.hosts[0]"));
+        assert!(rewritten.contains("dbx"));
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_setting_both_names_is_an_error() {
+        let aliases = [KeyAlias::new("db.url", "database.url")];
+        let errors = apply_aliases(
+            values(&[("db.url", "a", 1), ("database.url", "b", 4)]),
+            &aliases,
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path(), Some("db.url"));
+        assert!(errors[0].to_string().contains("config.toml:4"));
+    }
+
+    #[test]
+    fn test_table_alias_merges_disjoint_keys() {
+        let aliases = [KeyAlias::new("db", "database")];
+        let (rewritten, warnings) = apply_aliases(
+            values(&[("db.host", "a", 1), ("database.port", "5432", 4)]),
+            &aliases,
+        )
+        .unwrap();
+
+        assert!(rewritten.contains("database.host"));
+        assert!(rewritten.contains("database.port"));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_table_alias_reports_only_colliding_keys() {
+        let aliases = [KeyAlias::new("db", "database")];
+        let errors = apply_aliases(
+            values(&[
+                ("db.host", "a", 1),
+                ("db.port", "1", 2),
+                ("database.port", "5432", 4),
+            ]),
+            &aliases,
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path(), Some("db.port"));
+        assert!(errors[0]
+            .to_string()
+            .contains("'database.port' (config.toml:4)"));
+    }
+
+    #[test]
+    fn test_unused_alias_leaves_values_alone() {
+        let aliases = [KeyAlias::new("db.url", "database.url")];
+        let (rewritten, warnings) =
+            apply_aliases(values(&[("database.url", "b", 1)]), &aliases).unwrap();
+        assert!(rewritten.contains("database.url"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let alias = KeyAlias::new("url", "connection_url").with_prefix("database");
+        assert_eq!(alias.old, "database.url");
+        assert_eq!(alias.new, "database.connection_url");
+    }
+}
@@ -0,0 +1,452 @@
+//! Loading several typed sections from one set of sources.
+//!
+//! A `ConfigBuilder` produces a single type. When independent parts of an
+//! application own their own configuration types, building each one separately
+//! reads and parses every file once per type. `ConfigSet` loads and merges the
+//! sources once, then deserializes and validates each section from its own
+//! subtree, reporting the errors of every section together.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use premortem::prelude::*;
+//!
+//! let set = ConfigSet::builder()
+//!     .source(Toml::file("config.toml"))
+//!     .source(Env::prefix("APP_"))
+//!     .section::<DatabaseConfig>("database")
+//!     .section::<HttpConfig>("http")
+//!     .section::<TelemetryConfig>("telemetry")
+//!     .build()?;
+//!
+//! let database: &DatabaseConfig = set.get().unwrap();
+//! let http = set.take::<HttpConfig>().unwrap();
+//! ```
+
+use std::any::Any;
+
+use serde::de::DeserializeOwned;
+
+use crate::alias::KeyAlias;
+use crate::config::{finish_config, report_warnings, BuildOptions, ConfigBuilder};
+use crate::env::{ConfigEnv, RealEnv};
+use crate::error::{ConfigError, ConfigErrors, ConfigWarning};
+use crate::redact::RedactionPolicy;
+use crate::source::{ConfigValues, MergeStrategy, Source};
+use crate::validate::Validate;
+
+/// A section's deserialized value, type-erased so sections of different types
+/// can live side by side.
+type SectionValue = Box<dyn Any + Send + Sync>;
+
+/// Deserializes and validates one section from the merged values.
+type SectionLoader = Box<
+    dyn Fn(
+            &ConfigValues,
+            &[String],
+            &BuildOptions,
+        ) -> Result<(SectionValue, Vec<ConfigWarning>), ConfigErrors>
+        + Send
+        + Sync,
+>;
+
+/// Several configuration sections loaded from one merged set of sources.
+///
+/// Created with [`ConfigSet::builder`]. Sections are looked up by type with
+/// [`get`](Self::get) or, when a type is registered more than once, by path
+/// with [`section`](Self::section).
+pub struct ConfigSet {
+    sections: Vec<(String, SectionValue)>,
+}
+
+impl ConfigSet {
+    /// Create a builder for a configuration set.
+    pub fn builder() -> ConfigSetBuilder {
+        ConfigSetBuilder::new()
+    }
+
+    /// The first section of type `T`.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.sections
+            .iter()
+            .find_map(|(_, value)| value.downcast_ref::<T>())
+    }
+
+    /// The section loaded from `path`, if it has type `T`.
+    pub fn section<T: 'static>(&self, path: &str) -> Option<&T> {
+        self.sections
+            .iter()
+            .filter(|(section_path, _)| section_path == path)
+            .find_map(|(_, value)| value.downcast_ref::<T>())
+    }
+
+    /// Remove the first section of type `T` and return it by value.
+    pub fn take<T: 'static>(&mut self) -> Option<T> {
+        let index = self
+            .sections
+            .iter()
+            .position(|(_, value)| value.is::<T>())?;
+        let (_, value) = self.sections.remove(index);
+        value.downcast::<T>().ok().map(|value| *value)
+    }
+
+    /// Paths of the sections still in the set, in registration order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|(path, _)| path.as_str())
+    }
+
+    /// Number of sections in the set.
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Check if the set has no sections.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+impl std::fmt::Debug for ConfigSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigSet")
+            .field("sections", &self.paths().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Builder for [`ConfigSet`].
+///
+/// Sources and build settings work exactly as on [`ConfigBuilder`]; they apply
+/// to the merged values that every section is read from.
+pub struct ConfigSetBuilder {
+    inner: ConfigBuilder<()>,
+    sections: Vec<(String, SectionLoader)>,
+    /// Aliases declared on the section types, mounted at their sections.
+    section_aliases: Vec<KeyAlias>,
+}
+
+impl Default for ConfigSetBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigSetBuilder {
+    /// Create a new builder with no sources and no sections.
+    pub fn new() -> Self {
+        Self {
+            inner: ConfigBuilder::new(),
+            sections: Vec::new(),
+            section_aliases: Vec::new(),
+        }
+    }
+
+    /// Add a configuration source.
+    ///
+    /// Sources are applied in order, later sources override earlier ones.
+    pub fn source<S: Source + 'static>(mut self, source: S) -> Self {
+        self.inner = self.inner.source(source);
+        self
+    }
+
+    /// Register a section of type `T` read from the subtree at `path`.
+    ///
+    /// An empty path reads `T` from the root of the merged values. Error paths
+    /// are reported in full (`database.port`), as with
+    /// [`ConfigBuilder::from_subtree`].
+    pub fn section<T>(mut self, path: impl Into<String>) -> Self
+    where
+        T: DeserializeOwned + Validate + Send + Sync + 'static,
+    {
+        let path = path.into().trim_matches('.').to_string();
+        let subtree = (!path.is_empty()).then(|| path.clone());
+        let loader: SectionLoader = Box::new(move |merged, source_names, options| {
+            let options = BuildOptions {
+                subtree: subtree.clone(),
+                ..options.clone()
+            };
+            finish_config::<T>(merged, source_names, &options)
+                .map(|(value, warnings)| (Box::new(value) as SectionValue, warnings))
+        });
+        self.section_aliases
+            .extend(T::aliases().into_iter().map(|a| a.with_prefix(&path)));
+        self.sections.push((path, loader));
+        self
+    }
+
+    /// Set how values under `path` are combined across sources.
+    ///
+    /// See [`ConfigBuilder::merge_strategy`].
+    pub fn merge_strategy(mut self, path: impl Into<String>, strategy: MergeStrategy) -> Self {
+        self.inner = self.inner.merge_strategy(path, strategy);
+        self
+    }
+
+    /// Select a profile. See [`ConfigBuilder::profile`].
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.inner = self.inner.profile(profile);
+        self
+    }
+
+    /// Read the profile from an environment variable. See
+    /// [`ConfigBuilder::profile_from_env`].
+    pub fn profile_from_env(mut self, var: impl Into<String>) -> Self {
+        self.inner = self.inner.profile_from_env(var);
+        self
+    }
+
+    /// Resolve `${...}` references after merging. See
+    /// [`ConfigBuilder::interpolate`].
+    pub fn interpolate(mut self) -> Self {
+        self.inner = self.inner.interpolate();
+        self
+    }
+
+    /// Reject keys that a section's type does not consume.
+    ///
+    /// Only keys inside registered sections are checked.
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.inner = self.inner.deny_unknown_fields();
+        self
+    }
+
+    /// Warn about keys that a section's type does not consume.
+    ///
+    /// Only keys inside registered sections are checked.
+    pub fn warn_unknown_fields(mut self) -> Self {
+        self.inner = self.inner.warn_unknown_fields();
+        self
+    }
+
+    /// Read the old path `old` as `new`. See [`ConfigBuilder::alias`].
+    pub fn alias(mut self, old: impl Into<String>, new: impl Into<String>) -> Self {
+        self.inner = self.inner.alias(old, new);
+        self
+    }
+
+    /// Fail the build if there are any warnings. See
+    /// [`ConfigBuilder::warnings_as_errors`].
+    pub fn warnings_as_errors(mut self) -> Self {
+        self.inner = self.inner.warnings_as_errors();
+        self
+    }
+
+    /// Print warnings to stderr. See [`ConfigBuilder::print_warnings`].
+    pub fn print_warnings(mut self) -> Self {
+        self.inner = self.inner.print_warnings();
+        self
+    }
+
+    /// Set which values are redacted from errors and warnings. See
+    /// [`ConfigBuilder::redaction_policy`].
+    pub fn redaction_policy(mut self, policy: RedactionPolicy) -> Self {
+        self.inner = self.inner.redaction_policy(policy);
+        self
+    }
+
+    /// Load every section using the real environment.
+    pub fn build(self) -> Result<ConfigSet, ConfigErrors> {
+        self.build_with_env(&RealEnv::new())
+    }
+
+    /// Load every section with a custom environment.
+    ///
+    /// Sources are loaded and merged once. Errors from all sections are
+    /// accumulated into a single `ConfigErrors`; warnings are printed to stderr
+    /// only with [`print_warnings`](Self::print_warnings).
+    pub fn build_with_env(self, env: &dyn ConfigEnv) -> Result<ConfigSet, ConfigErrors> {
+        let options = self.inner.options();
+        let mut aliases = options.aliases.clone();
+        aliases.extend(self.section_aliases.iter().cloned());
+
+        let mut warnings = Vec::new();
+        let merged = self.inner.merged_values(&aliases, env, &mut warnings)?;
+        let source_names = self.inner.source_names();
+
+        let mut sections = Vec::with_capacity(self.sections.len());
+        let mut all_errors: Vec<ConfigError> = Vec::new();
+        for (path, loader) in &self.sections {
+            match loader(&merged, &source_names, options) {
+                Ok((value, unknown)) => {
+                    sections.push((path.clone(), value));
+                    warnings.extend(unknown);
+                }
+                Err(errors) => all_errors.extend(errors),
+            }
+        }
+
+        if let Some(errors) = ConfigErrors::from_vec(all_errors) {
+            return Err(errors);
+        }
+        report_warnings(warnings, options)?;
+        Ok(ConfigSet { sections })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::error::ConfigValidation;
+    use crate::sources::Toml;
+    use stillwater::Validation;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct DatabaseConfig {
+        host: String,
+        port: u16,
+    }
+
+    impl Validate for DatabaseConfig {
+        fn validate(&self) -> ConfigValidation<()> {
+            Validation::Success(())
+        }
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct HttpConfig {
+        port: u16,
+    }
+
+    impl Validate for HttpConfig {
+        fn validate(&self) -> ConfigValidation<()> {
+            if self.port >= 1024 {
+                return Validation::Success(());
+            }
+            Validation::Failure(ConfigErrors::single(ConfigError::ValidationError {
+                path: "port".to_string(),
+                source_location: None,
+                value: Some(self.port.to_string()),
+                message: "port must be unprivileged".to_string(),
+            }))
+        }
+
+        fn aliases() -> Vec<KeyAlias> {
+            vec![KeyAlias::new("listen_port", "port")]
+        }
+    }
+
+    const CONFIG: &str = r#"
+[database]
+host = "db"
+port = 5432
+
+[http]
+port = 8080
+"#;
+
+    #[test]
+    fn test_builds_each_section_from_its_subtree() {
+        let env = MockEnv::new().with_file("config.toml", CONFIG);
+        let mut set = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<DatabaseConfig>("database")
+            .section::<HttpConfig>("http")
+            .build_with_env(&env)
+            .unwrap();
+
+        assert_eq!(set.len(), 2);
+        let database = set.get::<DatabaseConfig>().unwrap();
+        assert_eq!((database.host.as_str(), database.port), ("db", 5432));
+        assert_eq!(set.section::<HttpConfig>("http").unwrap().port, 8080);
+        assert!(set.section::<HttpConfig>("database").is_none());
+
+        let http = set.take::<HttpConfig>().unwrap();
+        assert_eq!(http.port, 8080);
+        assert!(set.get::<HttpConfig>().is_none());
+        assert_eq!(set.paths().collect::<Vec<_>>(), vec!["database"]);
+    }
+
+    #[test]
+    fn test_errors_accumulate_across_sections() {
+        let env = MockEnv::new().with_file(
+            "config.toml",
+            "[database]\nhost = \"db\"\nport = \"x\"\n\n[http]\nport = 80\n",
+        );
+
+        let errors = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<DatabaseConfig>("database")
+            .section::<HttpConfig>("http")
+            .build_with_env(&env)
+            .unwrap_err();
+
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["database.port", "http.port"]);
+    }
+
+    #[test]
+    fn test_section_over_array() {
+        let env = MockEnv::new().with_file(
+            "config.toml",
+            "[[replicas]]\nhost = \"a\"\nport = 1\n\n[[replicas]]\nhost = \"b\"\nport = \"x\"\n",
+        );
+
+        let errors = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<Vec<DatabaseConfig>>("replicas")
+            .build_with_env(&env)
+            .unwrap_err();
+        assert_eq!(errors.first().path(), Some("replicas[1].port"));
+
+        let env = MockEnv::new().with_file(
+            "config.toml",
+            "[[replicas]]\nhost = \"a\"\nport = 1\n\n[[replicas]]\nhost = \"b\"\nport = 2\n",
+        );
+        let set = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<Vec<DatabaseConfig>>("replicas")
+            .build_with_env(&env)
+            .unwrap();
+        let replicas = set.section::<Vec<DatabaseConfig>>("replicas").unwrap();
+        assert_eq!(replicas.len(), 2);
+        assert_eq!(replicas[1].host, "b");
+    }
+
+    #[test]
+    fn test_same_type_in_several_sections() {
+        let env = MockEnv::new().with_file(
+            "config.toml",
+            "[primary]\nhost = \"a\"\nport = 1\n\n[replica]\nhost = \"b\"\nport = 2\n",
+        );
+
+        let set = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<DatabaseConfig>("primary")
+            .section::<DatabaseConfig>("replica")
+            .build_with_env(&env)
+            .unwrap();
+
+        assert_eq!(set.section::<DatabaseConfig>("replica").unwrap().host, "b");
+        assert_eq!(set.get::<DatabaseConfig>().unwrap().host, "a");
+    }
+
+    #[test]
+    fn test_section_and_builder_aliases() {
+        let env = MockEnv::new().with_file(
+            "config.toml",
+            "[db]\nhost = \"db\"\nport = 1\n\n[http]\nlisten_port = 8080\n",
+        );
+
+        // Aliases declared on a section type are mounted at the section
+        let set = ConfigSet::builder()
+            .source(Toml::file("config.toml"))
+            .section::<HttpConfig>("http")
+            .section::<DatabaseConfig>("database")
+            .alias("db", "database")
+            .build_with_env(&env)
+            .unwrap();
+
+        assert_eq!(set.get::<HttpConfig>().unwrap().port, 8080);
+        assert_eq!(set.get::<DatabaseConfig>().unwrap().host, "db");
+    }
+
+    #[test]
+    fn test_no_sources() {
+        let errors = ConfigSet::builder()
+            .section::<HttpConfig>("http")
+            .build_with_env(&MockEnv::new())
+            .unwrap_err();
+        assert!(matches!(errors.first(), ConfigError::NoSources));
+    }
+}
@@ -0,0 +1,1886 @@
+//! Path-aware deserialization of `ConfigValues` into typed configuration.
+//!
+//! This module provides a `serde::Deserializer` that runs directly over the
+//! merged `ConfigValues`. Unlike going through `serde_json::Value`, it keeps
+//! track of the current path and the `SourceLocation` of every value, so each
+//! bad field is reported as its own `ConfigError` pointing at the file line or
+//! environment variable it came from.
+//!
+//! # Error Accumulation
+//!
+//! serde stops at the first error, so the deserializer works around it in two ways:
+//!
+//! - Type mismatches it can detect itself (e.g. `"abc"` for a `u16`) are recorded
+//!   and a placeholder value is handed to serde so deserialization continues.
+//! - Errors raised by serde itself (missing fields, out-of-range integers, unknown
+//!   enum variants) abort the pass. The failing path is recorded, patched with a
+//!   placeholder, and deserialization is retried until no new errors appear.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use premortem::de::from_config_values;
+//!
+//! let values = Toml::string("port = \"abc\"\ntimeout = -1").load(&env)?;
+//! let errors = from_config_values::<ServerConfig>(&values).unwrap_err();
+//! // Configuration errors (2):
+//! //   [<string>:1] 'port': expected u16, got "abc"
+//! //   [<string>:2] 'timeout': expected u32, got "-1": invalid value: ...
+//! ```
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::value::SeqDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::error::{ConfigError, ConfigErrors, SourceLocation};
+use crate::redact::{without_value, REDACTED};
+use crate::secret::{is_secret_path, SECRET_NAME};
+use crate::source::{array_of_len, in_subtree, is_metadata, parse_path, ConfigValues, PathSegment};
+use crate::value::Value;
+
+/// Deserialize `ConfigValues` into `T`, accumulating every error.
+///
+/// Each type error becomes a `ConfigError::ParseError` carrying the path and
+/// source location of the offending value; each missing field becomes a
+/// `ConfigError::MissingField`.
+pub fn from_config_values<T: DeserializeOwned>(values: &ConfigValues) -> Result<T, ConfigErrors> {
+    from_config_values_with(values, &[], UnknownFields::Ignore).map(|d| d.value)
+}
+
+/// How keys that no field of the target type consumes are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum UnknownFields {
+    /// Silently ignore them (serde's default behavior).
+    #[default]
+    Ignore,
+    /// Return them alongside the value.
+    Warn,
+    /// Report them as errors.
+    Deny,
+}
+
+/// A successfully deserialized value and what was learned along the way.
+#[derive(Debug)]
+pub(crate) struct Deserialized<T> {
+    pub(crate) value: T,
+    /// Unknown keys, in `UnknownFields::Warn` mode.
+    pub(crate) unknown: Vec<ConfigError>,
+    /// Paths that were deserialized into a [`Secret`](crate::Secret).
+    pub(crate) secrets: Vec<String>,
+}
+
+/// Deserialize `ConfigValues` into `T`, also checking for unknown keys.
+///
+/// `source_names` are listed in missing-field errors.
+/// Unknown keys are returned as `ConfigError::UnknownField` alongside the value
+/// in `UnknownFields::Warn` mode, and added to the errors in `Deny` mode. Keys
+/// are only checked against structs; maps and flattened structs accept any key.
+///
+/// Type errors at paths that hold a `Secret` leave out the offending value.
+pub(crate) fn from_config_values_with<T: DeserializeOwned>(
+    values: &ConfigValues,
+    source_names: &[String],
+    unknown_fields: UnknownFields,
+) -> Result<Deserialized<T>, ConfigErrors> {
+    deserialize_at(values, "", source_names, unknown_fields)
+}
+
+/// Deserialize the single node at `path` into `T`, accumulating every error.
+///
+/// Errors carry full paths. If nothing is stored at or under `path`, the node
+/// is treated as missing: `Option`s deserialize to `None`, and anything that
+/// can't be built from nothing is reported as `ConfigError::MissingField`.
+pub(crate) fn from_config_values_at<T: DeserializeOwned>(
+    values: &ConfigValues,
+    path: &str,
+) -> Result<T, ConfigErrors> {
+    let result = deserialize_at(values, path, &[], UnknownFields::Ignore).map(|d| d.value);
+    let missing = !values.paths().any(|p| in_subtree(p, path));
+    result.map_err(|errors| match missing {
+        true => ConfigErrors::single(ConfigError::MissingField {
+            path: path.to_string(),
+            source_location: None,
+            searched_sources: Vec::new(),
+        }),
+        false => errors,
+    })
+}
+
+fn deserialize_at<T: DeserializeOwned>(
+    values: &ConfigValues,
+    path: &str,
+    source_names: &[String],
+    unknown_fields: UnknownFields,
+) -> Result<Deserialized<T>, ConfigErrors> {
+    let tree = Node::from_config_values(values);
+    let root = tree
+        .get(&parse_path(path))
+        .cloned()
+        .unwrap_or_else(Node::null);
+    let mut holes: BTreeMap<String, Hole> = BTreeMap::new();
+    let mut aborted = Vec::new();
+    let mut secrets: Vec<String> = Vec::new();
+
+    loop {
+        let ctx = Context {
+            holes: &holes,
+            recorded: RefCell::new(Vec::new()),
+            unknown: RefCell::new(Vec::new()),
+            secrets: RefCell::new(Vec::new()),
+        };
+        let result = T::deserialize(NodeDeserializer::new(&root, path.to_string(), &ctx));
+        let mut recorded = ctx.recorded.into_inner();
+        let unknown = ctx.unknown.into_inner();
+        for path in ctx.secrets.into_inner() {
+            if !secrets.contains(&path) {
+                secrets.push(path);
+            }
+        }
+
+        let warnings = match unknown_fields {
+            UnknownFields::Ignore => Vec::new(),
+            UnknownFields::Warn => unknown,
+            UnknownFields::Deny => {
+                recorded.extend(unknown);
+                Vec::new()
+            }
+        };
+
+        let retry = match result {
+            Ok(value) => {
+                return match collect_errors(aborted, recorded, &secrets) {
+                    Some(errors) => Err(errors),
+                    None => Ok(Deserialized {
+                        value,
+                        unknown: warnings,
+                        secrets,
+                    }),
+                };
+            }
+            Err(error) => {
+                // Patch the failing path and retry, unless it is the root or was
+                // already patched (the placeholder itself failed to deserialize)
+                let retry = error.hole().filter(|(failed, _)| {
+                    failed != path && !holes.keys().any(|hole| in_subtree(failed, hole))
+                });
+                aborted.push(error.into_config_error(source_names));
+                retry
+            }
+        };
+
+        match retry {
+            Some((path, hole)) => {
+                holes.insert(path, hole);
+            }
+            None => {
+                return Err(
+                    collect_errors(aborted, recorded, &secrets).expect("aborted is not empty")
+                );
+            }
+        }
+    }
+}
+
+/// Combine errors from aborted passes with those recorded in the last pass, ordered by path.
+fn collect_errors(
+    aborted: Vec<ConfigError>,
+    recorded: Vec<ConfigError>,
+    secrets: &[String],
+) -> Option<ConfigErrors> {
+    let mut errors: Vec<ConfigError> = aborted
+        .into_iter()
+        .chain(recorded)
+        .map(|error| redact_secret(error, secrets))
+        .collect();
+    errors.sort_by(|a, b| a.path().cmp(&b.path()));
+    ConfigErrors::from_vec(errors)
+}
+
+/// Leave the value out of a type error at a secret path.
+fn redact_secret(error: ConfigError, secrets: &[String]) -> ConfigError {
+    match error {
+        ConfigError::ParseError {
+            path,
+            source_location,
+            expected_type,
+            actual_value,
+            message,
+        } if is_secret_path(&path, secrets) => ConfigError::ParseError {
+            message: without_value(message, &actual_value, &expected_type),
+            path,
+            source_location,
+            expected_type,
+            actual_value: REDACTED.to_string(),
+        },
+        other => other,
+    }
+}
+
+/// How a path that previously failed is patched on the next pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hole {
+    /// Feed a placeholder value instead of the real (or missing) one.
+    Fill,
+    /// Leave the key out of its table entirely.
+    Skip,
+}
+
+/// State shared by every deserializer in a single pass.
+struct Context<'a> {
+    holes: &'a BTreeMap<String, Hole>,
+    recorded: RefCell<Vec<ConfigError>>,
+    unknown: RefCell<Vec<ConfigError>>,
+    secrets: RefCell<Vec<String>>,
+}
+
+impl Context<'_> {
+    fn hole(&self, path: &str) -> Option<Hole> {
+        self.holes.get(path).copied()
+    }
+}
+
+/// A node in the configuration tree rebuilt from flattened paths.
+#[derive(Debug, Clone)]
+struct Node {
+    kind: NodeKind,
+    location: Option<SourceLocation>,
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    Scalar(Value),
+    Table(BTreeMap<String, Node>),
+    Array(Vec<Node>),
+}
+
+impl Node {
+    fn null() -> Self {
+        Self {
+            kind: NodeKind::Scalar(Value::Null),
+            location: None,
+        }
+    }
+
+    /// Convert a (possibly nested) value, giving every node the same location.
+    fn from_value(value: &Value, location: &SourceLocation) -> Self {
+        let kind = match value {
+            Value::Table(table) => NodeKind::Table(
+                table
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Node::from_value(v, location)))
+                    .collect(),
+            ),
+            Value::Array(items) => NodeKind::Array(
+                items
+                    .iter()
+                    .map(|v| Node::from_value(v, location))
+                    .collect(),
+            ),
+            scalar => NodeKind::Scalar(scalar.clone()),
+        };
+        Self {
+            kind,
+            location: Some(location.clone()),
+        }
+    }
+
+    /// Rebuild the tree from flattened paths, mirroring `ConfigValues::to_json`.
+    fn from_config_values(values: &ConfigValues) -> Self {
+        let mut root = Node {
+            kind: NodeKind::Table(BTreeMap::new()),
+            location: None,
+        };
+
+        for (path, config_value) in values.iter() {
+            if let Some(array_path) = array_of_len(path) {
+                // Arrays exist even when empty
+                let node = root.entry(&parse_path(array_path));
+                if !matches!(node.kind, NodeKind::Array(_)) {
+                    node.kind = NodeKind::Array(Vec::new());
+                    node.location = Some(config_value.source.clone());
+                }
+                continue;
+            }
+            // Skip other internal metadata keys
+            if is_metadata(path) {
+                continue;
+            }
+
+            root.entry(&parse_path(path))
+                .merge(Node::from_value(&config_value.value, &config_value.source));
+        }
+
+        root
+    }
+
+    /// Walk to the node at `segments`, creating tables and arrays on the way.
+    fn entry(&mut self, segments: &[PathSegment]) -> &mut Node {
+        let Some((first, rest)) = segments.split_first() else {
+            return self;
+        };
+
+        match first {
+            PathSegment::Key(key) => {
+                if !matches!(self.kind, NodeKind::Table(_)) {
+                    self.kind = NodeKind::Table(BTreeMap::new());
+                }
+                let NodeKind::Table(children) = &mut self.kind else {
+                    unreachable!("node was just made a table")
+                };
+                children
+                    .entry(key.clone())
+                    .or_insert_with(Node::null)
+                    .entry(rest)
+            }
+            PathSegment::Index(index) => {
+                if !matches!(self.kind, NodeKind::Array(_)) {
+                    self.kind = NodeKind::Array(Vec::new());
+                }
+                let NodeKind::Array(items) = &mut self.kind else {
+                    unreachable!("node was just made an array")
+                };
+                while items.len() <= *index {
+                    items.push(Node::null());
+                }
+                items[*index].entry(rest)
+            }
+        }
+    }
+
+    /// The node at `segments`, if there is one.
+    fn get(&self, segments: &[PathSegment]) -> Option<&Node> {
+        let Some((first, rest)) = segments.split_first() else {
+            return Some(self);
+        };
+
+        match (first, &self.kind) {
+            (PathSegment::Key(key), NodeKind::Table(children)) => children.get(key)?.get(rest),
+            (PathSegment::Index(index), NodeKind::Array(items)) => items.get(*index)?.get(rest),
+            _ => None,
+        }
+    }
+
+    /// Merge another node into this one; tables merge key by key, anything else replaces.
+    fn merge(&mut self, other: Node) {
+        match (&mut self.kind, other.kind) {
+            (NodeKind::Table(existing), NodeKind::Table(incoming)) => {
+                for (key, node) in incoming {
+                    existing.entry(key).or_insert_with(Node::null).merge(node);
+                }
+                if self.location.is_none() {
+                    self.location = other.location;
+                }
+            }
+            (_, kind) => {
+                self.kind = kind;
+                self.location = other.location;
+            }
+        }
+    }
+
+    /// Collect the relative path and location of every leaf under this node.
+    ///
+    /// Leaves are scalars, inline values, and empty tables or arrays.
+    fn leaves(&self, path: String, out: &mut Vec<(String, Option<SourceLocation>)>) {
+        match &self.kind {
+            NodeKind::Table(children) if !children.is_empty() => {
+                for (key, child) in children {
+                    child.leaves(format!("{}.{}", path, key), out);
+                }
+            }
+            NodeKind::Array(items) if !items.is_empty() => {
+                for (index, item) in items.iter().enumerate() {
+                    item.leaves(format!("{}[{}]", path, index), out);
+                }
+            }
+            _ => out.push((path, self.location.clone())),
+        }
+    }
+
+    fn scalar(&self) -> Option<&Value> {
+        match &self.kind {
+            NodeKind::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The string stored here, if it may be read as a number or boolean.
+    ///
+    /// Only environment variables are untyped text; a string in a file such
+    /// as `port = "8080"` is a type error for a numeric field.
+    fn text(&self) -> Option<&str> {
+        match self.scalar() {
+            Some(Value::String(s)) if self.location.as_ref().is_some_and(|l| l.is_env()) => {
+                Some(s.trim())
+            }
+            _ => None,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match &self.kind {
+            NodeKind::Scalar(value) => value.type_name(),
+            NodeKind::Table(_) => "table",
+            NodeKind::Array(_) => "array",
+        }
+    }
+
+    /// Render the value for error messages.
+    fn describe(&self) -> String {
+        match &self.kind {
+            NodeKind::Scalar(Value::String(s)) => s.clone(),
+            NodeKind::Scalar(Value::Integer(i)) => i.to_string(),
+            NodeKind::Scalar(Value::UInteger(u)) => u.to_string(),
+            NodeKind::Scalar(Value::Float(f)) => f.to_string(),
+            NodeKind::Scalar(Value::Bool(b)) => b.to_string(),
+            NodeKind::Scalar(Value::Datetime(dt)) => dt.to_string(),
+            NodeKind::Scalar(Value::Bytes(bytes)) => format!("({} bytes)", bytes.len()),
+            NodeKind::Scalar(_) => "null".to_string(),
+            NodeKind::Table(_) => "(table)".to_string(),
+            NodeKind::Array(_) => "(array)".to_string(),
+        }
+    }
+}
+
+/// Join a table key onto a parent path.
+fn key_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Path used in errors for the root of the tree.
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        "(root)".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+fn merged_location() -> SourceLocation {
+    SourceLocation::new("merged config")
+}
+
+/// Suggest the candidate closest to `name`, if it is close enough to be a typo.
+fn suggest<'f>(name: &str, candidates: &[&'f str]) -> Option<&'f str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Edit distance between two strings, counting an adjacent transposition as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        d[0][j] = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Error raised through serde while deserializing a node.
+#[derive(Debug)]
+struct DeError {
+    kind: DeErrorKind,
+    path: Option<String>,
+    location: Option<SourceLocation>,
+    actual: Option<String>,
+}
+
+#[derive(Debug)]
+enum DeErrorKind {
+    MissingField(String),
+    UnknownField {
+        field: String,
+        did_you_mean: Option<String>,
+    },
+    Invalid {
+        expected: String,
+        message: String,
+    },
+}
+
+impl DeError {
+    fn new(kind: DeErrorKind) -> Self {
+        Self {
+            kind,
+            path: None,
+            location: None,
+            actual: None,
+        }
+    }
+
+    fn invalid(expected: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(DeErrorKind::Invalid {
+            expected: expected.into(),
+            message: message.into(),
+        })
+    }
+
+    /// Attach the path of the node that raised this error, if none is set yet.
+    ///
+    /// Errors bubble up through every enclosing node, so only the innermost
+    /// node gets to set the path.
+    fn at(mut self, path: &str, node: Option<&Node>) -> Self {
+        if self.path.is_some() {
+            return self;
+        }
+
+        match &mut self.kind {
+            DeErrorKind::MissingField(field) => {
+                self.path = Some(key_path(path, field));
+            }
+            DeErrorKind::UnknownField {
+                field,
+                did_you_mean,
+            } => {
+                self.location = node
+                    .and_then(|n| match &n.kind {
+                        NodeKind::Table(children) => children.get(field.as_str()),
+                        _ => None,
+                    })
+                    .and_then(|child| child.location.clone());
+                self.path = Some(key_path(path, field));
+                *did_you_mean = did_you_mean.as_deref().map(|s| key_path(path, s));
+            }
+            DeErrorKind::Invalid { .. } => {
+                self.path = Some(path.to_string());
+                self.location = node.and_then(|n| n.location.clone());
+                self.actual = node.map(Node::describe);
+            }
+        }
+        self
+    }
+
+    /// The path to patch on the next pass, and how.
+    fn hole(&self) -> Option<(String, Hole)> {
+        let hole = match self.kind {
+            DeErrorKind::UnknownField { .. } => Hole::Skip,
+            _ => Hole::Fill,
+        };
+        self.path.clone().map(|path| (path, hole))
+    }
+
+    fn into_config_error(self, source_names: &[String]) -> ConfigError {
+        let path = display_path(self.path.as_deref().unwrap_or(""));
+        match self.kind {
+            DeErrorKind::MissingField(_) => ConfigError::MissingField {
+                path,
+                source_location: None,
+                searched_sources: source_names.to_vec(),
+            },
+            DeErrorKind::UnknownField { did_you_mean, .. } => ConfigError::UnknownField {
+                path,
+                source_location: self.location.unwrap_or_else(merged_location),
+                did_you_mean,
+            },
+            DeErrorKind::Invalid { expected, message } => ConfigError::ParseError {
+                path,
+                source_location: self.location.unwrap_or_else(merged_location),
+                expected_type: expected,
+                actual_value: self.actual.unwrap_or_else(|| "(complex)".to_string()),
+                message,
+            },
+        }
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DeErrorKind::MissingField(field) => write!(f, "missing field `{}`", field),
+            DeErrorKind::UnknownField { field, .. } => write!(f, "unknown field `{}`", field),
+            DeErrorKind::Invalid { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::invalid("valid value", msg.to_string())
+    }
+
+    fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        Self::invalid(
+            exp.to_string(),
+            format!("invalid type: {}, expected {}", unexp, exp),
+        )
+    }
+
+    fn invalid_value(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        Self::invalid(
+            exp.to_string(),
+            format!("invalid value: {}, expected {}", unexp, exp),
+        )
+    }
+
+    fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        Self::invalid(
+            exp.to_string(),
+            format!("invalid length {}, expected {}", len, exp),
+        )
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        let variants = expected
+            .iter()
+            .map(|v| format!("`{}`", v))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self::invalid(
+            format!("one of {}", variants),
+            format!(
+                "unknown variant `{}`, expected one of {}",
+                variant, variants
+            ),
+        )
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        Self::new(DeErrorKind::UnknownField {
+            field: field.to_string(),
+            did_you_mean: suggest(field, expected).map(str::to_string),
+        })
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::new(DeErrorKind::MissingField(field.to_string()))
+    }
+}
+
+// ============================================================================
+// Node deserializer
+// ============================================================================
+
+/// Deserializer for a single node of the configuration tree.
+struct NodeDeserializer<'a> {
+    node: &'a Node,
+    path: String,
+    ctx: &'a Context<'a>,
+}
+
+impl<'a> NodeDeserializer<'a> {
+    fn new(node: &'a Node, path: String, ctx: &'a Context<'a>) -> Self {
+        Self { node, path, ctx }
+    }
+
+    /// Record a type mismatch at this node without aborting deserialization.
+    fn mismatch(&self, expected: &str, detail: Option<String>) {
+        let message = detail.unwrap_or_else(|| {
+            format!(
+                "invalid type: {}, expected {}",
+                self.node.type_name(),
+                expected
+            )
+        });
+        self.ctx
+            .recorded
+            .borrow_mut()
+            .push(ConfigError::ParseError {
+                path: display_path(&self.path),
+                source_location: self.node.location.clone().unwrap_or_else(merged_location),
+                expected_type: expected.to_string(),
+                actual_value: self.node.describe(),
+                message,
+            });
+    }
+
+    fn placeholder(&self) -> Placeholder {
+        Placeholder {
+            path: self.path.clone(),
+        }
+    }
+
+    fn deserialize_integer<'de, V: Visitor<'de>>(
+        self,
+        expected: &str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        if let Some(text) = self.node.text() {
+            return match text.parse::<i64>() {
+                Ok(i) => visitor.visit_i64(i),
+                Err(e) => match text.parse::<u64>() {
+                    Ok(u) => visitor.visit_u64(u),
+                    Err(_) => {
+                        self.mismatch(expected, Some(e.to_string()));
+                        self.placeholder().deserialize_i64(visitor)
+                    }
+                },
+            };
+        }
+        match self.node.scalar() {
+            Some(Value::Integer(i)) => visitor.visit_i64(*i),
+            Some(Value::UInteger(u)) => visitor.visit_u64(*u),
+            _ => {
+                self.mismatch(expected, None);
+                self.placeholder().deserialize_i64(visitor)
+            }
+        }
+    }
+
+    fn deserialize_float<'de, V: Visitor<'de>>(
+        self,
+        expected: &str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        if let Some(text) = self.node.text() {
+            return match text.parse::<f64>() {
+                Ok(f) => visitor.visit_f64(f),
+                Err(e) => {
+                    self.mismatch(expected, Some(e.to_string()));
+                    self.placeholder().deserialize_f64(visitor)
+                }
+            };
+        }
+        match self.node.scalar() {
+            Some(Value::Float(f)) => visitor.visit_f64(*f),
+            Some(Value::Integer(i)) => visitor.visit_f64(*i as f64),
+            Some(Value::UInteger(u)) => visitor.visit_f64(*u as f64),
+            _ => {
+                self.mismatch(expected, None);
+                self.placeholder().deserialize_f64(visitor)
+            }
+        }
+    }
+
+    /// Record every leaf under keys that are not fields of the struct being deserialized.
+    fn record_unknown(&self, entries: &[(String, Option<&'a Node>)], fields: &[&str]) {
+        let mut unknown = self.ctx.unknown.borrow_mut();
+        for (key, node) in entries {
+            if fields.contains(&key.as_str()) {
+                continue;
+            }
+            let Some(node) = node else { continue };
+
+            let suggestion = suggest(key, fields);
+            let mut leaves = Vec::new();
+            node.leaves(String::new(), &mut leaves);
+            for (rest, location) in leaves {
+                unknown.push(ConfigError::UnknownField {
+                    path: format!("{}{}", key_path(&self.path, key), rest),
+                    source_location: location.unwrap_or_else(merged_location),
+                    did_you_mean: suggestion
+                        .map(|field| format!("{}{}", key_path(&self.path, field), rest)),
+                });
+            }
+        }
+    }
+
+    /// Table access over this node's children, filling in patched fields.
+    ///
+    /// `fields` is `Some` when deserializing a struct; keys it does not list
+    /// are recorded as unknown.
+    fn table_access(
+        &self,
+        children: &'a BTreeMap<String, Node>,
+        fields: Option<&'static [&'static str]>,
+    ) -> TableAccess<'a> {
+        let mut entries: Vec<(String, Option<&'a Node>)> = children
+            .iter()
+            .filter(|(key, _)| self.ctx.hole(&key_path(&self.path, key)) != Some(Hole::Skip))
+            .map(|(key, node)| (key.clone(), Some(node)))
+            .collect();
+
+        if let Some(fields) = fields {
+            self.record_unknown(&entries, fields);
+        }
+        let fields = fields.unwrap_or_default();
+
+        // Fields that were missing on an earlier pass get a placeholder
+        for field in fields {
+            if !children.contains_key(*field)
+                && self.ctx.hole(&key_path(&self.path, field)) == Some(Hole::Fill)
+            {
+                entries.push((field.to_string(), None));
+            }
+        }
+
+        TableAccess {
+            entries: entries.into_iter(),
+            pending: None,
+            path: self.path.clone(),
+            ctx: self.ctx,
+        }
+    }
+}
+
+/// Deserialize a child node, or a placeholder if its path was patched.
+fn deserialize_child<'de, 'a, S: DeserializeSeed<'de>>(
+    seed: S,
+    node: Option<&'a Node>,
+    path: String,
+    ctx: &'a Context<'a>,
+) -> Result<S::Value, DeError> {
+    match node {
+        Some(node) if ctx.hole(&path) != Some(Hole::Fill) => {
+            seed.deserialize(NodeDeserializer::new(node, path, ctx))
+        }
+        _ => seed.deserialize(Placeholder { path }),
+    }
+}
+
+/// Forward a deserialize method and attach this node's path to any error.
+macro_rules! forward_with_path {
+    ($self:ident, $body:expr) => {{
+        let path = $self.path.clone();
+        let node = $self.node;
+        $body.map_err(|e: DeError| e.at(&path, Some(node)))
+    }};
+}
+
+macro_rules! deserialize_integers {
+    ($($method:ident => $expected:literal),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                forward_with_path!(self, self.deserialize_integer($expected, visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for NodeDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Scalar(Value::Null) => visitor.visit_unit(),
+                NodeKind::Scalar(Value::Bool(b)) => visitor.visit_bool(*b),
+                NodeKind::Scalar(Value::Integer(i)) => visitor.visit_i64(*i),
+                NodeKind::Scalar(Value::UInteger(u)) => visitor.visit_u64(*u),
+                NodeKind::Scalar(Value::Float(f)) => visitor.visit_f64(*f),
+                NodeKind::Scalar(Value::String(s)) => visitor.visit_string(s.clone()),
+                NodeKind::Scalar(Value::Datetime(dt)) => visitor.visit_string(dt.to_string()),
+                NodeKind::Scalar(Value::Bytes(bytes)) => visitor.visit_byte_buf(bytes.clone()),
+                NodeKind::Scalar(_) => unreachable!("nested values are expanded into nodes"),
+                NodeKind::Table(children) => visitor.visit_map(self.table_access(children, None)),
+                NodeKind::Array(items) => visitor.visit_seq(ArrayAccess::new(items, &self)),
+            }
+        )
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match (self.node.scalar(), self.node.text()) {
+                (Some(Value::Bool(b)), _) => visitor.visit_bool(*b),
+                (_, Some(text)) => match text.to_lowercase().as_str() {
+                    "true" | "yes" | "on" => visitor.visit_bool(true),
+                    "false" | "no" | "off" => visitor.visit_bool(false),
+                    _ => {
+                        self.mismatch("boolean", Some(format!("invalid boolean: {}", text)));
+                        self.placeholder().deserialize_bool(visitor)
+                    }
+                },
+                _ => {
+                    self.mismatch("boolean", None);
+                    self.placeholder().deserialize_bool(visitor)
+                }
+            }
+        )
+    }
+
+    deserialize_integers! {
+        deserialize_i8 => "i8",
+        deserialize_i16 => "i16",
+        deserialize_i32 => "i32",
+        deserialize_i64 => "i64",
+        deserialize_u8 => "u8",
+        deserialize_u16 => "u16",
+        deserialize_u32 => "u32",
+        deserialize_u64 => "u64",
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(self, self.deserialize_float("f32", visitor))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(self, self.deserialize_float("f64", visitor))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match self.node.scalar() {
+                Some(Value::String(s)) => visitor.visit_string(s.clone()),
+                // Scalars are accepted as strings (e.g. APP_NAME=123), and
+                // datetimes as RFC 3339 for `chrono` and `time`
+                Some(
+                    Value::Integer(_)
+                    | Value::UInteger(_)
+                    | Value::Float(_)
+                    | Value::Bool(_)
+                    | Value::Datetime(_),
+                ) => visitor.visit_string(self.node.describe()),
+                _ => {
+                    self.mismatch("string", None);
+                    self.placeholder().deserialize_string(visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Scalar(Value::String(s)) => visitor.visit_bytes(s.as_bytes()),
+                NodeKind::Scalar(Value::Bytes(bytes)) => visitor.visit_bytes(bytes),
+                NodeKind::Array(items) => visitor.visit_seq(ArrayAccess::new(items, &self)),
+                _ => {
+                    self.mismatch("bytes", None);
+                    self.placeholder().deserialize_bytes(visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.node.scalar() {
+            Some(Value::Null) => forward_with_path!(self, visitor.visit_none()),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match self.node.scalar() {
+                Some(Value::Null) => visitor.visit_unit(),
+                _ => {
+                    self.mismatch("unit", None);
+                    self.placeholder().deserialize_unit(visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        if name == SECRET_NAME {
+            self.ctx.secrets.borrow_mut().push(self.path.clone());
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Array(items) => visitor.visit_seq(ArrayAccess::new(items, &self)),
+                // `Vec<u8>` and `[u8; N]` read byte strings as sequences
+                NodeKind::Scalar(Value::Bytes(bytes)) => {
+                    visitor.visit_seq(SeqDeserializer::new(bytes.iter().copied()))
+                }
+                _ => {
+                    self.mismatch("array", None);
+                    self.placeholder().deserialize_seq(visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Array(items) => visitor.visit_seq(ArrayAccess::new(items, &self)),
+                // `Vec<u8>` and `[u8; N]` read byte strings as sequences
+                NodeKind::Scalar(Value::Bytes(bytes)) => {
+                    visitor.visit_seq(SeqDeserializer::new(bytes.iter().copied()))
+                }
+                _ => {
+                    self.mismatch("array", None);
+                    self.placeholder().deserialize_tuple(len, visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Table(children) => visitor.visit_map(self.table_access(children, None)),
+                _ => {
+                    self.mismatch("table", None);
+                    self.placeholder().deserialize_map(visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Table(children) => {
+                    visitor.visit_map(self.table_access(children, Some(fields)))
+                }
+                // A missing struct (e.g. no `[database]` table) reports its fields as missing
+                NodeKind::Scalar(Value::Null) if self.node.location.is_none() => {
+                    visitor.visit_map(self.table_access(&EMPTY_TABLE, Some(fields)))
+                }
+                _ => {
+                    self.mismatch("table", None);
+                    self.placeholder().deserialize_struct(name, fields, visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        forward_with_path!(
+            self,
+            match &self.node.kind {
+                NodeKind::Scalar(Value::String(s)) => {
+                    visitor.visit_enum(s.clone().into_deserializer())
+                }
+                NodeKind::Table(children) if children.len() == 1 => {
+                    let (key, node) = children.iter().next().expect("table has one entry");
+                    visitor.visit_enum(TableVariant {
+                        key: key.clone(),
+                        node,
+                        path: key_path(&self.path, key),
+                        ctx: self.ctx,
+                    })
+                }
+                _ => {
+                    self.mismatch("enum variant", None);
+                    self.placeholder().deserialize_enum(name, variants, visitor)
+                }
+            }
+        )
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Shared empty table for structs whose table is absent.
+static EMPTY_TABLE: BTreeMap<String, Node> = BTreeMap::new();
+
+/// Map access over a table node.
+struct TableAccess<'a> {
+    entries: std::vec::IntoIter<(String, Option<&'a Node>)>,
+    pending: Option<(String, Option<&'a Node>)>,
+    path: String,
+    ctx: &'a Context<'a>,
+}
+
+impl<'de, 'a> MapAccess<'de> for TableAccess<'a> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        match self.entries.next() {
+            Some((key, node)) => {
+                let result = seed.deserialize(key.clone().into_deserializer());
+                self.pending = Some((key, node));
+                result.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let (key, node) = self
+            .pending
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        deserialize_child(seed, node, key_path(&self.path, &key), self.ctx)
+    }
+}
+
+/// Sequence access over an array node.
+struct ArrayAccess<'a> {
+    items: std::iter::Enumerate<std::slice::Iter<'a, Node>>,
+    path: String,
+    ctx: &'a Context<'a>,
+}
+
+impl<'a> ArrayAccess<'a> {
+    fn new(items: &'a [Node], parent: &NodeDeserializer<'a>) -> Self {
+        Self {
+            items: items.iter().enumerate(),
+            path: parent.path.clone(),
+            ctx: parent.ctx,
+        }
+    }
+}
+
+impl<'de, 'a> SeqAccess<'de> for ArrayAccess<'a> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        match self.items.next() {
+            Some((index, node)) => {
+                let path = format!("{}[{}]", self.path, index);
+                deserialize_child(seed, Some(node), path, self.ctx).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Enum access for an externally tagged variant stored as a single-key table.
+struct TableVariant<'a> {
+    key: String,
+    node: &'a Node,
+    path: String,
+    ctx: &'a Context<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for TableVariant<'a> {
+    type Error = DeError;
+    type Variant = NodeDeserializer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), DeError> {
+        let variant = seed.deserialize(self.key.into_deserializer())?;
+        Ok((
+            variant,
+            NodeDeserializer::new(self.node, self.path, self.ctx),
+        ))
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for NodeDeserializer<'a> {
+    type Error = DeError;
+
+    fn unit_variant(self) -> Result<(), DeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, DeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, DeError> {
+        Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+// ============================================================================
+// Placeholder deserializer
+// ============================================================================
+
+/// Deserializer that produces an "empty" value of whatever type is requested.
+///
+/// Used in place of values that failed to deserialize, so the rest of the
+/// configuration can still be checked in the same pass.
+struct Placeholder {
+    path: String,
+}
+
+impl Placeholder {
+    fn child(&self) -> Placeholder {
+        Placeholder {
+            path: self.path.clone(),
+        }
+    }
+}
+
+macro_rules! placeholder_forward {
+    ($($method:ident => $visit:ident($($value:expr)?)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                let path = self.path;
+                let result: Result<V::Value, DeError> = visitor.$visit($($value)?);
+                result.map_err(|e| e.at(&path, None))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Placeholder {
+    type Error = DeError;
+
+    placeholder_forward! {
+        deserialize_any => visit_unit(),
+        deserialize_bool => visit_bool(false),
+        deserialize_i8 => visit_i64(0),
+        deserialize_i16 => visit_i64(0),
+        deserialize_i32 => visit_i64(0),
+        deserialize_i64 => visit_i64(0),
+        deserialize_u8 => visit_u64(0),
+        deserialize_u16 => visit_u64(0),
+        deserialize_u32 => visit_u64(0),
+        deserialize_u64 => visit_u64(0),
+        deserialize_f32 => visit_f64(0.0),
+        deserialize_f64 => visit_f64(0.0),
+        deserialize_char => visit_char('\0'),
+        deserialize_str => visit_str(""),
+        deserialize_string => visit_str(""),
+        deserialize_bytes => visit_bytes(&[]),
+        deserialize_byte_buf => visit_bytes(&[]),
+        deserialize_option => visit_none(),
+        deserialize_unit => visit_unit(),
+        deserialize_identifier => visit_str(""),
+        deserialize_ignored_any => visit_unit(),
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_tuple(0, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let path = self.path.clone();
+        visitor
+            .visit_seq(PlaceholderSeq {
+                remaining: len,
+                placeholder: self,
+            })
+            .map_err(|e| e.at(&path, None))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_struct("", &[], visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let path = self.path.clone();
+        visitor
+            .visit_map(PlaceholderMap {
+                fields: fields.iter(),
+                placeholder: self,
+            })
+            .map_err(|e| e.at(&path, None))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let path = self.path.clone();
+        match variants.first() {
+            Some(variant) => visitor.visit_enum(PlaceholderVariant {
+                variant,
+                placeholder: self,
+            }),
+            None => Err(de::Error::custom("enum has no variants")),
+        }
+        .map_err(|e| e.at(&path, None))
+    }
+}
+
+struct PlaceholderSeq {
+    remaining: usize,
+    placeholder: Placeholder,
+}
+
+impl<'de> SeqAccess<'de> for PlaceholderSeq {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(self.placeholder.child()).map(Some)
+    }
+}
+
+struct PlaceholderMap {
+    fields: std::slice::Iter<'static, &'static str>,
+    placeholder: Placeholder,
+}
+
+impl<'de> MapAccess<'de> for PlaceholderMap {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        match self.fields.next() {
+            Some(field) => seed.deserialize(field.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        seed.deserialize(self.placeholder.child())
+    }
+}
+
+struct PlaceholderVariant {
+    variant: &'static str,
+    placeholder: Placeholder,
+}
+
+impl<'de> EnumAccess<'de> for PlaceholderVariant {
+    type Error = DeError;
+    type Variant = Placeholder;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), DeError> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.placeholder))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Placeholder {
+    type Error = DeError;
+
+    fn unit_variant(self) -> Result<(), DeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, DeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, DeError> {
+        Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::source::{merge_config_values, Source};
+    use crate::sources::{Env, Toml};
+    use crate::value::ConfigValue;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ServerConfig {
+        host: String,
+        port: u16,
+        timeout: u32,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        server: ServerConfig,
+        mode: Mode,
+        #[serde(default)]
+        replicas: Option<u8>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Dev,
+        Prod,
+    }
+
+    fn load(sources: &[&dyn Source], env: &MockEnv) -> ConfigValues {
+        merge_config_values(
+            sources
+                .iter()
+                .map(|s| s.load(env).expect("source should load"))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_deserializes_valid_config() {
+        let env = MockEnv::new().with_env("APP_SERVER_PORT", "9090");
+        let toml = Toml::string(
+            r#"
+name = "svc"
+mode = "prod"
+
+[server]
+host = "localhost"
+port = 8080
+timeout = 30
+tags = ["a", "b"]
+"#,
+        );
+        let values = load(&[&toml, &Env::prefix("APP_")], &env);
+
+        let config: AppConfig = from_config_values(&values).unwrap();
+        assert_eq!(config.server.port, 9090);
+        assert_eq!(config.server.tags, vec!["a", "b"]);
+        assert_eq!(config.mode, Mode::Prod);
+        assert_eq!(config.replicas, None);
+    }
+
+    #[test]
+    fn test_reports_every_type_error_with_location() {
+        let env = MockEnv::new().with_env("APP_SERVER_PORT", "abc");
+        let toml = Toml::string(
+            r#"name = "svc"
+mode = "prod"
+replicas = 1000
+
+[server]
+host = "localhost"
+port = 8080
+timeout = "soon"
+"#,
+        );
+        let values = load(&[&toml, &Env::prefix("APP_")], &env);
+
+        let errors = from_config_values::<AppConfig>(&values).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["replicas", "server.port", "server.timeout"]);
+
+        let port = errors
+            .iter()
+            .find(|e| e.path() == Some("server.port"))
+            .unwrap();
+        assert_eq!(
+            port.source_location().unwrap().source,
+            "env:APP_SERVER_PORT"
+        );
+        assert!(matches!(
+            port,
+            ConfigError::ParseError { expected_type, actual_value, .. }
+                if expected_type == "u16" && actual_value == "abc"
+        ));
+
+        let timeout = errors
+            .iter()
+            .find(|e| e.path() == Some("server.timeout"))
+            .unwrap();
+        assert_eq!(timeout.source_location().unwrap().line, Some(8));
+
+        // Out-of-range values are rejected by serde itself and still located
+        let replicas = errors
+            .iter()
+            .find(|e| e.path() == Some("replicas"))
+            .unwrap();
+        assert_eq!(replicas.source_location().unwrap().line, Some(3));
+    }
+
+    #[test]
+    fn test_only_environment_strings_are_read_as_scalars() {
+        #[derive(Debug, Deserialize)]
+        struct Flags {
+            port: u16,
+            debug: bool,
+            ratio: f64,
+        }
+
+        let toml = Toml::string("port = \"8080\"\ndebug = \"yes\"\nratio = \"0.5\"\n");
+        let errors = from_config_values::<Flags>(&load(&[&toml], &MockEnv::new())).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["debug", "port", "ratio"]);
+        assert!(matches!(
+            errors.iter().find(|e| e.path() == Some("port")).unwrap(),
+            ConfigError::ParseError { expected_type, actual_value, .. }
+                if expected_type == "u16" && actual_value == "8080"
+        ));
+
+        let mut values = ConfigValues::empty();
+        for (path, text) in [("port", " 8080 "), ("debug", "on"), ("ratio", "0.5")] {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(text, SourceLocation::env(&path.to_uppercase())),
+            );
+        }
+        let flags: Flags = from_config_values(&values).unwrap();
+        assert_eq!((flags.port, flags.debug, flags.ratio), (8080, true, 0.5));
+    }
+
+    #[test]
+    fn test_accumulates_missing_fields() {
+        let toml = Toml::string("mode = \"dev\"\n[server]\nport = 80\n");
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors = from_config_values::<AppConfig>(&values).unwrap_err();
+        let missing: Vec<_> = errors
+            .iter()
+            .filter(|e| matches!(e, ConfigError::MissingField { .. }))
+            .filter_map(|e| e.path())
+            .collect();
+        assert_eq!(missing, vec!["name", "server.host", "server.timeout"]);
+    }
+
+    #[test]
+    fn test_missing_table_reported_once() {
+        let toml = Toml::string("name = \"svc\"\nmode = \"dev\"\n");
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors = from_config_values::<AppConfig>(&values).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["server"]);
+        assert!(matches!(errors.first(), ConfigError::MissingField { .. }));
+    }
+
+    #[test]
+    fn test_unknown_variant_reported_at_path() {
+        let toml = Toml::string(
+            "name = \"svc\"\nmode = \"staging\"\n[server]\nhost = \"h\"\nport = 1\ntimeout = 2\n",
+        );
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors = from_config_values::<AppConfig>(&values).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().path(), Some("mode"));
+        assert_eq!(errors.first().source_location().unwrap().line, Some(2));
+    }
+
+    #[test]
+    fn test_array_element_errors_use_indexed_paths() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Ports {
+            ports: Vec<u16>,
+        }
+
+        let toml = Toml::string("ports = [80, \"http\", 443, \"https\"]");
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors = from_config_values::<Ports>(&values).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["ports[1]", "ports[3]"]);
+    }
+
+    #[test]
+    fn test_deny_unknown_fields_reports_unknown_field() {
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        #[allow(dead_code)]
+        struct Strict {
+            host: String,
+        }
+
+        let toml = Toml::string("host = \"h\"\nhots = \"x\"\nprot = 1\n");
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors = from_config_values::<Strict>(&values).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["hots", "prot"]);
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e, ConfigError::UnknownField { .. })));
+    }
+
+    #[test]
+    fn test_unknown_fields_carry_location_and_suggestion() {
+        let env = MockEnv::new().with_env("APP_SERVER_TIMOUT", "5");
+        let toml = Toml::string(
+            r#"name = "svc"
+mode = "dev"
+
+[databse]
+host = "db"
+
+[server]
+host = "localhost"
+port = 8080
+timeout = 30
+"#,
+        );
+        let values = load(&[&toml, &Env::prefix("APP_")], &env);
+
+        let Deserialized {
+            value: config,
+            unknown,
+            ..
+        } = from_config_values_with::<AppConfig>(&values, &[], UnknownFields::Warn).unwrap();
+        assert_eq!(config.server.port, 8080);
+
+        let found: Vec<_> = unknown
+            .iter()
+            .map(|e| match e {
+                ConfigError::UnknownField {
+                    path,
+                    source_location,
+                    did_you_mean,
+                } => (
+                    path.as_str(),
+                    source_location.to_string(),
+                    did_you_mean.as_deref(),
+                ),
+                other => panic!("unexpected error: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("databse.host", "<string>:5".to_string(), None),
+                (
+                    "server.timout",
+                    "env:APP_SERVER_TIMOUT".to_string(),
+                    Some("server.timeout")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deny_unknown_fields_accumulates_with_type_errors() {
+        let toml = Toml::string(
+            "name = \"svc\"\nmode = \"dev\"\nnmae = \"x\"\n[server]\nhost = \"h\"\nport = \"x\"\ntimeout = 1\n",
+        );
+        let values = load(&[&toml], &MockEnv::new());
+
+        let errors =
+            from_config_values_with::<AppConfig>(&values, &[], UnknownFields::Deny).unwrap_err();
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["nmae", "server.port"]);
+        assert_eq!(
+            errors.first().suggestion().as_deref(),
+            Some("Change 'nmae' to 'name'")
+        );
+    }
+
+    #[test]
+    fn test_maps_accept_any_key() {
+        #[derive(Debug, Deserialize)]
+        struct Labels {
+            labels: BTreeMap<String, String>,
+        }
+
+        let toml = Toml::string("[labels]\nteam = \"core\"\ntier = \"1\"\n");
+        let values = load(&[&toml], &MockEnv::new());
+
+        let Deserialized {
+            value: config,
+            unknown,
+            ..
+        } = from_config_values_with::<Labels>(&values, &[], UnknownFields::Deny).unwrap();
+        assert_eq!(config.labels.len(), 2);
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn test_secret_paths_are_recorded_and_redacted_in_errors() {
+        #[derive(Debug, Deserialize)]
+        struct Database {
+            password: crate::Secret<String>,
+            pin: crate::Secret<u32>,
+        }
+
+        let toml = Toml::string("[db]\npassword = \"hunter2\"\npin = 1234\n");
+        let values = load(&[&toml], &MockEnv::new());
+        let database = from_config_values_with::<BTreeMap<String, Database>>(
+            &values,
+            &[],
+            UnknownFields::Ignore,
+        )
+        .unwrap();
+        assert_eq!(database.value["db"].password.expose_secret(), "hunter2");
+        assert_eq!(*database.value["db"].pin.expose_secret(), 1234);
+        assert_eq!(database.secrets, vec!["db.password", "db.pin"]);
+
+        let toml = Toml::string("[db]\npassword = \"hunter2\"\npin = \"12ab\"\n");
+        let values = load(&[&toml], &MockEnv::new());
+        let errors = from_config_values::<BTreeMap<String, Database>>(&values).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let rendered = errors.first().to_string();
+        assert!(rendered.contains("'db.pin'"), "{}", rendered);
+        assert!(!rendered.contains("12ab"), "{}", rendered);
+    }
+
+    #[test]
+    fn test_edit_distance_suggestions() {
+        assert_eq!(edit_distance("databse", "database"), 1);
+        assert_eq!(edit_distance("nmae", "name"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(suggest("hots", &["hosts", "port"]), Some("hosts"));
+        assert_eq!(suggest("verbose", &["hosts", "port"]), None);
+    }
+
+    #[test]
+    fn test_lossless_scalars() {
+        #[derive(Debug, Deserialize)]
+        struct Ids {
+            snowflake: u64,
+            signed: Option<i64>,
+            released: String,
+            born: String,
+            key: Vec<u8>,
+        }
+
+        let toml = Toml::string(
+            r#"
+snowflake = 1
+released = 1979-05-27T07:32:00-07:00
+born = 1979-05-27
+"#,
+        );
+        let mut values = load(&[&toml], &MockEnv::new());
+        let source = SourceLocation::new("ids.json");
+        values.insert(
+            "snowflake".to_string(),
+            ConfigValue::new(Value::UInteger(u64::MAX), source.clone()),
+        );
+        values.insert(
+            "key".to_string(),
+            ConfigValue::new(Value::Bytes(vec![0xde, 0xad]), source),
+        );
+
+        let ids: Ids = from_config_values(&values).unwrap();
+        assert_eq!(ids.snowflake, u64::MAX);
+        assert_eq!(ids.released, "1979-05-27T07:32:00-07:00");
+        assert_eq!(ids.born, "1979-05-27");
+        assert_eq!(ids.key, vec![0xde, 0xad]);
+        assert_eq!(ids.signed, None);
+
+        values.insert(
+            "signed".to_string(),
+            ConfigValue::new(Value::UInteger(u64::MAX), SourceLocation::new("ids.json")),
+        );
+        let errors = from_config_values::<Ids>(&values).unwrap_err();
+        assert_eq!(errors.first().path(), Some("signed"));
+    }
+}
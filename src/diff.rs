@@ -0,0 +1,439 @@
+//! Structured differences between two configurations.
+//!
+//! Compares the merged values of two configurations, e.g. staging against
+//! production or the configuration before and after a reload, and lists every
+//! path that was added, removed, or changed along with where each value came
+//! from.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use premortem::diff::ConfigDiff;
+//!
+//! let staging = Config::<AppConfig>::builder()
+//!     .source(Toml::file("staging.toml"))
+//!     .build_traced()?;
+//! let prod = Config::<AppConfig>::builder()
+//!     .source(Toml::file("prod.toml"))
+//!     .build_traced()?;
+//!
+//! let diff = ConfigDiff::from_traced(&staging, &prod);
+//! if diff.has_changes_under("database") {
+//!     println!("{}", diff);
+//! }
+//! ```
+//!
+//! ```text
+//! ~ database.host: "db.staging" (staging.toml:2) -> "db.prod" (prod.toml:2)
+//! ~ database.password: [REDACTED] (staging.toml:4) -> [REDACTED] (prod.toml:4)
+//! + database.replica = "db-ro.prod" (prod.toml:5)
+//! - feature.beta = true (staging.toml:9)
+//! ```
+
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value as JsonValue};
+
+use crate::redact::{RedactionPolicy, REDACTED};
+use crate::secret::is_secret_path;
+use crate::source::{in_subtree, is_metadata, value_to_json, ConfigValues};
+use crate::trace::TracedConfig;
+use crate::value::ConfigValue;
+
+/// A single difference between two configurations.
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// The path only exists in the new configuration.
+    Added {
+        /// Path of the value
+        path: String,
+        /// The new value and its source
+        new: ConfigValue,
+    },
+    /// The path only exists in the old configuration.
+    Removed {
+        /// Path of the value
+        path: String,
+        /// The old value and its source
+        old: ConfigValue,
+    },
+    /// The path exists in both configurations with different values.
+    Changed {
+        /// Path of the value
+        path: String,
+        /// The old value and its source
+        old: ConfigValue,
+        /// The new value and its source
+        new: ConfigValue,
+    },
+}
+
+impl Change {
+    /// Path of the changed value.
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. } => path,
+        }
+    }
+
+    /// The value before the change, if the path existed.
+    pub fn old_value(&self) -> Option<&ConfigValue> {
+        match self {
+            Change::Removed { old, .. } | Change::Changed { old, .. } => Some(old),
+            Change::Added { .. } => None,
+        }
+    }
+
+    /// The value after the change, if the path still exists.
+    pub fn new_value(&self) -> Option<&ConfigValue> {
+        match self {
+            Change::Added { new, .. } | Change::Changed { new, .. } => Some(new),
+            Change::Removed { .. } => None,
+        }
+    }
+}
+
+/// Differences between two sets of configuration values.
+///
+/// Changes are ordered by path. Array length metadata (`__len`) is not
+/// reported; added or removed elements show up as their own paths.
+///
+/// `Display` and the JSON form redact sensitive paths unless redaction is
+/// turned off with [`with_redaction`](Self::with_redaction).
+#[derive(Debug, Clone)]
+pub struct ConfigDiff {
+    changes: Vec<Change>,
+    redact_sensitive: bool,
+    policy: RedactionPolicy,
+    secrets: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Pure function: compute the differences from `old` to `new`.
+    pub fn between(old: &ConfigValues, new: &ConfigValues) -> Self {
+        let mut changes = Vec::new();
+
+        for (path, old_value) in old.iter() {
+            if is_metadata(path) {
+                continue;
+            }
+            match new.get(path) {
+                None => changes.push(Change::Removed {
+                    path: path.clone(),
+                    old: old_value.clone(),
+                }),
+                Some(new_value) if new_value.value != old_value.value => {
+                    changes.push(Change::Changed {
+                        path: path.clone(),
+                        old: old_value.clone(),
+                        new: new_value.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for (path, new_value) in new.iter() {
+            if !is_metadata(path) && !old.contains(path) {
+                changes.push(Change::Added {
+                    path: path.clone(),
+                    new: new_value.clone(),
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        Self {
+            changes,
+            redact_sensitive: true,
+            policy: RedactionPolicy::default(),
+            secrets: Vec::new(),
+        }
+    }
+
+    /// Compute the differences between the merged values of two traced builds.
+    ///
+    /// Secrets of either build are redacted, and values are shown according
+    /// to the redaction policy of the new build.
+    pub fn from_traced<A, B>(old: &TracedConfig<A>, new: &TracedConfig<B>) -> Self {
+        let mut diff = Self::between(old.values(), new.values())
+            .with_redaction_policy(new.redaction_policy().clone());
+        diff.secrets = old
+            .secret_paths()
+            .iter()
+            .chain(new.secret_paths())
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Set whether `Display` and JSON output redact sensitive values.
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact_sensitive = redact;
+        self
+    }
+
+    /// Set which values count as sensitive.
+    pub fn with_redaction_policy(mut self, policy: RedactionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// All changes, ordered by path.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Changes for paths that only exist in the new configuration.
+    pub fn added(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, Change::Added { .. }))
+    }
+
+    /// Changes for paths that only exist in the old configuration.
+    pub fn removed(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, Change::Removed { .. }))
+    }
+
+    /// Changes for paths whose value differs.
+    pub fn changed(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, Change::Changed { .. }))
+    }
+
+    /// Check if anything changed at `prefix` or beneath it.
+    ///
+    /// An empty prefix matches every change.
+    pub fn has_changes_under(&self, prefix: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|c| prefix.is_empty() || in_subtree(c.path(), prefix))
+    }
+
+    /// Check if the configurations are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of changed paths.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Convert to JSON: `added`, `removed` and `changed` lists of entries with
+    /// the path, value, and source of each side.
+    pub fn to_json(&self) -> JsonValue {
+        let entry = |path: &str, value: &ConfigValue| {
+            json!({
+                "value": self.json_value(path, value),
+                "source": value.source.to_string(),
+            })
+        };
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut changed = Vec::new();
+        for change in &self.changes {
+            match change {
+                Change::Added { path, new } => {
+                    added.push(json!({ "path": path, "new": entry(path, new) }))
+                }
+                Change::Removed { path, old } => {
+                    removed.push(json!({ "path": path, "old": entry(path, old) }))
+                }
+                Change::Changed { path, old, new } => changed.push(json!({
+                    "path": path,
+                    "old": entry(path, old),
+                    "new": entry(path, new),
+                })),
+            }
+        }
+
+        json!({ "added": added, "removed": removed, "changed": changed })
+    }
+
+    fn is_redacted(&self, path: &str, value: &ConfigValue) -> bool {
+        self.redact_sensitive
+            && (is_secret_path(path, &self.secrets) || self.policy.redacts(path, &value.value))
+    }
+
+    fn json_value(&self, path: &str, value: &ConfigValue) -> JsonValue {
+        if self.is_redacted(path, value) {
+            JsonValue::String(REDACTED.to_string())
+        } else {
+            value_to_json(&value.value)
+        }
+    }
+
+    fn display_value(&self, path: &str, value: &ConfigValue) -> String {
+        if self.is_redacted(path, value) {
+            REDACTED.to_string()
+        } else {
+            value_to_json(&value.value).to_string()
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match change {
+                Change::Added { path, new } => writeln!(
+                    f,
+                    "+ {} = {} ({})",
+                    path,
+                    self.display_value(path, new),
+                    new.source
+                )?,
+                Change::Removed { path, old } => writeln!(
+                    f,
+                    "- {} = {} ({})",
+                    path,
+                    self.display_value(path, old),
+                    old.source
+                )?,
+                Change::Changed { path, old, new } => writeln!(
+                    f,
+                    "~ {}: {} ({}) -> {} ({})",
+                    path,
+                    self.display_value(path, old),
+                    old.source,
+                    self.display_value(path, new),
+                    new.source
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for ConfigDiff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SourceLocation;
+    use crate::value::Value;
+
+    fn values(source: &str, entries: &[(&str, Value)]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (line, (path, value)) in entries.iter().enumerate() {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(
+                    value.clone(),
+                    SourceLocation::new(source).with_line(line as u32 + 1),
+                ),
+            );
+        }
+        values
+    }
+
+    fn staging_and_prod() -> ConfigDiff {
+        let staging = values(
+            "staging.toml",
+            &[
+                ("database.host", Value::from("db.staging")),
+                ("database.password", Value::from("s3cret")),
+                ("database.port", Value::Integer(5432)),
+                ("feature.beta", Value::Bool(true)),
+                ("hosts.__len", Value::Integer(1)),
+            ],
+        );
+        let prod = values(
+            "prod.toml",
+            &[
+                ("database.host", Value::from("db.prod")),
+                ("database.password", Value::from("hunter2")),
+                ("database.port", Value::Integer(5432)),
+                ("database.replica", Value::from("db-ro.prod")),
+                ("hosts.__len", Value::Integer(2)),
+            ],
+        );
+        ConfigDiff::between(&staging, &prod)
+    }
+
+    #[test]
+    fn test_between_lists_added_removed_changed() {
+        let diff = staging_and_prod();
+
+        let paths: Vec<_> = diff.changes().iter().map(Change::path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "database.host",
+                "database.password",
+                "database.replica",
+                "feature.beta"
+            ]
+        );
+        assert_eq!(diff.added().count(), 1);
+        assert_eq!(diff.removed().count(), 1);
+        assert_eq!(diff.changed().count(), 2);
+
+        let host = &diff.changes()[0];
+        assert_eq!(host.old_value().unwrap().value, Value::from("db.staging"));
+        assert_eq!(host.new_value().unwrap().source.source, "prod.toml");
+    }
+
+    #[test]
+    fn test_has_changes_under() {
+        let diff = staging_and_prod();
+        assert!(diff.has_changes_under("database"));
+        assert!(diff.has_changes_under("feature.beta"));
+        assert!(!diff.has_changes_under("data"));
+        assert!(!diff.has_changes_under("hosts"));
+        assert!(diff.has_changes_under(""));
+    }
+
+    #[test]
+    fn test_display_redacts_sensitive_values() {
+        let diff = staging_and_prod();
+        let output = diff.to_string();
+
+        assert!(output.contains(
+            "~ database.host: \"db.staging\" (staging.toml:1) -> \"db.prod\" (prod.toml:1)\n"
+        ));
+        assert!(output.contains(
+            "~ database.password: [REDACTED] (staging.toml:2) -> [REDACTED] (prod.toml:2)\n"
+        ));
+        assert!(output.contains("+ database.replica = \"db-ro.prod\" (prod.toml:4)\n"));
+        assert!(output.contains("- feature.beta = true (staging.toml:4)\n"));
+        assert!(!output.contains("hunter2"));
+
+        let unredacted = diff.with_redaction(false).to_string();
+        assert!(unredacted.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_json_serialization() {
+        let diff = staging_and_prod();
+        let json = serde_json::to_value(&diff).unwrap();
+
+        assert_eq!(json, diff.to_json());
+        assert_eq!(json["added"][0]["path"], "database.replica");
+        assert_eq!(json["added"][0]["new"]["source"], "prod.toml:4");
+        assert_eq!(json["removed"][0]["old"]["value"], true);
+        assert_eq!(json["changed"][1]["new"]["value"], REDACTED);
+    }
+
+    #[test]
+    fn test_identical_values_have_no_changes() {
+        let a = values("a.toml", &[("host", Value::from("x"))]);
+        let b = values("b.toml", &[("host", Value::from("x"))]);
+        let diff = ConfigDiff::between(&a, &b);
+        assert!(diff.is_empty());
+        assert_eq!(diff.len(), 0);
+        assert_eq!(diff.to_string(), "");
+    }
+}
@@ -0,0 +1,419 @@
+//! Reference documentation generated from config structs.
+//!
+//! Renders the fields captured by `#[derive(ConfigSchema)]` as Markdown or
+//! HTML tables: one row per field with its type, default, constraints,
+//! environment variable, and doc comment. Nested structs become sub-sections,
+//! so the reference follows the shape of the config files.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use premortem::docs::ConfigDocs;
+//!
+//! let docs = ConfigDocs::of::<AppConfig>()
+//!     .title("Configuration reference")
+//!     .env(&Env::prefix("APP_"))
+//!     .defaults(&AppConfig::default());
+//!
+//! std::fs::write("docs/config.md", docs.to_markdown())?;
+//! ```
+//!
+//! ```text
+//! ## database
+//!
+//! | Key | Type | Required | Default | Constraints | Env var | Description |
+//! |-----|------|----------|---------|-------------|---------|-------------|
+//! | `database.host` | `String` | yes | `"localhost"` | `non_empty` | `APP_DATABASE_HOST` | Hostname of the primary |
+//! ```
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::redact::REDACTED;
+use crate::schema::{ConfigSchema, FieldInfo};
+use crate::sources::Env;
+
+/// Reference documentation for a config type.
+#[derive(Debug, Clone)]
+pub struct ConfigDocs {
+    title: String,
+    description: Option<String>,
+    fields: Vec<FieldInfo>,
+    env: Option<Env>,
+    defaults: Option<JsonValue>,
+}
+
+/// A group of fields rendered as one table.
+struct Section<'a> {
+    /// Dotted path of the nested struct; empty for the root
+    path: String,
+    depth: usize,
+    description: Option<&'a str>,
+    rows: Vec<(String, &'a FieldInfo)>,
+}
+
+impl ConfigDocs {
+    /// Documentation for `T`, titled with the type's name.
+    pub fn of<T: ConfigSchema>() -> Self {
+        let schema = T::json_schema();
+        Self {
+            title: schema["title"]
+                .as_str()
+                .unwrap_or("Configuration")
+                .to_string(),
+            description: schema["description"].as_str().map(str::to_string),
+            fields: T::fields(),
+            env: None,
+            defaults: None,
+        }
+    }
+
+    /// Replace the document title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Show the environment variable each field maps to under `env`.
+    pub fn env(mut self, env: &Env) -> Self {
+        self.env = Some(env.clone());
+        self
+    }
+
+    /// Show default values taken from `defaults` (typically `T::default()`).
+    ///
+    /// Defaults of `#[sensitive]` fields are redacted.
+    pub fn defaults<D: Serialize>(mut self, defaults: &D) -> Self {
+        self.defaults = serde_json::to_value(defaults).ok();
+        self
+    }
+
+    /// Render as Markdown: a heading per section and a table per heading.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        if let Some(description) = &self.description {
+            out.push_str(&format!("\n{}\n", description));
+        }
+
+        for section in self.sections() {
+            if !section.path.is_empty() {
+                let level = "#".repeat(section.depth.min(6));
+                out.push_str(&format!("\n{} {}\n", level, section.path));
+                if let Some(description) = section.description {
+                    out.push_str(&format!("\n{}\n", description));
+                }
+            }
+            if section.rows.is_empty() {
+                continue;
+            }
+
+            let headers = self.headers();
+            out.push_str(&format!("\n| {} |\n", headers.join(" | ")));
+            out.push_str(&format!(
+                "|{}|\n",
+                headers
+                    .iter()
+                    .map(|h| "-".repeat(h.len() + 2))
+                    .collect::<Vec<_>>()
+                    .join("|")
+            ));
+            for (path, field) in &section.rows {
+                let cells: Vec<String> = self
+                    .cells(path, field)
+                    .into_iter()
+                    .map(|cell| markdown_cell(&cell))
+                    .collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+        out
+    }
+
+    /// Render as an HTML fragment: a heading and a `<table>` per section.
+    pub fn to_html(&self) -> String {
+        let mut out = format!("<h1>{}</h1>\n", escape_html(&self.title));
+        if let Some(description) = &self.description {
+            out.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+        }
+
+        for section in self.sections() {
+            if !section.path.is_empty() {
+                let level = section.depth.min(6);
+                out.push_str(&format!(
+                    "<h{level} id=\"{id}\">{title}</h{level}>\n",
+                    id = escape_html(&section.path),
+                    title = escape_html(&section.path),
+                ));
+                if let Some(description) = section.description {
+                    out.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+                }
+            }
+            if section.rows.is_empty() {
+                continue;
+            }
+
+            out.push_str("<table>\n<thead>\n<tr>");
+            for header in self.headers() {
+                out.push_str(&format!("<th>{}</th>", header));
+            }
+            out.push_str("</tr>\n</thead>\n<tbody>\n");
+            for (path, field) in &section.rows {
+                out.push_str("<tr>");
+                for cell in self.cells(path, field) {
+                    out.push_str(&format!("<td>{}</td>", html_cell(&cell)));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</tbody>\n</table>\n");
+        }
+        out
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        let mut headers = vec!["Key", "Type", "Required", "Default", "Constraints"];
+        if self.env.is_some() {
+            headers.push("Env var");
+        }
+        headers.push("Description");
+        headers
+    }
+
+    /// Cell contents for one field. Code spans are wrapped in backticks and
+    /// converted to `<code>` for HTML.
+    fn cells(&self, path: &str, field: &FieldInfo) -> Vec<String> {
+        let code = |s: &str| format!("`{}`", s);
+        let mut cells = vec![
+            code(path),
+            code(field.rust_type),
+            if field.required { "yes" } else { "no" }.to_string(),
+            self.default_for(path, field)
+                .map(|d| code(&d))
+                .unwrap_or_default(),
+            field
+                .validators
+                .iter()
+                .map(|v| code(v))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ];
+        if let Some(env) = &self.env {
+            cells.push(env.var_name(path).map(|v| code(&v)).unwrap_or_default());
+        }
+        cells.push(field.description.unwrap_or_default().to_string());
+        cells
+    }
+
+    fn default_for(&self, path: &str, field: &FieldInfo) -> Option<String> {
+        let value = path
+            .split('.')
+            .try_fold(self.defaults.as_ref()?, |value, key| value.get(key))?;
+        if value.is_null() {
+            return None;
+        }
+        if field.sensitive {
+            return Some(REDACTED.to_string());
+        }
+        Some(value.to_string())
+    }
+
+    /// Pure function: split the field tree into one section per struct.
+    fn sections(&self) -> Vec<Section<'_>> {
+        let mut sections = Vec::new();
+        collect_sections(&self.fields, String::new(), 1, None, &mut sections);
+        sections
+    }
+}
+
+fn collect_sections<'a>(
+    fields: &'a [FieldInfo],
+    path: String,
+    depth: usize,
+    description: Option<&'a str>,
+    sections: &mut Vec<Section<'a>>,
+) {
+    let mut rows = Vec::new();
+    let mut nested = Vec::new();
+    flatten_rows(fields, &path, &mut rows, &mut nested);
+
+    sections.push(Section {
+        path,
+        depth,
+        description,
+        rows,
+    });
+    for (child_path, field) in nested {
+        collect_sections(
+            &field.children,
+            child_path,
+            depth + 1,
+            field.description,
+            sections,
+        );
+    }
+}
+
+/// Split fields into table rows and nested structs, inlining flattened fields.
+fn flatten_rows<'a>(
+    fields: &'a [FieldInfo],
+    prefix: &str,
+    rows: &mut Vec<(String, &'a FieldInfo)>,
+    nested: &mut Vec<(String, &'a FieldInfo)>,
+) {
+    for field in fields {
+        if field.flatten {
+            flatten_rows(&field.children, prefix, rows, nested);
+            continue;
+        }
+        let path = if prefix.is_empty() {
+            field.name.to_string()
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        if field.children.is_empty() {
+            rows.push((path, field));
+        } else {
+            nested.push((path, field));
+        }
+    }
+}
+
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+fn html_cell(cell: &str) -> String {
+    // Turn `code` spans into <code> elements
+    escape_html(cell)
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("<code>{}</code>", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect()
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{object, JsonValue};
+
+    fn leaf(name: &'static str, rust_type: &'static str) -> FieldInfo {
+        FieldInfo {
+            name,
+            rust_type,
+            description: None,
+            validators: &[],
+            required: true,
+            has_default: false,
+            sensitive: false,
+            flatten: false,
+            children: Vec::new(),
+        }
+    }
+
+    struct App;
+
+    impl ConfigSchema for App {
+        fn json_schema() -> JsonValue {
+            object("App", Some("Application settings."), Vec::new(), false)
+        }
+
+        fn fields() -> Vec<FieldInfo> {
+            vec![
+                FieldInfo {
+                    description: Some("Log level | verbosity"),
+                    validators: &["non_empty"],
+                    ..leaf("log_level", "String")
+                },
+                FieldInfo {
+                    description: Some("Primary database."),
+                    children: vec![
+                        leaf("host", "String"),
+                        FieldInfo {
+                            sensitive: true,
+                            ..leaf("password", "String")
+                        },
+                    ],
+                    ..leaf("database", "Database")
+                },
+                FieldInfo {
+                    flatten: true,
+                    children: vec![FieldInfo {
+                        required: false,
+                        ..leaf("threads", "Option<u32>")
+                    }],
+                    ..leaf("runtime", "Runtime")
+                },
+            ]
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Defaults {
+        log_level: &'static str,
+        database: DatabaseDefaults,
+    }
+
+    #[derive(Serialize)]
+    struct DatabaseDefaults {
+        host: &'static str,
+        password: &'static str,
+    }
+
+    fn docs() -> ConfigDocs {
+        ConfigDocs::of::<App>()
+            .env(&Env::prefix("APP_"))
+            .defaults(&Defaults {
+                log_level: "info",
+                database: DatabaseDefaults {
+                    host: "localhost",
+                    password: "hunter2",
+                },
+            })
+    }
+
+    #[test]
+    fn test_markdown_sections_and_rows() {
+        let markdown = docs().to_markdown();
+
+        assert!(markdown.starts_with("# App\n\nApplication settings.\n"));
+        assert!(markdown.contains(
+            "| `log_level` | `String` | yes | `\"info\"` | `non_empty` |  | Log level \\| verbosity |"
+        ));
+        assert!(markdown.contains("| `threads` | `Option<u32>` | no |"));
+        assert!(markdown.contains("\n## database\n\nPrimary database.\n"));
+        assert!(markdown.contains("| `database.host` | `String` | yes | `\"localhost\"` |"));
+        assert!(markdown.contains("`[REDACTED]`"));
+        assert!(!markdown.contains("hunter2"));
+        // log_level contains the separator, so no variable reaches it
+        assert!(!markdown.contains("APP_LOG_LEVEL"));
+    }
+
+    #[test]
+    fn test_html_escapes_and_uses_code_elements() {
+        let html = docs().to_html();
+
+        assert!(html.contains("<h2 id=\"database\">database</h2>"));
+        assert!(html.contains("<td><code>Option&lt;u32&gt;</code></td>"));
+        assert!(html.contains("<td><code>APP_DATABASE_HOST</code></td>"));
+        assert!(!html.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_env_column_only_with_env() {
+        let markdown = ConfigDocs::of::<App>().title("Reference").to_markdown();
+        assert!(markdown.starts_with("# Reference\n"));
+        assert!(!markdown.contains("Env var"));
+    }
+}
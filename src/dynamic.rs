@@ -0,0 +1,524 @@
+//! Untyped configuration validated against a runtime schema.
+//!
+//! When the shape of a configuration isn't known at compile time — plugin
+//! settings, tenant overrides — there is no type to deserialize into.
+//! `DynamicConfig` is built by the same `ConfigBuilder` pipeline (sources,
+//! profiles, merge strategies, aliases, interpolation) and exposes the merged
+//! tree as [`Value`]s with their source locations. A [`DynamicSchema`]
+//! registers the existing [`validators`](crate::validators) per path, and every
+//! failure is reported together, just like a typed build:
+//!
+//! ```ignore
+//! use premortem::prelude::*;
+//!
+//! let schema = DynamicSchema::new()
+//!     .required("name")
+//!     .string("name", NonEmpty)
+//!     .integer("port", Range(1..=65535))
+//!     .string("servers[*].host", Pattern::new(r"^[a-z0-9.-]+$"));
+//!
+//! let config = DynamicConfig::builder()
+//!     .source(Toml::file("plugins/cache.toml"))
+//!     .source(Env::prefix("CACHE_"))
+//!     .build_dynamic(&schema)?;
+//!
+//! let port = config.get_path("port").and_then(Value::as_integer);
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use stillwater::Validation;
+
+use crate::config::{redact_warnings, report_warnings, ConfigBuilder};
+use crate::env::{ConfigEnv, RealEnv};
+use crate::error::{ConfigError, ConfigErrors, ConfigValidation, SourceLocation};
+use crate::source::{
+    array_of_len, glob_to_regex, in_subtree, is_metadata, parse_path, ConfigValues, PathSegment,
+};
+use crate::validate::{collect_warnings, Validator};
+use crate::value::Value;
+
+/// Configuration whose shape is only known at runtime.
+///
+/// Created with [`ConfigBuilder::build_dynamic`], or from values at hand with
+/// [`from_values`](Self::from_values).
+#[derive(Debug, Clone)]
+pub struct DynamicConfig {
+    values: ConfigValues,
+    root: Value,
+}
+
+impl DynamicConfig {
+    /// Create a builder for a dynamic configuration.
+    pub fn builder() -> ConfigBuilder<DynamicConfig> {
+        ConfigBuilder::new()
+    }
+
+    /// Wrap merged values.
+    pub fn from_values(values: ConfigValues) -> Self {
+        let root = value_tree(&values);
+        Self { values, root }
+    }
+
+    /// The whole configuration as a table.
+    pub fn root(&self) -> &Value {
+        &self.root
+    }
+
+    /// The value at `path`, e.g. `database.host` or `servers[0].port`.
+    ///
+    /// Tables and arrays are returned whole.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        self.root.get_path(path)
+    }
+
+    /// Where the value at `path` came from.
+    ///
+    /// For a table or array, this is the location of its first value.
+    pub fn location(&self, path: &str) -> Option<&SourceLocation> {
+        self.values
+            .get(path)
+            .or_else(|| {
+                self.values
+                    .iter()
+                    .find(|(p, _)| in_subtree(p, path) && !p.contains(".__"))
+                    .map(|(_, cv)| cv)
+            })
+            .map(|cv| &cv.source)
+    }
+
+    /// Deserialize the value at `path` into `T`. See [`ConfigValues::get_as`].
+    pub fn get_as<T: DeserializeOwned>(&self, path: &str) -> Result<T, ConfigErrors> {
+        self.values.get_as(path)
+    }
+
+    /// The flat values the configuration was built from.
+    pub fn values(&self) -> &ConfigValues {
+        &self.values
+    }
+
+    /// Every path that holds a value, a table or an array.
+    fn node_paths(&self) -> BTreeSet<String> {
+        let mut paths = BTreeSet::new();
+        for path in self.values.paths() {
+            let path = array_of_len(path).unwrap_or(path);
+            if path.is_empty() || is_metadata(path) {
+                continue;
+            }
+            for (i, _) in path.match_indices(['.', '[']).filter(|(i, _)| *i > 0) {
+                paths.insert(path[..i].to_string());
+            }
+            paths.insert(path.to_string());
+        }
+        paths
+    }
+}
+
+/// Checks one value, given its full path.
+type Check = Box<dyn Fn(&DynamicConfig, &str) -> ConfigValidation<()> + Send + Sync>;
+
+/// A rule registered for a path pattern.
+enum Rule {
+    /// The pattern must match at least one value.
+    Required(String),
+    /// Every value the pattern matches must pass the check.
+    Check(String, Check),
+}
+
+/// Validators registered per path, applied to a [`DynamicConfig`].
+///
+/// Paths may contain globs: `*` matches within one key or index
+/// (`servers[*].host`, `plugins.*.enabled`) and `**` across any number of
+/// segments. Rules only apply to values that are present; add
+/// [`required`](Self::required) for values that must be.
+///
+/// Values are read the way a typed build reads them, so `"8080"` from an
+/// environment variable passes an [`integer`](Self::integer) rule.
+#[derive(Default)]
+pub struct DynamicSchema {
+    rules: Vec<Rule>,
+}
+
+impl fmt::Debug for DynamicSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns: Vec<&str> = self
+            .rules
+            .iter()
+            .map(|rule| match rule {
+                Rule::Required(pattern) | Rule::Check(pattern, _) => pattern.as_str(),
+            })
+            .collect();
+        f.debug_struct("DynamicSchema")
+            .field("rules", &patterns)
+            .finish()
+    }
+}
+
+impl DynamicSchema {
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a value at `path`; with a glob, at least one matching value.
+    pub fn required(mut self, path: impl Into<String>) -> Self {
+        self.rules.push(Rule::Required(path.into()));
+        self
+    }
+
+    /// Validate the values at `path` as `T`.
+    ///
+    /// Values that can't be read as `T` are reported as
+    /// `ConfigError::ParseError`.
+    ///
+    /// ```ignore
+    /// let schema = DynamicSchema::new().rule::<u16>("port", Range(1024..=65535));
+    /// ```
+    pub fn rule<T>(
+        mut self,
+        path: impl Into<String>,
+        validator: impl Validator<T> + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let check = move |config: &DynamicConfig, path: &str| match config.get_as::<T>(path) {
+            Ok(value) => validator.validate(&value, path),
+            Err(errors) => Validation::Failure(errors),
+        };
+        self.rules.push(Rule::Check(path.into(), Box::new(check)));
+        self
+    }
+
+    /// Validate the strings at `path`, e.g. with `NonEmpty` or `Pattern`.
+    pub fn string(
+        self,
+        path: impl Into<String>,
+        validator: impl Validator<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rule::<String>(path, validator)
+    }
+
+    /// Validate the integers at `path`, e.g. with `Range` or `Positive`.
+    pub fn integer(
+        self,
+        path: impl Into<String>,
+        validator: impl Validator<i64> + Send + Sync + 'static,
+    ) -> Self {
+        self.rule::<i64>(path, validator)
+    }
+
+    /// Validate the numbers at `path`; integers are accepted as floats.
+    pub fn float(
+        self,
+        path: impl Into<String>,
+        validator: impl Validator<f64> + Send + Sync + 'static,
+    ) -> Self {
+        self.rule::<f64>(path, validator)
+    }
+
+    /// Validate the arrays at `path`, e.g. with `MinItems` or `NonEmptyCollection`.
+    pub fn array(
+        mut self,
+        path: impl Into<String>,
+        validator: impl Validator<[Value]> + Send + Sync + 'static,
+    ) -> Self {
+        let check = move |config: &DynamicConfig, path: &str| match config.get_path(path) {
+            Some(Value::Array(items)) => validator.validate(items, path),
+            Some(other) => Validation::Failure(ConfigErrors::single(ConfigError::ParseError {
+                path: path.to_string(),
+                source_location: config
+                    .location(path)
+                    .cloned()
+                    .unwrap_or_else(|| SourceLocation::new("merged config")),
+                expected_type: "array".to_string(),
+                actual_value: other.type_name().to_string(),
+                message: format!("invalid type: {}, expected array", other.type_name()),
+            })),
+            None => Validation::Success(()),
+        };
+        self.rules.push(Rule::Check(path.into(), Box::new(check)));
+        self
+    }
+
+    /// Check the values at `path` with a function of the raw value and its path.
+    pub fn custom<F>(mut self, path: impl Into<String>, check: F) -> Self
+    where
+        F: Fn(&Value, &str) -> ConfigValidation<()> + Send + Sync + 'static,
+    {
+        let check = move |config: &DynamicConfig, path: &str| match config.get_path(path) {
+            Some(value) => check(value, path),
+            None => Validation::Success(()),
+        };
+        self.rules.push(Rule::Check(path.into(), Box::new(check)));
+        self
+    }
+
+    /// Validate `config`, accumulating the errors of every rule.
+    ///
+    /// Validation errors without a source location get the location of the
+    /// value that failed.
+    pub fn validate(&self, config: &DynamicConfig) -> ConfigValidation<()> {
+        let nodes = config.node_paths();
+        let mut errors = Vec::new();
+
+        for rule in &self.rules {
+            match rule {
+                Rule::Required(pattern) => {
+                    let regex = glob_to_regex(pattern);
+                    if !nodes.iter().any(|path| regex.is_match(path)) {
+                        errors.push(ConfigError::MissingField {
+                            path: pattern.clone(),
+                            source_location: None,
+                            searched_sources: Vec::new(),
+                        });
+                    }
+                }
+                Rule::Check(pattern, check) => {
+                    let regex = glob_to_regex(pattern);
+                    for path in nodes.iter().filter(|path| regex.is_match(path)) {
+                        if let Validation::Failure(failed) = check(config, path) {
+                            errors.extend(failed.into_iter().map(|e| with_location(e, config)));
+                        }
+                    }
+                }
+            }
+        }
+
+        match ConfigErrors::from_vec(errors) {
+            Some(errors) => Validation::Failure(errors),
+            None => Validation::Success(()),
+        }
+    }
+}
+
+/// Attach the location of the failing value to a validation error that has none.
+fn with_location(error: ConfigError, config: &DynamicConfig) -> ConfigError {
+    match error {
+        ConfigError::ValidationError {
+            path,
+            source_location: None,
+            value,
+            message,
+        } => ConfigError::ValidationError {
+            source_location: config.location(&path).cloned(),
+            path,
+            value,
+            message,
+        },
+        other => other,
+    }
+}
+
+impl ConfigBuilder<DynamicConfig> {
+    /// Load and merge the sources, then validate the result against `schema`.
+    ///
+    /// Uses the real environment.
+    pub fn build_dynamic(self, schema: &DynamicSchema) -> Result<DynamicConfig, ConfigErrors> {
+        self.build_dynamic_with_env(schema, &RealEnv::new())
+    }
+
+    /// Load, merge and validate against `schema` with a custom environment.
+    ///
+    /// With [`from_subtree`](ConfigBuilder::from_subtree), only the values
+    /// beneath the subtree are kept and schema paths are relative to it;
+    /// errors still carry full paths. Warnings, including those of
+    /// [`Warn`](crate::Warn) rules, are printed to stderr only with
+    /// [`print_warnings`](ConfigBuilder::print_warnings).
+    pub fn build_dynamic_with_env(
+        self,
+        schema: &DynamicSchema,
+        env: &dyn ConfigEnv,
+    ) -> Result<DynamicConfig, ConfigErrors> {
+        let options = self.options();
+        let mut warnings = Vec::new();
+        let merged = self.merged_values(&options.aliases, env, &mut warnings)?;
+
+        let prefix = options.subtree.as_deref().unwrap_or_default();
+        let config = DynamicConfig::from_values(merged.subtree(prefix));
+
+        match collect_warnings(|| schema.validate(&config)) {
+            (Validation::Success(()), failed_checks) => {
+                warnings.extend(failed_checks.into_iter().map(|w| match prefix.is_empty() {
+                    true => w,
+                    false => w.with_path_prefix(prefix),
+                }));
+                report_warnings(redact_warnings(warnings, options), options)?;
+                Ok(config)
+            }
+            (Validation::Failure(errors), _) => {
+                let errors = match prefix.is_empty() {
+                    true => errors,
+                    false => errors.with_path_prefix(prefix),
+                };
+                Err(options.redaction.redact_errors(errors))
+            }
+        }
+    }
+}
+
+/// Pure function: rebuild the nested value tree from flattened paths.
+fn value_tree(values: &ConfigValues) -> Value {
+    let mut root = Value::Table(Default::default());
+    for (path, cv) in values.iter() {
+        if let Some(array) = array_of_len(path) {
+            // Arrays exist even when empty
+            let node = entry(&mut root, &parse_path(array));
+            if !matches!(node, Value::Array(_)) {
+                *node = Value::Array(Vec::new());
+            }
+            continue;
+        }
+        if is_metadata(path) {
+            continue;
+        }
+        *entry(&mut root, &parse_path(path)) = cv.value.clone();
+    }
+    root
+}
+
+/// Walk to the value at `segments`, creating tables and arrays on the way.
+fn entry<'a>(node: &'a mut Value, segments: &[PathSegment]) -> &'a mut Value {
+    let Some((first, rest)) = segments.split_first() else {
+        return node;
+    };
+
+    match first {
+        PathSegment::Key(key) => {
+            if !matches!(node, Value::Table(_)) {
+                *node = Value::Table(Default::default());
+            }
+            let Value::Table(table) = node else {
+                unreachable!("node was just made a table")
+            };
+            entry(table.entry(key.clone()).or_default(), rest)
+        }
+        PathSegment::Index(index) => {
+            if !matches!(node, Value::Array(_)) {
+                *node = Value::Array(Vec::new());
+            }
+            let Value::Array(items) = node else {
+                unreachable!("node was just made an array")
+            };
+            if items.len() <= *index {
+                items.resize(*index + 1, Value::Null);
+            }
+            entry(&mut items[*index], rest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::sources::Toml;
+    use crate::validate::validators::{MinItems, NonEmpty, Pattern, Range};
+    use crate::value::ConfigValue;
+
+    const PLUGIN: &str = r#"
+name = "cache"
+port = 8080
+
+[[servers]]
+host = "a.internal"
+
+[[servers]]
+host = "B_INTERNAL"
+"#;
+
+    fn env() -> MockEnv {
+        MockEnv::new().with_file("plugin.toml", PLUGIN)
+    }
+
+    #[test]
+    fn test_dynamic_config_exposes_values_and_locations() {
+        let config = DynamicConfig::builder()
+            .source(Toml::file("plugin.toml"))
+            .build_dynamic_with_env(&DynamicSchema::new(), &env())
+            .unwrap();
+
+        assert_eq!(
+            config.get_path("name").and_then(Value::as_str),
+            Some("cache")
+        );
+        assert_eq!(
+            config.get_path("servers[1].host").and_then(Value::as_str),
+            Some("B_INTERNAL")
+        );
+        assert_eq!(
+            config
+                .get_path("servers")
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(config.location("port").unwrap().line, Some(3));
+        assert_eq!(config.location("servers").unwrap().source, "plugin.toml");
+        assert_eq!(config.get_as::<u16>("port").unwrap(), 8080);
+    }
+
+    #[test]
+    fn test_schema_accumulates_errors() {
+        let schema = DynamicSchema::new()
+            .required("name")
+            .required("version")
+            .integer("port", Range(1..=1024))
+            .string("servers[*].host", Pattern::new(r"^[a-z.]+$"))
+            .array("servers", MinItems(3));
+
+        let errors = DynamicConfig::builder()
+            .source(Toml::file("plugin.toml"))
+            .build_dynamic_with_env(&schema, &env())
+            .unwrap_err();
+
+        let paths: Vec<_> = errors.iter().filter_map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["version", "port", "servers[1].host", "servers"]);
+        let port = errors.iter().find(|e| e.path() == Some("port")).unwrap();
+        assert_eq!(port.source_location().unwrap().line, Some(3));
+    }
+
+    #[test]
+    fn test_schema_reports_type_mismatches() {
+        let mut values = ConfigValues::empty();
+        let source = SourceLocation::env("APP_PORT");
+        values.insert("port".to_string(), ConfigValue::new("8080", source.clone()));
+        values.insert("name".to_string(), ConfigValue::new(true, source.clone()));
+        values.insert("tags".to_string(), ConfigValue::new("a,b", source));
+        let config = DynamicConfig::from_values(values);
+
+        let schema = DynamicSchema::new()
+            .integer("port", Range(1..=65535))
+            .string("name", NonEmpty)
+            .array("tags", MinItems(1));
+        let errors = match schema.validate(&config) {
+            Validation::Failure(errors) => errors,
+            Validation::Success(()) => panic!("tags is not an array"),
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors.first(),
+            ConfigError::ParseError { path, .. } if path == "tags"
+        ));
+    }
+
+    #[test]
+    fn test_build_dynamic_from_subtree_uses_full_paths() {
+        let env = MockEnv::new().with_file("app.toml", "[plugins.cache]\nport = 0\n");
+        let errors = DynamicConfig::builder()
+            .source(Toml::file("app.toml"))
+            .from_subtree("plugins.cache")
+            .build_dynamic_with_env(
+                &DynamicSchema::new().integer("port", Range(1..=65535)),
+                &env,
+            )
+            .unwrap_err();
+
+        assert_eq!(errors.first().path(), Some("plugins.cache.port"));
+    }
+}
@@ -15,10 +15,11 @@ use std::sync::RwLock;
 ///
 /// # Stillwater Integration
 ///
-/// Used as the `Env` parameter in `Effect<T, E, Env>`:
+/// Any `ConfigEnv` that is also `Clone` can be the `Env` of an `Effect`:
 ///
 /// ```ignore
-/// fn load<E: ConfigEnv>(&self) -> Effect<ConfigValues, ConfigErrors, E>
+/// fn load_effect<E: ConfigEnv + Clone>(&self)
+///     -> impl Effect<Output = ConfigValues, Error = ConfigErrors, Env = E>
 /// ```
 ///
 /// # Example
@@ -68,6 +69,19 @@ pub trait ConfigEnv: Send + Sync {
     ///
     /// Used by Env source when no prefix is specified.
     fn all_env_vars(&self) -> Vec<(String, String)>;
+
+    /// Replace a file's contents atomically.
+    ///
+    /// Readers see either the old contents or the new, never a partial write.
+    /// Used to write configuration snapshots; environments that cannot write
+    /// return `ErrorKind::Unsupported`, which is the default.
+    fn write_file(&self, path: &Path, contents: &str) -> io::Result<()> {
+        let _ = contents;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("cannot write {}", path.display()),
+        ))
+    }
 }
 
 /// Production environment using standard library I/O.
@@ -110,6 +124,125 @@ impl ConfigEnv for RealEnv {
     fn all_env_vars(&self) -> Vec<(String, String)> {
         std::env::vars().collect()
     }
+
+    /// Writes a temporary file next to `path`, syncs it, and renames it over
+    /// `path`. The temporary name is unique to this write, so concurrent
+    /// writers never share one; on Unix the directory is synced after the
+    /// rename and the file is only readable by its owner.
+    fn write_file(&self, path: &Path, contents: &str) -> io::Result<()> {
+        use std::io::Write;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+        ));
+        let tmp = path.with_file_name(tmp_name);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let written = options.open(&tmp).and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)
+        });
+        if written.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        written?;
+
+        #[cfg(unix)]
+        {
+            let dir = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+/// Async counterpart of [`ConfigEnv`], used by `ConfigBuilder::build_async()`.
+///
+/// Every `ConfigEnv` is also an `AsyncConfigEnv` whose futures complete
+/// immediately, so `RealEnv` and `MockEnv` work with async builds unchanged.
+/// Implement this trait directly to back file and environment access with an
+/// async runtime.
+///
+/// Only available with the `async` feature enabled.
+///
+/// # Example
+///
+/// ```ignore
+/// let env = MockEnv::new().with_file("config.toml", "port = 8080");
+/// let config = Config::<App>::builder()
+///     .source(Toml::file("config.toml"))
+///     .async_source(RemoteSource::new("https://config.internal/app"))
+///     .build_async_with_env(&env)
+///     .await?;
+/// ```
+#[cfg(feature = "async")]
+pub trait AsyncConfigEnv: Send + Sync {
+    /// Read a file's contents as a UTF-8 string.
+    fn read_file_async<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<String>>;
+
+    /// Check if a file exists.
+    fn file_exists_async<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, bool>;
+
+    /// Get an environment variable by name.
+    fn get_env_async<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Option<String>>;
+
+    /// Get all environment variables matching a prefix.
+    fn env_vars_with_prefix_async<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Vec<(String, String)>>;
+
+    /// Synchronous view of this environment.
+    ///
+    /// Used to run synchronous `Source`s, profile selection and interpolation
+    /// during an async build.
+    fn sync_env(&self) -> &dyn ConfigEnv;
+}
+
+/// A boxed future that can be sent across threads.
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+#[cfg(feature = "async")]
+impl<E: ConfigEnv> AsyncConfigEnv for E {
+    fn read_file_async<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<String>> {
+        Box::pin(std::future::ready(ConfigEnv::read_file(self, path)))
+    }
+
+    fn file_exists_async<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, bool> {
+        Box::pin(std::future::ready(ConfigEnv::file_exists(self, path)))
+    }
+
+    fn get_env_async<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Option<String>> {
+        Box::pin(std::future::ready(ConfigEnv::get_env(self, name)))
+    }
+
+    fn env_vars_with_prefix_async<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Vec<(String, String)>> {
+        Box::pin(std::future::ready(ConfigEnv::env_vars_with_prefix(
+            self, prefix,
+        )))
+    }
+
+    fn sync_env(&self) -> &dyn ConfigEnv {
+        self
+    }
 }
 
 /// Mock file state for testing.
@@ -243,6 +376,18 @@ impl MockEnv {
     }
 }
 
+/// Cloning snapshots the current files, variables, and directories, so a
+/// `MockEnv` can serve as the environment of an `Effect`.
+impl Clone for MockEnv {
+    fn clone(&self) -> Self {
+        Self {
+            files: RwLock::new(self.files.read().unwrap().clone()),
+            env_vars: RwLock::new(self.env_vars.read().unwrap().clone()),
+            directories: RwLock::new(self.directories.read().unwrap().clone()),
+        }
+    }
+}
+
 impl ConfigEnv for MockEnv {
     fn read_file(&self, path: &Path) -> io::Result<String> {
         let files = self.files.read().unwrap();
@@ -294,6 +439,11 @@ impl ConfigEnv for MockEnv {
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect()
     }
+
+    fn write_file(&self, path: &Path, contents: &str) -> io::Result<()> {
+        self.set_file(path, contents);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -381,6 +531,36 @@ mod tests {
         assert_eq!(env.get_env("VAR"), None);
     }
 
+    #[test]
+    fn test_real_env_write_file_replaces_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.snapshot.json");
+        let env = RealEnv::new();
+
+        env.write_file(&path, "first").unwrap();
+        env.write_file(&path, "second").unwrap();
+
+        assert_eq!(env.read_file(&path).unwrap(), "second");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_real_env_concurrent_writes_use_separate_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.snapshot.json");
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || RealEnv::new().write_file(path, &i.to_string()).unwrap());
+            }
+        });
+
+        let contents: u32 = RealEnv::new().read_file(&path).unwrap().parse().unwrap();
+        assert!(contents < 8);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn test_mock_env_directories() {
         let env = MockEnv::new()
@@ -5,6 +5,7 @@
 
 use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
 use stillwater::{NonEmptyVec, Semigroup, Validation};
 
@@ -46,6 +47,11 @@ impl SourceLocation {
         Self::new(format!("env:{}", var_name))
     }
 
+    /// Check whether this location is an environment variable.
+    pub(crate) fn is_env(&self) -> bool {
+        self.source.starts_with("env:")
+    }
+
     /// Create a location for a file with optional position.
     pub fn file(path: &str, line: Option<u32>, column: Option<u32>) -> Self {
         Self {
@@ -300,7 +306,7 @@ impl ConfigError {
             } => ConfigError::UnknownField {
                 path: prefix_path(prefix, &path),
                 source_location,
-                did_you_mean,
+                did_you_mean: did_you_mean.map(|p| prefix_path(prefix, &p)),
             },
             other => other, // SourceError and NoSources don't have paths
         }
@@ -308,7 +314,7 @@ impl ConfigError {
 }
 
 /// Helper function to prefix a path with a parent path.
-fn prefix_path(prefix: &str, path: &str) -> String {
+pub(crate) fn prefix_path(prefix: &str, path: &str) -> String {
     if path.is_empty() {
         prefix.to_string()
     } else if path.starts_with('[') {
@@ -383,6 +389,392 @@ impl fmt::Display for ConfigError {
 
 impl std::error::Error for ConfigError {}
 
+/// Non-fatal problems found while loading configuration.
+///
+/// Warnings never stop a build on their own. They are returned by
+/// [`ConfigBuilder::build_with_warnings`](crate::ConfigBuilder::build_with_warnings),
+/// printed to stderr by the other build methods when
+/// [`ConfigBuilder::print_warnings`](crate::ConfigBuilder::print_warnings) is set,
+/// and turned into errors by
+/// [`ConfigBuilder::warnings_as_errors`](crate::ConfigBuilder::warnings_as_errors).
+#[derive(Debug, Clone)]
+pub enum ConfigWarning {
+    /// A deprecated key was used
+    DeprecatedKey {
+        path: String,
+        source_location: Option<SourceLocation>,
+        replacement: Option<String>,
+        message: Option<String>,
+    },
+
+    /// A key was set but no field of the target type consumes it
+    UnusedKey {
+        path: String,
+        source_location: SourceLocation,
+        did_you_mean: Option<String>,
+    },
+
+    /// A value from a higher-trust source was overridden by a lower-trust one
+    LowerTrustOverride {
+        path: String,
+        source_location: SourceLocation,
+        overridden: SourceLocation,
+    },
+
+    /// An environment variable with the app's prefix matched no field
+    UnmatchedEnvVar {
+        var: String,
+        path: String,
+        did_you_mean: Option<String>,
+    },
+
+    /// A value failed a validation rule with `severity = "warn"`
+    ValidationWarning {
+        path: String,
+        source_location: Option<SourceLocation>,
+        value: Option<String>,
+        message: String,
+    },
+
+    /// Sources failed to load and the last-known-good snapshot was used instead
+    SnapshotUsed {
+        snapshot: String,
+        age: Duration,
+        errors: ConfigErrors,
+    },
+
+    /// The snapshot could not be written after a successful build
+    SnapshotNotWritten { snapshot: String, message: String },
+}
+
+impl ConfigWarning {
+    /// Get the configuration path that this warning relates to.
+    ///
+    /// Snapshot warnings concern the whole configuration; their path is empty.
+    pub fn path(&self) -> &str {
+        match self {
+            ConfigWarning::DeprecatedKey { path, .. }
+            | ConfigWarning::UnusedKey { path, .. }
+            | ConfigWarning::LowerTrustOverride { path, .. }
+            | ConfigWarning::UnmatchedEnvVar { path, .. }
+            | ConfigWarning::ValidationWarning { path, .. } => path,
+            ConfigWarning::SnapshotUsed { .. } | ConfigWarning::SnapshotNotWritten { .. } => "",
+        }
+    }
+
+    /// Get the source location of this warning, if any.
+    pub fn source_location(&self) -> Option<&SourceLocation> {
+        match self {
+            ConfigWarning::DeprecatedKey {
+                source_location, ..
+            }
+            | ConfigWarning::ValidationWarning {
+                source_location, ..
+            } => source_location.as_ref(),
+            ConfigWarning::UnusedKey {
+                source_location, ..
+            }
+            | ConfigWarning::LowerTrustOverride {
+                source_location, ..
+            } => Some(source_location),
+            ConfigWarning::UnmatchedEnvVar { .. }
+            | ConfigWarning::SnapshotUsed { .. }
+            | ConfigWarning::SnapshotNotWritten { .. } => None,
+        }
+    }
+
+    /// Get a suggestion for fixing this warning, if available.
+    pub fn suggestion(&self) -> Option<String> {
+        match self {
+            ConfigWarning::DeprecatedKey {
+                path,
+                replacement: Some(new),
+                ..
+            } => Some(format!("Rename '{}' to '{}'", path, new)),
+            ConfigWarning::UnusedKey {
+                path,
+                did_you_mean: Some(s),
+                ..
+            } => Some(format!("Change '{}' to '{}'", path, s)),
+            ConfigWarning::UnmatchedEnvVar {
+                var,
+                did_you_mean: Some(s),
+                ..
+            } => Some(format!("Check '{}'; it may be meant for '{}'", var, s)),
+            _ => None,
+        }
+    }
+
+    /// Classify a key that no field consumed: keys from environment variables
+    /// become `UnmatchedEnvVar`, everything else `UnusedKey`.
+    pub(crate) fn from_unknown_field(error: ConfigError) -> Option<Self> {
+        match error {
+            ConfigError::UnknownField {
+                path,
+                source_location,
+                did_you_mean,
+            } => Some(match source_location.source.strip_prefix("env:") {
+                Some(var) => ConfigWarning::UnmatchedEnvVar {
+                    var: var.to_string(),
+                    path,
+                    did_you_mean,
+                },
+                None => ConfigWarning::UnusedKey {
+                    path,
+                    source_location,
+                    did_you_mean,
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Downgrade a validation failure to a warning.
+    pub(crate) fn from_validation_error(error: ConfigError) -> Self {
+        match error {
+            ConfigError::ValidationError {
+                path,
+                source_location,
+                value,
+                message,
+            } => ConfigWarning::ValidationWarning {
+                path,
+                source_location,
+                value,
+                message,
+            },
+            ConfigError::CrossFieldError { paths, message } => ConfigWarning::ValidationWarning {
+                path: paths.join(", "),
+                source_location: None,
+                value: None,
+                message,
+            },
+            other => ConfigWarning::ValidationWarning {
+                path: other.path().unwrap_or_default().to_string(),
+                source_location: other.source_location().cloned(),
+                value: None,
+                message: other.to_string(),
+            },
+        }
+    }
+
+    /// Add a path prefix to this warning, as for [`ConfigError::with_path_prefix`].
+    pub fn with_path_prefix(self, prefix: &str) -> Self {
+        match self {
+            ConfigWarning::DeprecatedKey {
+                path,
+                source_location,
+                replacement,
+                message,
+            } => ConfigWarning::DeprecatedKey {
+                path: prefix_path(prefix, &path),
+                source_location,
+                replacement: replacement.map(|p| prefix_path(prefix, &p)),
+                message,
+            },
+            ConfigWarning::UnusedKey {
+                path,
+                source_location,
+                did_you_mean,
+            } => ConfigWarning::UnusedKey {
+                path: prefix_path(prefix, &path),
+                source_location,
+                did_you_mean: did_you_mean.map(|p| prefix_path(prefix, &p)),
+            },
+            ConfigWarning::LowerTrustOverride {
+                path,
+                source_location,
+                overridden,
+            } => ConfigWarning::LowerTrustOverride {
+                path: prefix_path(prefix, &path),
+                source_location,
+                overridden,
+            },
+            ConfigWarning::UnmatchedEnvVar {
+                var,
+                path,
+                did_you_mean,
+            } => ConfigWarning::UnmatchedEnvVar {
+                var,
+                path: prefix_path(prefix, &path),
+                did_you_mean: did_you_mean.map(|p| prefix_path(prefix, &p)),
+            },
+            ConfigWarning::ValidationWarning {
+                path,
+                source_location,
+                value,
+                message,
+            } => ConfigWarning::ValidationWarning {
+                path: prefix_path(prefix, &path),
+                source_location,
+                value,
+                message,
+            },
+            snapshot @ (ConfigWarning::SnapshotUsed { .. }
+            | ConfigWarning::SnapshotNotWritten { .. }) => snapshot,
+        }
+    }
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigWarning::DeprecatedKey {
+                path,
+                source_location,
+                replacement,
+                message,
+            } => {
+                if let Some(loc) = source_location {
+                    write!(f, "[{}] ", loc)?;
+                }
+                write!(f, "'{}' is deprecated", path)?;
+                if let Some(new) = replacement {
+                    write!(f, "; use '{}' instead", new)?;
+                }
+                if let Some(message) = message {
+                    write!(f, " ({})", message)?;
+                }
+                Ok(())
+            }
+            ConfigWarning::UnusedKey {
+                path,
+                source_location,
+                did_you_mean,
+            } => {
+                write!(f, "[{}] unused key '{}'", source_location, path)?;
+                if let Some(suggestion) = did_you_mean {
+                    write!(f, "; did you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            ConfigWarning::LowerTrustOverride {
+                path,
+                source_location,
+                overridden,
+            } => write!(
+                f,
+                "[{}] '{}' overrides a value from higher-trust source {}",
+                source_location, path, overridden
+            ),
+            ConfigWarning::UnmatchedEnvVar {
+                var, did_you_mean, ..
+            } => {
+                write!(f, "environment variable {} matches no field", var)?;
+                if let Some(suggestion) = did_you_mean {
+                    write!(f, "; did you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            ConfigWarning::ValidationWarning {
+                path,
+                source_location,
+                message,
+                ..
+            } => match source_location {
+                Some(loc) => write!(f, "[{}] '{}': {}", loc, path, message),
+                None => write!(f, "'{}': {}", path, message),
+            },
+            ConfigWarning::SnapshotUsed {
+                snapshot,
+                age,
+                errors,
+            } => {
+                write!(
+                    f,
+                    "[{}] using last-known-good snapshot written {} ago; sources failed to load: ",
+                    snapshot,
+                    crate::snapshot::format_age(*age)
+                )?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+            ConfigWarning::SnapshotNotWritten { snapshot, message } => {
+                write!(f, "[{}] snapshot not written: {}", snapshot, message)
+            }
+        }
+    }
+}
+
+impl From<ConfigWarning> for ConfigError {
+    /// Escalate a warning, e.g. under `warnings_as_errors`.
+    fn from(warning: ConfigWarning) -> Self {
+        match warning {
+            ConfigWarning::UnusedKey {
+                path,
+                source_location,
+                did_you_mean,
+            } => ConfigError::UnknownField {
+                path,
+                source_location,
+                did_you_mean,
+            },
+            ConfigWarning::UnmatchedEnvVar {
+                var,
+                path,
+                did_you_mean,
+            } => ConfigError::UnknownField {
+                path,
+                source_location: SourceLocation::new(format!("env:{}", var)),
+                did_you_mean,
+            },
+            ConfigWarning::DeprecatedKey {
+                path,
+                source_location,
+                replacement,
+                ..
+            } => ConfigError::ValidationError {
+                path,
+                source_location,
+                value: None,
+                message: match replacement {
+                    Some(new) => format!("deprecated key; use '{}' instead", new),
+                    None => "deprecated key".to_string(),
+                },
+            },
+            ConfigWarning::LowerTrustOverride {
+                path,
+                source_location,
+                overridden,
+            } => ConfigError::ValidationError {
+                path,
+                source_location: Some(source_location),
+                value: None,
+                message: format!("overrides a value from higher-trust source {}", overridden),
+            },
+            ConfigWarning::ValidationWarning {
+                path,
+                source_location,
+                value,
+                message,
+            } => ConfigError::ValidationError {
+                path,
+                source_location,
+                value,
+                message,
+            },
+            ConfigWarning::SnapshotUsed { snapshot, age, .. } => ConfigError::SourceError {
+                source_name: snapshot,
+                kind: SourceErrorKind::Other {
+                    message: format!(
+                        "sources failed to load; fell back to a snapshot written {} ago",
+                        crate::snapshot::format_age(age)
+                    ),
+                },
+            },
+            ConfigWarning::SnapshotNotWritten { snapshot, message } => ConfigError::SourceError {
+                source_name: snapshot,
+                kind: SourceErrorKind::IoError { message },
+            },
+        }
+    }
+}
+
 /// A non-empty collection of configuration errors.
 ///
 /// Uses `NonEmptyVec` from stillwater to guarantee at least one error exists.
@@ -1110,4 +1502,57 @@ mod tests {
         let paths: Vec<_> = prefixed.iter().filter_map(|e| e.path()).collect();
         assert_eq!(paths, vec!["database.host", "database.port"]);
     }
+
+    #[test]
+    fn test_warning_from_unknown_field() {
+        let from_env = ConfigWarning::from_unknown_field(ConfigError::UnknownField {
+            path: "prots".to_string(),
+            source_location: SourceLocation::env("APP_PROTS"),
+            did_you_mean: Some("port".to_string()),
+        })
+        .unwrap();
+        assert!(matches!(
+            &from_env,
+            ConfigWarning::UnmatchedEnvVar { var, .. } if var == "APP_PROTS"
+        ));
+        assert_eq!(from_env.path(), "prots");
+        assert!(from_env.source_location().is_none());
+
+        let warning = ConfigWarning::from_unknown_field(ConfigError::UnknownField {
+            path: "prot".to_string(),
+            source_location: SourceLocation::new("config.toml").with_line(3),
+            did_you_mean: Some("port".to_string()),
+        })
+        .unwrap()
+        .with_path_prefix("server");
+        assert_eq!(warning.path(), "server.prot");
+        assert_eq!(
+            warning.to_string(),
+            "[config.toml:3] unused key 'server.prot'; did you mean 'server.port'?"
+        );
+
+        let error = ConfigError::from(warning);
+        assert!(matches!(
+            error,
+            ConfigError::UnknownField { ref path, .. } if path == "server.prot"
+        ));
+    }
+
+    #[test]
+    fn test_deprecated_warning_display() {
+        let warning = ConfigWarning::DeprecatedKey {
+            path: "db_url".to_string(),
+            source_location: Some(SourceLocation::new("app.toml").with_line(2)),
+            replacement: Some("database.url".to_string()),
+            message: None,
+        };
+        assert_eq!(
+            warning.to_string(),
+            "[app.toml:2] 'db_url' is deprecated; use 'database.url' instead"
+        );
+        assert_eq!(
+            warning.suggestion().as_deref(),
+            Some("Rename 'db_url' to 'database.url'")
+        );
+    }
 }
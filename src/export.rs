@@ -0,0 +1,547 @@
+//! Exporting the effective configuration.
+//!
+//! Serializes merged `ConfigValues` back into a configuration file, so the
+//! final configuration can be inspected exactly as the application sees it or
+//! frozen as a snapshot. Each value can carry a comment naming the source it
+//! came from, and sensitive paths are redacted.
+//!
+//! Use it through [`TracedConfig::export`](crate::trace::TracedConfig::export):
+//!
+//! ```ignore
+//! use premortem::export::Format;
+//!
+//! let traced = Config::<AppConfig>::builder()
+//!     .source(Toml::file("config.toml"))
+//!     .source(Env::prefix("APP_"))
+//!     .build_traced()?;
+//!
+//! println!("{}", traced.export(Format::Toml));
+//! ```
+//!
+//! ```text
+//! [database]
+//! host = "db.internal" # env:APP_DATABASE_HOST
+//! password = "[REDACTED]" # config.toml:4
+//! port = 5432 # config.toml:3
+//! ```
+
+use serde_json::Value as JsonValue;
+
+use crate::redact::{RedactionPolicy, REDACTED};
+use crate::secret::is_secret_path;
+use crate::source::{in_subtree, ConfigValues};
+use crate::value::ConfigValue;
+
+/// Output format for an exported configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// TOML, with provenance as trailing `#` comments
+    #[cfg(feature = "toml")]
+    Toml,
+    /// Pretty-printed JSON. JSON has no comments, so provenance is omitted.
+    #[cfg(feature = "json")]
+    Json,
+    /// YAML, with provenance as trailing `#` comments
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+/// Options for exporting a configuration.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Annotate each value with the source it came from.
+    pub provenance: bool,
+    /// Replace sensitive values with `[REDACTED]`.
+    pub redact_sensitive: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            provenance: true,
+            redact_sensitive: true,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Default options: provenance comments on, redaction on.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to annotate values with their source.
+    pub fn with_provenance(mut self, provenance: bool) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    /// Set whether to redact sensitive values.
+    ///
+    /// Redacted values are written as strings, so a snapshot exported with
+    /// redaction only loads back if the redacted fields are overridden.
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact_sensitive = redact;
+        self
+    }
+}
+
+/// Where the values of a subtree came from, for provenance comments.
+#[cfg_attr(not(any(feature = "toml", feature = "yaml")), allow(dead_code))]
+struct Provenance<'a> {
+    values: &'a ConfigValues,
+    origin: &'a dyn Fn(&str, &ConfigValue) -> String,
+    enabled: bool,
+}
+
+#[cfg_attr(not(any(feature = "toml", feature = "yaml")), allow(dead_code))]
+impl Provenance<'_> {
+    /// Comment for the value at `path`: the distinct origins of every value
+    /// beneath it, or of the nearest ancestor stored as a single value.
+    fn comment(&self, path: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut origins: Vec<String> = Vec::new();
+        for (value_path, value) in self.values.iter() {
+            if value_path.contains(".__") || !in_subtree(value_path, path) {
+                continue;
+            }
+            let origin = (self.origin)(value_path, value);
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+
+        if origins.is_empty() {
+            // Arrays loaded as one value (e.g. `list_separator`) store their
+            // elements under the array's path
+            let mut ancestor = path;
+            while let Some(end) = ancestor.rfind(['.', '[']) {
+                ancestor = &ancestor[..end];
+                if let Some(value) = self.values.get(ancestor) {
+                    origins.push((self.origin)(ancestor, value));
+                    break;
+                }
+            }
+        }
+
+        (!origins.is_empty()).then(|| origins.join(", ").replace('\n', " "))
+    }
+}
+
+/// Pure function: render `values` in `format`.
+///
+/// `origin` names the source of a value for provenance comments; values
+/// `policy` redacts and values at `secrets` are redacted.
+pub(crate) fn export_values(
+    values: &ConfigValues,
+    origin: &dyn Fn(&str, &ConfigValue) -> String,
+    policy: &RedactionPolicy,
+    secrets: &[String],
+    format: Format,
+    options: &ExportOptions,
+) -> String {
+    let mut tree = values.to_json();
+    if options.redact_sensitive {
+        redact(&mut tree, "", &|path, leaf| {
+            is_secret_path(path, secrets)
+                || match leaf {
+                    JsonValue::String(s) => policy.redacts_text(path, s),
+                    _ => policy.redacts_path(path),
+                }
+        });
+    }
+
+    #[allow(unused_variables)]
+    let provenance = Provenance {
+        values,
+        origin,
+        enabled: options.provenance,
+    };
+
+    match format {
+        #[cfg(feature = "toml")]
+        Format::Toml => toml::render(&tree, &provenance),
+        #[cfg(feature = "json")]
+        Format::Json => {
+            let mut out = serde_json::to_string_pretty(&tree).unwrap_or_default();
+            out.push('\n');
+            out
+        }
+        #[cfg(feature = "yaml")]
+        Format::Yaml => yaml::render(&tree, &provenance),
+    }
+}
+
+/// Replace every leaf that `redacted` flags with the redaction placeholder.
+fn redact(value: &mut JsonValue, path: &str, redacted: &dyn Fn(&str, &JsonValue) -> bool) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                redact(child, &key_path(path, key), redacted);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                redact(child, &index_path(path, i), redacted);
+            }
+        }
+        JsonValue::Null => {}
+        leaf => {
+            if redacted(path, leaf) {
+                *leaf = JsonValue::String(REDACTED.to_string());
+            }
+        }
+    }
+}
+
+fn key_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn index_path(prefix: &str, index: usize) -> String {
+    format!("{}[{}]", prefix, index)
+}
+
+#[cfg(feature = "toml")]
+mod toml {
+    use serde_json::{Map, Value as JsonValue};
+    use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, Value};
+
+    use super::{index_path, key_path, Provenance};
+
+    pub(super) fn render(tree: &JsonValue, provenance: &Provenance<'_>) -> String {
+        let mut doc = DocumentMut::new();
+        if let JsonValue::Object(map) = tree {
+            fill_table(doc.as_table_mut(), map, "", provenance);
+        }
+        doc.to_string()
+    }
+
+    fn fill_table(
+        table: &mut Table,
+        map: &Map<String, JsonValue>,
+        prefix: &str,
+        provenance: &Provenance<'_>,
+    ) {
+        for (key, value) in map {
+            let path = key_path(prefix, key);
+            let item = match value {
+                JsonValue::Object(child) => {
+                    let mut sub = Table::new();
+                    // Skip the header of tables that only hold other tables
+                    sub.set_implicit(!child.is_empty());
+                    fill_table(&mut sub, child, &path, provenance);
+                    Item::Table(sub)
+                }
+                JsonValue::Array(items)
+                    if !items.is_empty() && items.iter().all(JsonValue::is_object) =>
+                {
+                    let mut tables = ArrayOfTables::new();
+                    for (i, item) in items.iter().enumerate() {
+                        let mut sub = Table::new();
+                        if let JsonValue::Object(child) = item {
+                            fill_table(&mut sub, child, &index_path(&path, i), provenance);
+                        }
+                        tables.push(sub);
+                    }
+                    Item::ArrayOfTables(tables)
+                }
+                _ => {
+                    // TOML has no null
+                    let Some(mut value) = toml_value(value) else {
+                        continue;
+                    };
+                    if let Some(comment) = provenance.comment(&path) {
+                        value.decor_mut().set_suffix(format!(" # {}", comment));
+                    }
+                    Item::Value(value)
+                }
+            };
+            table.insert(key, item);
+        }
+    }
+
+    fn toml_value(value: &JsonValue) -> Option<Value> {
+        Some(match value {
+            JsonValue::Null => return None,
+            JsonValue::Bool(b) => Value::from(*b),
+            JsonValue::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::from(i),
+                // TOML integers are signed; a string still reads back into a `u64`
+                (None, Some(u)) => Value::from(u.to_string()),
+                (None, None) => Value::from(n.as_f64()?),
+            },
+            JsonValue::String(s) => Value::from(s.as_str()),
+            JsonValue::Array(items) => {
+                Value::Array(items.iter().filter_map(toml_value).collect::<Array>())
+            }
+            JsonValue::Object(map) => {
+                let mut table = InlineTable::new();
+                for (key, value) in map {
+                    if let Some(value) = toml_value(value) {
+                        table.insert(key, value);
+                    }
+                }
+                Value::InlineTable(table)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "yaml")]
+mod yaml {
+    use serde_json::{Map, Value as JsonValue};
+
+    use super::{index_path, key_path, Provenance};
+
+    pub(super) fn render(tree: &JsonValue, provenance: &Provenance<'_>) -> String {
+        let mut out = String::new();
+        match tree {
+            JsonValue::Object(map) if !map.is_empty() => {
+                write_map(&mut out, map, "", 0, provenance)
+            }
+            _ => out.push_str("{}\n"),
+        }
+        out
+    }
+
+    fn write_map(
+        out: &mut String,
+        map: &Map<String, JsonValue>,
+        prefix: &str,
+        indent: usize,
+        provenance: &Provenance<'_>,
+    ) {
+        for (key, value) in map {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(&yaml_key(key));
+            out.push(':');
+            write_value(out, value, &key_path(prefix, key), indent, provenance);
+        }
+    }
+
+    fn write_seq(
+        out: &mut String,
+        items: &[JsonValue],
+        prefix: &str,
+        indent: usize,
+        provenance: &Provenance<'_>,
+    ) {
+        for (i, item) in items.iter().enumerate() {
+            out.push_str(&" ".repeat(indent));
+            out.push('-');
+            write_value(out, item, &index_path(prefix, i), indent, provenance);
+        }
+    }
+
+    /// Write the value that follows a `key:` or `-` already on the line.
+    fn write_value(
+        out: &mut String,
+        value: &JsonValue,
+        path: &str,
+        indent: usize,
+        provenance: &Provenance<'_>,
+    ) {
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                out.push('\n');
+                write_map(out, map, path, indent + 2, provenance);
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_seq(out, items, path, indent + 2, provenance);
+            }
+            scalar => {
+                out.push(' ');
+                out.push_str(&yaml_scalar(scalar));
+                if let Some(comment) = provenance.comment(path) {
+                    out.push_str(" # ");
+                    out.push_str(&comment);
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    /// Scalars are written as JSON, which YAML reads as flow scalars.
+    /// Strings are always quoted so `"yes"` or `"1.0"` keep their type.
+    fn yaml_scalar(value: &JsonValue) -> String {
+        serde_json::to_string(value).unwrap_or_default()
+    }
+
+    fn yaml_key(key: &str) -> String {
+        let plain = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            && !matches!(
+                key.to_ascii_lowercase().as_str(),
+                "true" | "false" | "null" | "yes" | "no" | "on" | "off" | "y" | "n"
+            );
+        if plain {
+            key.to_string()
+        } else {
+            serde_json::to_string(key).unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SourceLocation;
+    use crate::value::Value;
+
+    fn values() -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        let file = |line| SourceLocation::new("config.toml").with_line(line);
+        values.insert(
+            "database.host".to_string(),
+            ConfigValue::new(
+                Value::String("db".to_string()),
+                SourceLocation::new("env:APP_DATABASE_HOST"),
+            ),
+        );
+        values.insert(
+            "database.port".to_string(),
+            ConfigValue::new(Value::Integer(5432), file(3)),
+        );
+        values.insert(
+            "database.password".to_string(),
+            ConfigValue::new(Value::String("hunter2".to_string()), file(4)),
+        );
+        values.insert(
+            "hosts".to_string(),
+            ConfigValue::new(
+                Value::Array(vec![Value::from("a"), Value::from("b")]),
+                SourceLocation::new("env:APP_HOSTS"),
+            ),
+        );
+        values.insert(
+            "servers[0].name".to_string(),
+            ConfigValue::new(Value::from("web"), file(7)),
+        );
+        values.insert(
+            "servers.__len".to_string(),
+            ConfigValue::new(Value::Integer(1), file(6)),
+        );
+        values
+    }
+
+    fn source_origin(_: &str, value: &ConfigValue) -> String {
+        value.source.to_string()
+    }
+
+    #[test]
+    fn test_redaction_and_provenance_comment() {
+        let values = values();
+        let provenance = Provenance {
+            values: &values,
+            origin: &source_origin,
+            enabled: true,
+        };
+        assert_eq!(
+            provenance.comment("database.port").as_deref(),
+            Some("config.toml:3")
+        );
+        assert_eq!(
+            provenance.comment("database").as_deref(),
+            Some("env:APP_DATABASE_HOST, config.toml:4, config.toml:3")
+        );
+        assert_eq!(
+            provenance.comment("hosts[1]").as_deref(),
+            Some("env:APP_HOSTS")
+        );
+
+        let mut tree = values.to_json();
+        let policy = RedactionPolicy::default();
+        redact(&mut tree, "", &|path, _| policy.redacts_path(path));
+        assert_eq!(tree["database"]["password"], REDACTED);
+        assert_eq!(tree["database"]["host"], "db");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_export_toml() {
+        let out = export_values(
+            &values(),
+            &source_origin,
+            &RedactionPolicy::default(),
+            &[],
+            Format::Toml,
+            &ExportOptions::default(),
+        );
+        assert!(out.contains("hosts = [\"a\", \"b\"] # env:APP_HOSTS\n"));
+        assert!(out.contains("[database]\n"));
+        assert!(out.contains("port = 5432 # config.toml:3\n"));
+        assert!(out.contains("password = \"[REDACTED]\" # config.toml:4\n"));
+        assert!(out.contains("[[servers]]\nname = \"web\" # config.toml:7\n"));
+        assert!(!out.contains("hunter2"));
+        assert!(!out.contains("__len"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_export_toml_without_provenance_or_redaction() {
+        let options = ExportOptions::new()
+            .with_provenance(false)
+            .with_redaction(false);
+        let out = export_values(
+            &values(),
+            &source_origin,
+            &RedactionPolicy::default(),
+            &[],
+            Format::Toml,
+            &options,
+        );
+        assert!(out.contains("password = \"hunter2\"\n"));
+        assert!(!out.contains('#'));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_export_json() {
+        let out = export_values(
+            &values(),
+            &source_origin,
+            &RedactionPolicy::default(),
+            &[],
+            Format::Json,
+            &ExportOptions::default(),
+        );
+        let parsed: JsonValue = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed["database"]["port"], 5432);
+        assert_eq!(parsed["database"]["password"], REDACTED);
+        assert_eq!(parsed["servers"][0]["name"], "web");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_export_yaml() {
+        let out = export_values(
+            &values(),
+            &source_origin,
+            &RedactionPolicy::default(),
+            &[],
+            Format::Yaml,
+            &ExportOptions::default(),
+        );
+        assert!(out.contains("database:\n  host: \"db\" # env:APP_DATABASE_HOST\n"));
+        assert!(out.contains("hosts:\n  - \"a\" # env:APP_HOSTS\n"));
+        assert!(out.contains("servers:\n  -\n    name: \"web\" # config.toml:7\n"));
+
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&out).unwrap();
+        assert_eq!(parsed["database"]["port"], serde_yaml::Value::from(5432));
+        assert_eq!(parsed["hosts"][1], serde_yaml::Value::from("b"));
+    }
+}
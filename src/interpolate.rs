@@ -0,0 +1,546 @@
+//! Interpolation of `${...}` references in configuration values.
+//!
+//! Interpolation runs on the merged `ConfigValues`, after every source has been
+//! applied and before deserialization, so references see the final value of
+//! whatever they point at.
+//!
+//! # Syntax
+//!
+//! - `${env:NAME}` - the environment variable `NAME`, read through `ConfigEnv`
+//! - `${path.to.key}` - another configuration value
+//! - `${NAME}` - the environment variable `NAME`, when no configuration value
+//!   has that path
+//! - `${NAME:-fallback}` - any form, with a fallback used when it is unset
+//! - `$${` - a literal `${`
+//!
+//! A string that consists of a single reference takes the type of the value it
+//! refers to, so `port = "${defaults.port}"` stays an integer. Environment
+//! variables and fallbacks are typed the way the `Env` source types them, so
+//! `port = "${env:PORT:-8080}"` is an integer too. Tables and arrays cannot be
+//! referenced.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // config.toml:
+//! //   [database]
+//! //   host = "${env:DB_HOST:-localhost}"
+//! //   url = "postgres://${database.host}:5432"
+//!
+//! let config = Config::<AppConfig>::builder()
+//!     .source(Toml::file("config.toml"))
+//!     .interpolate()
+//!     .build()?;
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::env::ConfigEnv;
+use crate::error::{ConfigError, ConfigErrors, SourceLocation};
+use crate::source::{in_subtree, ConfigValues};
+use crate::sources::parse_scalar;
+use crate::value::{ConfigValue, Value};
+
+/// Resolve every `${...}` reference in `values`.
+///
+/// All unresolved references, malformed expressions, and reference cycles are
+/// reported together, each pointing at the value that contains them.
+pub fn interpolate(
+    values: &ConfigValues,
+    env: &dyn ConfigEnv,
+) -> Result<ConfigValues, ConfigErrors> {
+    let mut interpolator = Interpolator {
+        values,
+        env,
+        resolved: BTreeMap::new(),
+        stack: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let mut result = ConfigValues::empty();
+    for (path, config_value) in values.iter() {
+        if let Some(value) = interpolator.resolve_path(path) {
+            result.insert(
+                path.clone(),
+                ConfigValue::new(value, config_value.source.clone()),
+            );
+        }
+    }
+
+    match ConfigErrors::from_vec(interpolator.errors) {
+        Some(errors) => Err(errors),
+        None => Ok(result),
+    }
+}
+
+/// A piece of a string value: literal text or a `${...}` reference.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Reference {
+        target: String,
+        fallback: Option<String>,
+    },
+}
+
+/// Pure function: split a string into literal text and references.
+fn parse_segments(s: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find('$') {
+        literal.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some(after) = tail.strip_prefix("$${") {
+            literal.push_str("${");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("unclosed reference '{}'", tail))?;
+            let expr = &after[..end];
+            let (target, fallback) = match expr.split_once(":-") {
+                Some((target, fallback)) => (target, Some(fallback.to_string())),
+                None => (expr, None),
+            };
+            let target = target.trim();
+            if target.is_empty() || target == "env:" {
+                return Err(format!("empty reference '${{{}}}'", expr));
+            }
+
+            if !literal.is_empty() {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Reference {
+                target: target.to_string(),
+                fallback,
+            });
+            rest = &after[end + 1..];
+        } else {
+            literal.push('$');
+            rest = &tail[1..];
+        }
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// Render a scalar for embedding in a larger string.
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Integer(i) => Some(i.to_string()),
+        Value::UInteger(u) => Some(u.to_string()),
+        Value::Float(f) => Some(f.to_string()),
+        Value::Datetime(dt) => Some(dt.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Resolution state for one interpolation pass.
+struct Interpolator<'a> {
+    values: &'a ConfigValues,
+    env: &'a dyn ConfigEnv,
+    /// Resolved value of each path; `None` if it failed (already reported)
+    resolved: BTreeMap<String, Option<Value>>,
+    /// Paths currently being resolved, for cycle detection
+    stack: Vec<String>,
+    errors: Vec<ConfigError>,
+}
+
+impl Interpolator<'_> {
+    /// Resolve the value stored at `path`, or `None` if resolution failed.
+    fn resolve_path(&mut self, path: &str) -> Option<Value> {
+        if let Some(value) = self.resolved.get(path) {
+            return value.clone();
+        }
+
+        let config_value = self.values.get(path)?;
+        self.stack.push(path.to_string());
+        let value = self.resolve_value(path, &config_value.value, &config_value.source);
+        self.stack.pop();
+
+        self.resolved.insert(path.to_string(), value.clone());
+        value
+    }
+
+    fn resolve_value(
+        &mut self,
+        path: &str,
+        value: &Value,
+        source: &SourceLocation,
+    ) -> Option<Value> {
+        match value {
+            Value::String(s) => self.resolve_string(path, s, source),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.resolve_value(path, item, source))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array),
+            Value::Table(table) => table
+                .iter()
+                .map(|(k, v)| self.resolve_value(path, v, source).map(|v| (k.clone(), v)))
+                .collect::<Option<BTreeMap<_, _>>>()
+                .map(Value::Table),
+            other => Some(other.clone()),
+        }
+    }
+
+    fn resolve_string(&mut self, path: &str, s: &str, source: &SourceLocation) -> Option<Value> {
+        if !s.contains('$') {
+            return Some(Value::String(s.to_string()));
+        }
+
+        let segments = match parse_segments(s) {
+            Ok(segments) => segments,
+            Err(message) => {
+                self.error(path, s, source, message);
+                return None;
+            }
+        };
+
+        // A lone reference keeps the type of the value it points at
+        if let [Segment::Reference { target, fallback }] = segments.as_slice() {
+            return self.resolve_reference(path, s, source, target, fallback.as_deref(), true);
+        }
+
+        let mut result = String::new();
+        let mut ok = true;
+        for segment in &segments {
+            match segment {
+                Segment::Literal(text) => result.push_str(text),
+                Segment::Reference { target, fallback } => {
+                    match self.resolve_reference(
+                        path,
+                        s,
+                        source,
+                        target,
+                        fallback.as_deref(),
+                        false,
+                    ) {
+                        Some(value) => match scalar_to_string(&value) {
+                            Some(text) => result.push_str(&text),
+                            None => {
+                                self.error(
+                                    path,
+                                    s,
+                                    source,
+                                    format!(
+                                        "'{}' is a {} and cannot be embedded in a string",
+                                        target,
+                                        value.type_name()
+                                    ),
+                                );
+                                ok = false;
+                            }
+                        },
+                        None => ok = false,
+                    }
+                }
+            }
+        }
+
+        ok.then_some(Value::String(result))
+    }
+
+    /// Resolve one reference; with `typed`, text from the environment or a
+    /// fallback is parsed into a scalar.
+    fn resolve_reference(
+        &mut self,
+        path: &str,
+        raw: &str,
+        source: &SourceLocation,
+        target: &str,
+        fallback: Option<&str>,
+        typed: bool,
+    ) -> Option<Value> {
+        let text = |text: &str| match typed {
+            true => parse_scalar(text),
+            false => Value::String(text.to_string()),
+        };
+
+        if let Some(name) = target.strip_prefix("env:") {
+            return match self.env.get_env(name).filter(|v| !v.is_empty()) {
+                Some(value) => Some(text(&value)),
+                None => match fallback {
+                    Some(fallback) => Some(text(fallback)),
+                    None => {
+                        self.error(
+                            path,
+                            raw,
+                            source,
+                            format!("environment variable '{}' is not set", name),
+                        );
+                        None
+                    }
+                },
+            };
+        }
+
+        if let Some(position) = self.stack.iter().position(|p| p == target) {
+            let mut cycle = self.stack[position..].to_vec();
+            cycle.push(target.to_string());
+            self.error(
+                path,
+                raw,
+                source,
+                format!("reference cycle: {}", cycle.join(" -> ")),
+            );
+            return None;
+        }
+
+        if let Some(kind) = self.container_kind(target) {
+            self.error(
+                path,
+                raw,
+                source,
+                format!("'{}' is {} and cannot be interpolated", target, kind),
+            );
+            return None;
+        }
+
+        let exists = self
+            .values
+            .get(target)
+            .is_some_and(|cv| !cv.value.is_null());
+        if exists {
+            return self.resolve_path(target);
+        }
+
+        // `${NAME}` falls back to the environment when no value has that path
+        match (self.env.get_env(target).filter(|v| !v.is_empty()), fallback) {
+            (Some(value), _) => Some(text(&value)),
+            (None, Some(fallback)) => Some(text(fallback)),
+            (None, None) => {
+                self.error(
+                    path,
+                    raw,
+                    source,
+                    format!(
+                        "'{}' does not exist in the configuration and no environment variable of that name is set",
+                        target
+                    ),
+                );
+                None
+            }
+        }
+    }
+
+    /// `"a table"` or `"an array"` if `target` is stored as flattened children.
+    fn container_kind(&self, target: &str) -> Option<&'static str> {
+        let mut children = self
+            .values
+            .paths()
+            .filter(|p| p.as_str() != target && in_subtree(p, target));
+        let first = children.next()?;
+        let is_array = first.as_bytes().get(target.len()) == Some(&b'[')
+            || children.any(|p| p.as_bytes().get(target.len()) == Some(&b'['));
+        Some(if is_array { "an array" } else { "a table" })
+    }
+
+    fn error(&mut self, path: &str, raw: &str, source: &SourceLocation, message: String) {
+        self.errors.push(ConfigError::ParseError {
+            path: path.to_string(),
+            source_location: source.clone(),
+            expected_type: "resolvable reference".to_string(),
+            actual_value: raw.to_string(),
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+
+    fn values(entries: &[(&str, Value, u32)]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, value, line) in entries {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(
+                    value.clone(),
+                    SourceLocation::new("config.toml").with_line(*line),
+                ),
+            );
+        }
+        values
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        assert_eq!(
+            parse_segments("a${b}c").unwrap(),
+            vec![
+                Segment::Literal("a".into()),
+                Segment::Reference {
+                    target: "b".into(),
+                    fallback: None
+                },
+                Segment::Literal("c".into()),
+            ]
+        );
+        assert_eq!(
+            parse_segments("${env:X:-d:1}").unwrap(),
+            vec![Segment::Reference {
+                target: "env:X".into(),
+                fallback: Some("d:1".into())
+            }]
+        );
+        assert_eq!(
+            parse_segments("$${x} $5").unwrap(),
+            vec![Segment::Literal("${x} $5".into())]
+        );
+        assert!(parse_segments("${open").is_err());
+        assert!(parse_segments("${}").is_err());
+    }
+
+    #[test]
+    fn test_interpolates_env_and_paths() {
+        let env = MockEnv::new().with_env("DB_HOST", "db.internal");
+        let values = values(&[
+            ("db.host", Value::from("${env:DB_HOST}"), 1),
+            ("db.port", Value::Integer(5432), 2),
+            (
+                "db.url",
+                Value::from("postgres://${db.host}:${db.port}/app"),
+                3,
+            ),
+            ("db.user", Value::from("${env:DB_USER:-admin}"), 4),
+            ("pool.port", Value::from("${db.port}"), 5),
+        ]);
+
+        let result = interpolate(&values, &env).unwrap();
+        assert_eq!(
+            result.get("db.url").unwrap().value,
+            Value::from("postgres://db.internal:5432/app")
+        );
+        assert_eq!(result.get("db.user").unwrap().value, Value::from("admin"));
+        assert_eq!(result.get("pool.port").unwrap().value, Value::Integer(5432));
+        assert_eq!(result.get("db.url").unwrap().source.line, Some(3));
+    }
+
+    #[test]
+    fn test_unresolved_references_accumulate_with_locations() {
+        let values = values(&[
+            ("a", Value::from("${env:MISSING}"), 1),
+            ("b", Value::from("x-${no.such.key}"), 2),
+            ("c", Value::from("${no.such.key:-fine}"), 3),
+        ]);
+
+        let errors = interpolate(&values, &MockEnv::new()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        let lines: Vec<_> = errors
+            .iter()
+            .map(|e| e.source_location().unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![Some(1), Some(2)]);
+        assert!(errors.first().to_string().contains("MISSING"));
+    }
+
+    #[test]
+    fn test_detects_cycles() {
+        let values = values(&[
+            ("a", Value::from("${b}"), 1),
+            ("b", Value::from("${c}"), 2),
+            ("c", Value::from("${a}"), 3),
+            ("d", Value::from("${d}"), 4),
+        ]);
+
+        let errors = interpolate(&values, &MockEnv::new()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.first().to_string().contains("a -> b -> c -> a"));
+        assert!(errors.iter().any(|e| e.to_string().contains("d -> d")));
+    }
+
+    #[test]
+    fn test_bare_name_falls_back_to_environment() {
+        let env = MockEnv::new()
+            .with_env("DB_HOST", "db.internal")
+            .with_env("port", "1");
+        let values = values(&[
+            ("port", Value::Integer(5432), 1),
+            ("url", Value::from("postgres://${DB_HOST}:${port}"), 2),
+            ("user", Value::from("${DB_USER:-admin}"), 3),
+        ]);
+
+        let result = interpolate(&values, &env).unwrap();
+        assert_eq!(
+            result.get("url").unwrap().value,
+            Value::from("postgres://db.internal:5432")
+        );
+        assert_eq!(result.get("user").unwrap().value, Value::from("admin"));
+    }
+
+    #[test]
+    fn test_lone_environment_references_are_typed() {
+        let env = MockEnv::new()
+            .with_env("PORT", "8080")
+            .with_env("DEBUG", "on");
+        let values = values(&[
+            ("port", Value::from("${env:PORT}"), 1),
+            ("debug", Value::from("${DEBUG}"), 2),
+            ("workers", Value::from("${env:WORKERS:-4}"), 3),
+            ("label", Value::from("port-${PORT}"), 4),
+        ]);
+
+        let result = interpolate(&values, &env).unwrap();
+        assert_eq!(result.get("port").unwrap().value, Value::Integer(8080));
+        assert_eq!(result.get("debug").unwrap().value, Value::Bool(true));
+        assert_eq!(result.get("workers").unwrap().value, Value::Integer(4));
+        assert_eq!(result.get("label").unwrap().value, Value::from("port-8080"));
+    }
+
+    #[test]
+    fn test_bare_name_missing_everywhere_is_an_error() {
+        let values = values(&[("url", Value::from("${DB_HOST}"), 1)]);
+        let errors = interpolate(&values, &MockEnv::new()).unwrap_err();
+        assert!(errors
+            .first()
+            .to_string()
+            .contains("no environment variable of that name is set"));
+    }
+
+    #[test]
+    fn test_flattened_table_reference_is_an_error() {
+        let env = MockEnv::new().with_env("database", "ignored");
+        let values = values(&[
+            ("database.host", Value::from("db"), 1),
+            ("database.port", Value::Integer(5432), 2),
+            ("hosts[0]", Value::from("a"), 3),
+            ("hosts.__len", Value::Integer(1), 3),
+            ("copy", Value::from("${database}"), 4),
+            ("url", Value::from("db=${database:-x}"), 5),
+            ("first", Value::from("${hosts}"), 6),
+        ]);
+
+        let errors = interpolate(&values, &env).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 3);
+        assert!(messages[0].contains("'database' is a table and cannot be interpolated"));
+        assert!(messages[1].contains("'hosts' is an array and cannot be interpolated"));
+        assert!(messages[2].contains("'database' is a table and cannot be interpolated"));
+        assert!(!messages.iter().any(|m| m.contains("does not exist")));
+    }
+
+    #[test]
+    fn test_table_cannot_be_embedded() {
+        let mut table = BTreeMap::new();
+        table.insert("x".to_string(), Value::Integer(1));
+        let values = values(&[
+            ("t", Value::Table(table), 1),
+            ("s", Value::from("val=${t}"), 2),
+        ]);
+
+        let errors = interpolate(&values, &MockEnv::new()).unwrap_err();
+        assert!(errors.first().to_string().contains("cannot be embedded"));
+    }
+}
@@ -123,8 +123,22 @@
 //! # Module Structure
 //!
 //! - [`prelude`]: Convenient re-exports for common usage
+//! - [`alias`]: `KeyAlias` for renamed and deprecated keys
 //! - [`config`]: `Config` and `ConfigBuilder` for loading configuration
+//! - [`config_set`]: `ConfigSet` for loading several sections from one set of sources
+//! - [`diff`]: `ConfigDiff` between two configurations
+//! - [`docs`]: Markdown and HTML reference docs generated from `ConfigSchema`
+//! - [`de`]: Path-aware deserializer that reports every type error
+//! - [`dynamic`]: `DynamicConfig` for untyped configuration checked by a runtime schema
+//! - [`export`]: Export the merged configuration as TOML, JSON, or YAML
 //! - [`error`]: Error types (`ConfigError`, `ConfigErrors`, `ConfigValidation`)
+//! - [`interpolate`]: `${env:NAME}` and `${path.to.key}` references in values
+//! - [`lock`]: `config.lock` files that catch configuration drift in CI
+//! - [`redact`]: `RedactionPolicy` for which values are hidden in output
+//! - [`schema`]: JSON Schema generation with `ConfigSchema`
+//! - [`secret`]: `Secret<T>` for values that must never be printed
+//! - [`snapshot`]: Last-known-good snapshots to fall back on when sources fail
+//! - [`units`]: `Duration` and `ByteSize` written with units, like `30s` or `512MiB`
 //! - [`value`]: `Value` enum for intermediate representation
 //! - [`source`]: `Source` trait and `ConfigValues` container
 //! - [`mod@env`]: `ConfigEnv` trait and `MockEnv` for testing
@@ -142,34 +156,67 @@
 //!
 //! These are re-exported from the prelude for convenience.
 
+pub mod alias;
 pub mod config;
+pub mod config_set;
+pub mod de;
+pub mod diff;
+pub mod docs;
+pub mod dynamic;
 pub mod env;
 pub mod error;
+pub mod export;
+pub mod interpolate;
+pub mod lock;
 pub mod prelude;
 pub mod pretty;
+mod profile;
+pub mod redact;
+pub mod schema;
+pub mod secret;
+pub mod snapshot;
 pub mod source;
 pub mod sources;
 pub mod trace;
+pub mod units;
 pub mod validate;
 pub mod value;
 #[cfg(feature = "watch")]
 pub mod watch;
 
 // Re-exports for convenience
+pub use alias::KeyAlias;
 pub use config::{Config, ConfigBuilder};
+pub use config_set::{ConfigSet, ConfigSetBuilder};
+pub use diff::{Change, ConfigDiff};
+pub use docs::ConfigDocs;
+pub use dynamic::{DynamicConfig, DynamicSchema};
 pub use env::{ConfigEnv, MockEnv, RealEnv};
 pub use error::{
     group_by_source, ConfigError, ConfigErrors, ConfigValidation, ConfigValidationExt,
-    SourceErrorKind, SourceLocation,
+    ConfigWarning, SourceErrorKind, SourceLocation,
+};
+pub use export::{ExportOptions, Format};
+pub use lock::ConfigLock;
+pub use pretty::{
+    format_report, format_warnings, print_report, print_warnings, ColorOption, PrettyPrintOptions,
+    ValidationExt,
+};
+pub use redact::{RedactionPolicy, ValueDetector};
+pub use schema::ConfigSchema;
+pub use secret::Secret;
+pub use snapshot::SnapshotInfo;
+pub use source::{
+    merge_config_values, merge_config_values_with, ConfigValues, MergeAction, MergeStrategy,
+    Source, Trust,
 };
-pub use pretty::{ColorOption, PrettyPrintOptions, ValidationExt};
-pub use source::{merge_config_values, ConfigValues, Source};
 pub use trace::{TraceBuilder, TracedConfig, TracedValue, ValueTrace};
+pub use units::{ByteSize, Duration};
 pub use validate::validators;
 pub use validate::{
-    current_source_location, custom, from_predicate, validate_field, validate_nested,
-    validate_optional_nested, validate_with_predicate, with_validation_context, SourceLocationMap,
-    Validate, ValidationContext, Validator, When,
+    collect_warnings, current_source_location, custom, from_predicate, validate_field,
+    validate_nested, validate_optional_nested, validate_with_predicate, with_validation_context,
+    SourceLocationMap, Validate, ValidationContext, Validator, Warn, When,
 };
 pub use value::{ConfigValue, Value};
 
@@ -181,14 +228,20 @@ pub use sources::Json;
 pub use sources::Toml;
 #[cfg(feature = "yaml")]
 pub use sources::Yaml;
-pub use sources::{Defaults, PartialDefaults};
+pub use sources::{Defaults, Mounted, PartialDefaults, Trusted};
 
 // Re-export watch types
 #[cfg(feature = "watch")]
 pub use watch::{ConfigEvent, ConfigWatcher, WatchedConfig};
 
+// Re-export async types
+#[cfg(feature = "async")]
+pub use env::{AsyncConfigEnv, BoxFuture};
+#[cfg(feature = "async")]
+pub use source::AsyncSource;
+
 // Re-export stillwater types that are commonly used
-pub use stillwater::{NonEmptyVec, Semigroup, Validation};
+pub use stillwater::{Effect, EffectExt, NonEmptyVec, Semigroup, Validation};
 
 // Re-export stillwater predicate module and types (0.13.0+)
 pub use stillwater::predicate::{self, Predicate, PredicateExt};
@@ -197,6 +250,10 @@ pub use stillwater::predicate::{self, Predicate, PredicateExt};
 #[cfg(feature = "derive")]
 pub use premortem_derive::Validate as DeriveValidate;
 
+// Re-export derive macro for JSON Schema generation
+#[cfg(feature = "derive")]
+pub use premortem_derive::ConfigSchema as DeriveConfigSchema;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,455 @@
+//! Lock files for detecting configuration drift.
+//!
+//! A [`ConfigLock`] records every resolved path of a traced configuration with
+//! a hash of its value and the source that set it. Committed next to the
+//! configuration, it lets CI fail when the effective configuration for an
+//! environment changes without review:
+//!
+//! ```ignore
+//! use premortem::lock::{verify_lock, write_lock};
+//!
+//! #[test]
+//! fn production_config_is_locked() {
+//!     let traced = Config::<AppConfig>::builder()
+//!         .source(Toml::file("config/production.toml"))
+//!         .build_traced()
+//!         .unwrap();
+//!
+//!     if std::env::var_os("UPDATE_CONFIG_LOCK").is_some() {
+//!         write_lock(&traced, "config/production.lock").unwrap();
+//!     }
+//!     verify_lock(&traced, "config/production.lock").unwrap();
+//! }
+//! ```
+//!
+//! The lock is JSON sorted by path, so it only changes when the configuration
+//! does. Values never appear in it: non-sensitive values are stored as a
+//! hash, and values the configuration redacts (secrets and the
+//! [`RedactionPolicy`](crate::RedactionPolicy)) as `[REDACTED]`, so only
+//! their presence and source are checked. Sources are recorded without line
+//! numbers, so reformatting a file is not drift.
+//!
+//! [`verify_lock`] reports each drifted path as its own `ConfigError`:
+//!
+//! ```text
+//! [config/production.toml] 'database.pool_size': value changed since the lock was written
+//! [env:APP_LOG_LEVEL] 'log.level': now set by env:APP_LOG_LEVEL instead of config/production.toml
+//! [config/production.toml] 'cache.ttl': not in the lock file
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::env::{ConfigEnv, RealEnv};
+use crate::error::{ConfigError, ConfigErrors, SourceErrorKind, SourceLocation};
+use crate::redact::REDACTED;
+use crate::source::{value_to_json, ConfigValues};
+use crate::trace::TracedConfig;
+use crate::value::{ConfigValue, Value};
+
+/// Version of the lock file format.
+const FORMAT_VERSION: u32 = 1;
+
+/// The locked state of a configuration: a hash and source per resolved path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigLock {
+    version: u32,
+    entries: BTreeMap<String, LockEntry>,
+}
+
+/// The locked state of a single path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    /// Hash of the value, or `[REDACTED]` for redacted values
+    pub hash: String,
+    /// Source that set the value, with its profile if any
+    pub source: String,
+}
+
+impl LockEntry {
+    /// Check if the value was redacted, so only its source is locked.
+    pub fn is_redacted(&self) -> bool {
+        self.hash == REDACTED
+    }
+}
+
+impl ConfigLock {
+    /// Lock the resolved values of a traced configuration.
+    pub fn from_traced<T>(traced: &TracedConfig<T>) -> Self {
+        let origin = |path: &str, value: &ConfigValue| match traced.trace(path) {
+            Some(trace) => match &trace.final_value.profile {
+                Some(profile) => format!("{} ({})", trace.final_value.source.source, profile),
+                None => trace.final_value.source.source.clone(),
+            },
+            None => value.source.source.clone(),
+        };
+        let redacted = |path: &str, value: &ConfigValue| {
+            traced.is_secret(path) || traced.redaction_policy().redacts(path, &value.value)
+        };
+        lock_values(traced.values(), &origin, &redacted)
+    }
+
+    /// Parse a lock file; `name` identifies it in errors.
+    pub fn parse(content: &str, name: &str) -> Result<Self, ConfigErrors> {
+        let invalid = |message: String| {
+            ConfigErrors::single(ConfigError::SourceError {
+                source_name: name.to_string(),
+                kind: SourceErrorKind::ParseError {
+                    message,
+                    line: None,
+                    column: None,
+                },
+            })
+        };
+        let lock: Self = serde_json::from_str(content)
+            .map_err(|e| invalid(format!("invalid lock file: {}", e)))?;
+        if lock.version != FORMAT_VERSION {
+            return Err(invalid(format!(
+                "unsupported lock file version {}",
+                lock.version
+            )));
+        }
+        Ok(lock)
+    }
+
+    /// Get the locked state of `path`.
+    pub fn get(&self, path: &str) -> Option<&LockEntry> {
+        self.entries.get(path)
+    }
+
+    /// Iterate over the locked paths in order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &LockEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_str(), entry))
+    }
+
+    /// Get the number of locked paths.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no paths are locked.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pure function: compare `actual` against this lock, one error per
+    /// drifted path.
+    pub fn drift(&self, actual: &ConfigLock) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        for (path, locked) in &self.entries {
+            match actual.entries.get(path) {
+                None => errors.push(drift_error(
+                    path,
+                    &locked.source,
+                    "in the lock file but no longer set",
+                )),
+                Some(current) => {
+                    if let Some(message) = entry_drift(locked, current) {
+                        errors.push(drift_error(path, &current.source, &message));
+                    }
+                }
+            }
+        }
+        for (path, current) in &actual.entries {
+            if !self.entries.contains_key(path) {
+                errors.push(drift_error(path, &current.source, "not in the lock file"));
+            }
+        }
+
+        errors
+    }
+
+    /// Pure function: check that `actual` matches this lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns every drifted path, as collected by [`drift`](Self::drift).
+    pub fn verify(&self, actual: &ConfigLock) -> Result<(), ConfigErrors> {
+        match ConfigErrors::from_vec(self.drift(actual)) {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Renders the lock file: pretty JSON, sorted by path, with a trailing newline.
+impl fmt::Display for ConfigLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
+        writeln!(f, "{}", json)
+    }
+}
+
+/// Write the lock file for `traced` to `path`.
+pub fn write_lock<T>(traced: &TracedConfig<T>, path: impl AsRef<Path>) -> Result<(), ConfigErrors> {
+    write_lock_with_env(traced, path, &RealEnv::new())
+}
+
+/// Write the lock file for `traced` to `path` with a custom environment.
+pub fn write_lock_with_env<T>(
+    traced: &TracedConfig<T>,
+    path: impl AsRef<Path>,
+    env: &dyn ConfigEnv,
+) -> Result<(), ConfigErrors> {
+    let path = path.as_ref();
+    env.write_file(path, &ConfigLock::from_traced(traced).to_string())
+        .map_err(|e| {
+            ConfigErrors::single(ConfigError::SourceError {
+                source_name: path.display().to_string(),
+                kind: SourceErrorKind::IoError {
+                    message: e.to_string(),
+                },
+            })
+        })
+}
+
+/// Check `traced` against the lock file at `path`.
+///
+/// # Errors
+///
+/// Returns an error for each path that was added, removed, changed value, or
+/// moved to another source since the lock was written, or a `SourceError` if
+/// the lock file cannot be read.
+pub fn verify_lock<T>(
+    traced: &TracedConfig<T>,
+    path: impl AsRef<Path>,
+) -> Result<(), ConfigErrors> {
+    verify_lock_with_env(traced, path, &RealEnv::new())
+}
+
+/// Check `traced` against the lock file at `path` with a custom environment.
+pub fn verify_lock_with_env<T>(
+    traced: &TracedConfig<T>,
+    path: impl AsRef<Path>,
+    env: &dyn ConfigEnv,
+) -> Result<(), ConfigErrors> {
+    let path = path.as_ref();
+    let name = path.display().to_string();
+    let content = env.read_file(path).map_err(|e| {
+        ConfigErrors::single(ConfigError::SourceError {
+            source_name: name.clone(),
+            kind: match e.kind() {
+                std::io::ErrorKind::NotFound => SourceErrorKind::NotFound { path: name.clone() },
+                _ => SourceErrorKind::IoError {
+                    message: e.to_string(),
+                },
+            },
+        })
+    })?;
+    ConfigLock::parse(&content, &name)?.verify(&ConfigLock::from_traced(traced))
+}
+
+/// Pure function: lock `values`, naming each value's source with `origin`
+/// and leaving out the values `redacted` flags.
+///
+/// Array length metadata (`__len`) is not locked; added or removed elements
+/// show up as their own paths.
+fn lock_values(
+    values: &ConfigValues,
+    origin: &dyn Fn(&str, &ConfigValue) -> String,
+    redacted: &dyn Fn(&str, &ConfigValue) -> bool,
+) -> ConfigLock {
+    let entries = values
+        .iter()
+        .filter(|(path, _)| !path.contains(".__"))
+        .map(|(path, value)| {
+            let hash = if redacted(path, value) {
+                REDACTED.to_string()
+            } else {
+                hash_value(&value.value)
+            };
+            let entry = LockEntry {
+                hash,
+                source: origin(path, value),
+            };
+            (path.clone(), entry)
+        })
+        .collect();
+    ConfigLock {
+        version: FORMAT_VERSION,
+        entries,
+    }
+}
+
+/// Pure function: a stable hash of `value` (64-bit FNV-1a over its JSON form).
+///
+/// JSON keeps `1`, `1.0` and `"1"` apart, and unlike `std::hash` the result
+/// does not depend on the Rust version.
+fn hash_value(value: &Value) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = value_to_json(value)
+        .to_string()
+        .bytes()
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+    format!("{:016x}", hash)
+}
+
+/// Pure function: describe how a path's entry changed, if it did.
+///
+/// Redacted values are only compared by source.
+fn entry_drift(locked: &LockEntry, current: &LockEntry) -> Option<String> {
+    let value_changed =
+        !locked.is_redacted() && !current.is_redacted() && locked.hash != current.hash;
+    let moved = (locked.source != current.source)
+        .then(|| format!("now set by {} instead of {}", current.source, locked.source));
+    match (value_changed, moved) {
+        (false, None) => None,
+        (true, None) => Some("value changed since the lock was written".to_string()),
+        (false, Some(moved)) => Some(moved),
+        (true, Some(moved)) => Some(format!(
+            "value changed since the lock was written; {}",
+            moved
+        )),
+    }
+}
+
+/// A drift error at `path`, attributed to `source`.
+fn drift_error(path: &str, source: &str, message: &str) -> ConfigError {
+    ConfigError::ValidationError {
+        path: path.to_string(),
+        source_location: Some(SourceLocation::new(source)),
+        value: None,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::env::MockEnv;
+
+    fn values(entries: &[(&str, Value, &str)]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, value, source) in entries {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(value.clone(), SourceLocation::new(*source).with_line(3)),
+            );
+        }
+        values
+    }
+
+    fn lock(entries: &[(&str, Value, &str)]) -> ConfigLock {
+        lock_values(
+            &values(entries),
+            &|_, value| value.source.source.clone(),
+            &|path, _| path.ends_with("password"),
+        )
+    }
+
+    fn base() -> Vec<(&'static str, Value, &'static str)> {
+        vec![
+            ("database.host", Value::from("db"), "config.toml"),
+            ("database.password", Value::from("hunter2"), "config.toml"),
+            ("database.pool_size", Value::from(10), "config.toml"),
+            ("hosts[0]", Value::from("a"), "config.toml"),
+            ("hosts.__len", Value::from(1), "config.toml"),
+        ]
+    }
+
+    #[test]
+    fn test_lock_is_sorted_redacted_and_stable() {
+        let locked = lock(&base());
+        let rendered = locked.to_string();
+
+        assert_eq!(locked.len(), 4);
+        assert!(locked.get("hosts.__len").is_none());
+        assert!(locked.get("database.password").unwrap().is_redacted());
+        assert!(!rendered.contains("hunter2"));
+        assert!(!rendered.contains("config.toml:3"));
+        assert!(rendered.find("database.host").unwrap() < rendered.find("hosts[0]").unwrap());
+
+        assert_eq!(ConfigLock::parse(&rendered, "config.lock").unwrap(), locked);
+        assert_eq!(lock(&base()).to_string(), rendered);
+    }
+
+    #[test]
+    fn test_hash_value_distinguishes_types() {
+        assert_eq!(hash_value(&Value::from(1)), hash_value(&Value::from(1)));
+        assert_ne!(hash_value(&Value::from(1)), hash_value(&Value::from(1.0)));
+        assert_ne!(hash_value(&Value::from(1)), hash_value(&Value::from("1")));
+        assert_eq!(hash_value(&Value::from(1)).len(), 16);
+    }
+
+    #[test]
+    fn test_drift_names_each_changed_path() {
+        let locked = lock(&base());
+        let actual = lock(&[
+            ("database.host", Value::from("db"), "env:APP_DATABASE_HOST"),
+            ("database.password", Value::from("rotated"), "config.toml"),
+            ("database.pool_size", Value::from(20), "config.toml"),
+            ("cache.ttl", Value::from(60), "config.toml"),
+        ]);
+
+        let drift: Vec<String> = locked
+            .drift(&actual)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(drift.len(), 4, "{:#?}", drift);
+        assert!(drift[0].contains("'database.host'"));
+        assert!(drift[0].contains("now set by env:APP_DATABASE_HOST instead of config.toml"));
+        assert!(drift[1].contains("'database.pool_size'"));
+        assert!(drift[1].contains("value changed since the lock was written"));
+        assert!(drift[2].contains("'hosts[0]'"));
+        assert!(drift[2].contains("no longer set"));
+        assert!(drift[3].contains("'cache.ttl'"));
+        assert!(drift[3].contains("not in the lock file"));
+
+        assert!(locked.verify(&lock(&base())).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_versions() {
+        let errors =
+            ConfigLock::parse(r#"{"version": 2, "entries": {}}"#, "config.lock").unwrap_err();
+        assert!(errors
+            .first()
+            .to_string()
+            .contains("unsupported lock file version 2"));
+    }
+
+    #[test]
+    fn test_write_and_verify_lock() {
+        let traced = TracedConfig::new(Config::new(()), BTreeMap::new()).with_values(values(&[
+            ("api_token", Value::from("s3cr3t"), "config.toml"),
+            ("port", Value::from(8080), "config.toml"),
+        ]));
+        let env = MockEnv::new();
+
+        assert!(matches!(
+            verify_lock_with_env(&traced, "config.lock", &env)
+                .unwrap_err()
+                .first(),
+            ConfigError::SourceError {
+                kind: SourceErrorKind::NotFound { .. },
+                ..
+            }
+        ));
+
+        write_lock_with_env(&traced, "config.lock", &env).unwrap();
+        let written = env.read_file(Path::new("config.lock")).unwrap();
+        assert!(written.contains("\"api_token\""));
+        assert!(!written.contains("s3cr3t"));
+        verify_lock_with_env(&traced, "config.lock", &env).unwrap();
+
+        let changed = TracedConfig::new(Config::new(()), BTreeMap::new()).with_values(values(&[
+            ("api_token", Value::from("s3cr3t"), "config.toml"),
+            ("port", Value::from(9090), "config.toml"),
+        ]));
+        let errors = verify_lock_with_env(&changed, "config.lock", &env).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().path(), Some("port"));
+    }
+}
@@ -96,6 +96,11 @@ pub use stillwater::Semigroup;
 /// preventing "empty error list" bugs.
 pub use stillwater::NonEmptyVec;
 
+/// Deferred computation over an environment. Returned by `Source::load_effect()`
+/// and `ConfigBuilder::into_effect()`; `EffectExt` adds `map_err`, `or_else`, and
+/// friends.
+pub use stillwater::{Effect, EffectExt};
+
 // Re-export stillwater predicates for composable validation (stillwater 0.13.0+)
 pub use stillwater::predicate::prelude::*;
 
@@ -128,6 +133,9 @@ pub use crate::error::SourceLocation;
 /// Kinds of source loading errors.
 pub use crate::error::SourceErrorKind;
 
+/// Non-fatal diagnostic returned by `ConfigBuilder::build_with_warnings()`.
+pub use crate::error::ConfigWarning;
+
 /// Group errors by their source for organized reporting.
 pub use crate::error::group_by_source;
 
@@ -143,6 +151,12 @@ pub use crate::config::Config;
 /// Builder for constructing configuration from multiple sources.
 pub use crate::config::ConfigBuilder;
 
+/// Several typed sections loaded from one merged set of sources.
+pub use crate::config_set::{ConfigSet, ConfigSetBuilder};
+
+/// Untyped configuration validated against a runtime schema.
+pub use crate::dynamic::{DynamicConfig, DynamicSchema};
+
 // ============================================================================
 // Sources
 // ============================================================================
@@ -156,6 +170,9 @@ pub use crate::source::ConfigValues;
 /// Pure function to merge multiple ConfigValues by priority.
 pub use crate::source::merge_config_values;
 
+/// Per-path strategy for merging arrays and tables across sources.
+pub use crate::source::MergeStrategy;
+
 /// JSON file configuration source (requires `json` feature).
 #[cfg(feature = "json")]
 pub use crate::sources::Json;
@@ -177,6 +194,12 @@ pub use crate::sources::Defaults;
 /// Partial defaults builder for specific paths.
 pub use crate::sources::PartialDefaults;
 
+/// A source mounted under a sub-path with `Source::at()`.
+pub use crate::sources::Mounted;
+
+/// How much a source is trusted; set with `Source::with_trust()`.
+pub use crate::source::Trust;
+
 // ============================================================================
 // Environment abstractions
 // ============================================================================
@@ -199,6 +222,15 @@ pub use crate::env::MockEnv;
 /// Implement this trait to add custom validation logic to your config types.
 pub use crate::validate::Validate;
 
+/// Types that can describe themselves as a JSON Schema.
+pub use crate::schema::ConfigSchema;
+
+/// Differences between two configurations.
+pub use crate::diff::ConfigDiff;
+
+/// Markdown and HTML reference docs for a `ConfigSchema` type.
+pub use crate::docs::ConfigDocs;
+
 /// Trait for individual validators.
 pub use crate::validate::Validator;
 
@@ -264,6 +296,8 @@ pub mod validators {
     pub use crate::validate::validators::{
         // Path validators
         DirExists,
+        // Unit validators
+        DurationRange,
         // Collection validators
         Each,
         // String validators
@@ -284,6 +318,7 @@ pub mod validators {
         Pattern,
         Positive,
         Range,
+        SizeRange,
         Url,
     };
 }
@@ -298,6 +333,15 @@ pub use crate::value::Value;
 /// Configuration value with source location tracking.
 pub use crate::value::ConfigValue;
 
+/// Configuration value that is redacted when printed and zeroized on drop.
+pub use crate::secret::Secret;
+
+/// Durations and byte sizes written with units, like `30s` or `512MiB`.
+pub use crate::units::{ByteSize, Duration};
+
+/// Rules for which values are redacted when printed.
+pub use crate::redact::{RedactionPolicy, ValueDetector};
+
 // ============================================================================
 // Tracing (debugging configuration origin)
 // ============================================================================
@@ -345,6 +389,14 @@ pub use crate::watch::ConfigWatcher;
 #[cfg(feature = "watch")]
 pub use crate::watch::ConfigEvent;
 
+/// Configuration source that loads asynchronously (requires `async` feature).
+#[cfg(feature = "async")]
+pub use crate::source::AsyncSource;
+
+/// Async counterpart of `ConfigEnv` (requires `async` feature).
+#[cfg(feature = "async")]
+pub use crate::env::AsyncConfigEnv;
+
 // ============================================================================
 // Derive macro
 // ============================================================================
@@ -353,6 +405,10 @@ pub use crate::watch::ConfigEvent;
 #[cfg(feature = "derive")]
 pub use premortem_derive::Validate as DeriveValidate;
 
+/// Derive macro for `ConfigSchema` trait (requires `derive` feature).
+#[cfg(feature = "derive")]
+pub use premortem_derive::ConfigSchema as DeriveConfigSchema;
+
 #[cfg(test)]
 mod tests {
     use super::*;
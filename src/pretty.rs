@@ -1,7 +1,8 @@
 //! Pretty printing for configuration errors.
 //!
 //! This module provides colorized, grouped error output with support for
-//! suggestions, redaction of sensitive values, and truncation.
+//! suggestions, redaction of sensitive values, and truncation. Which values
+//! are sensitive is decided by the [`RedactionPolicy`] in the options.
 //!
 //! # Example
 //!
@@ -27,14 +28,30 @@
 //! Hints:
 //!   • Add 'database.host' to your configuration
 //! ```
+//!
+//! Warnings are rendered the same way, in yellow, with [`format_warnings`]:
+//!
+//! ```text
+//! Configuration warnings (1):
+//!
+//!     • [config.toml:7] unused key 'databse.host'; did you mean 'database.host'?
+//! ```
+//!
+//! [`format_report`] prints errors and warnings (such as failures of
+//! `severity = "warn"` rules) together, each in its own section.
 
 use std::io::Write;
 
-use crate::error::{group_by_source, ConfigError, ConfigErrors, ConfigValidation};
+use crate::error::{group_by_source, ConfigError, ConfigErrors, ConfigValidation, ConfigWarning};
+use crate::redact::{RedactionPolicy, REDACTED};
 use stillwater::Validation;
 
 /// Options for pretty printing errors.
+///
+/// Fields may be added in minor releases, so build options from
+/// [`Default`] or the constructors and change them with the `with_*` methods.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PrettyPrintOptions {
     /// Enable colored output (auto-detected by default).
     pub color: ColorOption,
@@ -46,6 +63,10 @@ pub struct PrettyPrintOptions {
     pub max_errors: Option<usize>,
     /// Redact sensitive values.
     pub redact_sensitive: bool,
+    /// Which values count as sensitive.
+    pub redaction: RedactionPolicy,
+    /// Include the warnings section in reports.
+    pub show_warnings: bool,
 }
 
 impl Default for PrettyPrintOptions {
@@ -56,6 +77,8 @@ impl Default for PrettyPrintOptions {
             show_suggestions: true,
             max_errors: Some(20),
             redact_sensitive: true,
+            redaction: RedactionPolicy::default(),
+            show_warnings: true,
         }
     }
 }
@@ -106,6 +129,18 @@ impl PrettyPrintOptions {
         self.redact_sensitive = redact;
         self
     }
+
+    /// Set which values count as sensitive.
+    pub fn with_redaction_policy(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = policy;
+        self
+    }
+
+    /// Set whether reports include warnings.
+    pub fn with_warnings(mut self, show: bool) -> Self {
+        self.show_warnings = show;
+        self
+    }
 }
 
 /// Color output option.
@@ -356,6 +391,66 @@ impl<'a> ErrorPrinter<'a> {
         }
     }
 
+    fn print_report(
+        &self,
+        errors: Option<&ConfigErrors>,
+        warnings: &[ConfigWarning],
+        writer: &mut dyn Write,
+    ) {
+        if let Some(errors) = errors {
+            self.print(errors, writer);
+        }
+        if self.options.show_warnings && !warnings.is_empty() {
+            self.print_warnings(warnings, writer);
+        }
+    }
+
+    fn print_warnings(&self, warnings: &[ConfigWarning], writer: &mut dyn Write) {
+        let c = &self.colors;
+
+        writeln!(
+            writer,
+            "\n{}Configuration warnings ({}):{}\n",
+            c.warning,
+            warnings.len(),
+            c.reset
+        )
+        .ok();
+
+        for (shown, warning) in warnings.iter().enumerate() {
+            if let Some(max) = self.options.max_errors {
+                if shown >= max {
+                    writeln!(
+                        writer,
+                        "\n  {}...and {} more warnings{}",
+                        c.warning,
+                        warnings.len() - shown,
+                        c.reset
+                    )
+                    .ok();
+                    break;
+                }
+            }
+            writeln!(writer, "    {}•{} {}", c.warning, c.reset, warning).ok();
+        }
+        writeln!(writer).ok();
+
+        if self.options.show_suggestions {
+            let suggestions: Vec<_> = warnings
+                .iter()
+                .filter_map(|w| w.suggestion())
+                .take(3)
+                .collect();
+            if !suggestions.is_empty() {
+                writeln!(writer, "{}Hints:{}", c.hint, c.reset).ok();
+                for suggestion in suggestions {
+                    writeln!(writer, "  • {}", suggestion).ok();
+                }
+                writeln!(writer).ok();
+            }
+        }
+    }
+
     fn print_suggestions(&self, errors: &ConfigErrors, writer: &mut dyn Write) {
         let c = &self.colors;
         let suggestions: Vec<_> = errors
@@ -374,28 +469,14 @@ impl<'a> ErrorPrinter<'a> {
     }
 
     fn maybe_redact(&self, value: &str, path: &str) -> String {
-        if self.options.redact_sensitive && is_sensitive_path(path) {
-            "[REDACTED]".to_string()
+        if self.options.redact_sensitive && self.options.redaction.redacts_text(path, value) {
+            REDACTED.to_string()
         } else {
             value.to_string()
         }
     }
 }
 
-/// Check if a config path appears to contain sensitive data.
-fn is_sensitive_path(path: &str) -> bool {
-    let sensitive_patterns = [
-        "password",
-        "secret",
-        "key",
-        "token",
-        "credential",
-        "api_key",
-    ];
-    let lower = path.to_lowercase();
-    sensitive_patterns.iter().any(|p| lower.contains(p))
-}
-
 /// Detect if stdout/stderr is a TTY for color support.
 fn should_use_color(color_option: ColorOption) -> bool {
     match color_option {
@@ -442,6 +523,51 @@ impl ConfigErrors {
     }
 }
 
+/// Pretty print warnings to stderr.
+pub fn print_warnings(warnings: &[ConfigWarning], options: &PrettyPrintOptions) {
+    let use_color = should_use_color(options.color);
+    let printer = ErrorPrinter::new(options, use_color);
+    let mut stderr = std::io::stderr();
+    printer.print_warnings(warnings, &mut stderr);
+}
+
+/// Pretty print warnings to a string.
+pub fn format_warnings(warnings: &[ConfigWarning], options: &PrettyPrintOptions) -> String {
+    let use_color = options.color == ColorOption::Always;
+    let printer = ErrorPrinter::new(options, use_color);
+    let mut buf = Vec::new();
+    printer.print_warnings(warnings, &mut buf);
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+/// Pretty print errors and warnings to stderr, each in its own section.
+pub fn print_report(
+    errors: Option<&ConfigErrors>,
+    warnings: &[ConfigWarning],
+    options: &PrettyPrintOptions,
+) {
+    let use_color = should_use_color(options.color);
+    let printer = ErrorPrinter::new(options, use_color);
+    let mut stderr = std::io::stderr();
+    printer.print_report(errors, warnings, &mut stderr);
+}
+
+/// Pretty print errors and warnings to a string, each in its own section.
+///
+/// Errors come first; the warnings section is left out when
+/// `show_warnings` is off.
+pub fn format_report(
+    errors: Option<&ConfigErrors>,
+    warnings: &[ConfigWarning],
+    options: &PrettyPrintOptions,
+) -> String {
+    let use_color = options.color == ColorOption::Always;
+    let printer = ErrorPrinter::new(options, use_color);
+    let mut buf = Vec::new();
+    printer.print_report(errors, warnings, &mut buf);
+    String::from_utf8(buf).unwrap_or_default()
+}
+
 /// Trait extension for easy error handling with pretty printing.
 ///
 /// # Stillwater Integration
@@ -525,6 +651,66 @@ mod tests {
         .unwrap()
     }
 
+    #[test]
+    fn test_format_warnings() {
+        let warnings = vec![
+            ConfigWarning::UnusedKey {
+                path: "databse.host".to_string(),
+                source_location: SourceLocation::new("config.toml").with_line(7),
+                did_you_mean: Some("database.host".to_string()),
+            },
+            ConfigWarning::UnmatchedEnvVar {
+                var: "APP_PROTS".to_string(),
+                path: "prots".to_string(),
+                did_you_mean: None,
+            },
+        ];
+
+        let output = format_warnings(&warnings, &PrettyPrintOptions::no_color());
+        assert!(output.contains("Configuration warnings (2):"));
+        assert!(output.contains(
+            "• [config.toml:7] unused key 'databse.host'; did you mean 'database.host'?"
+        ));
+        assert!(output.contains("APP_PROTS"));
+        assert!(output.contains("Change 'databse.host' to 'database.host'"));
+        assert!(!output.contains("\x1b["));
+
+        let colored = format_warnings(
+            &warnings,
+            &PrettyPrintOptions {
+                color: ColorOption::Always,
+                ..Default::default()
+            },
+        );
+        assert!(colored.contains("\x1b[1;33mConfiguration warnings (2):"));
+    }
+
+    #[test]
+    fn test_format_report_separates_errors_and_warnings() {
+        let errors = create_test_errors();
+        let warnings = vec![ConfigWarning::ValidationWarning {
+            path: "database.pool_size".to_string(),
+            source_location: Some(SourceLocation::new("config.toml").with_line(9)),
+            value: Some("500".to_string()),
+            message: "value must be between 1 and 200".to_string(),
+        }];
+
+        let output = format_report(Some(&errors), &warnings, &PrettyPrintOptions::no_color());
+        let errors_at = output.find("Configuration errors (3):").unwrap();
+        let warnings_at = output.find("Configuration warnings (1):").unwrap();
+        assert!(errors_at < warnings_at);
+        assert!(output[warnings_at..].contains("'database.pool_size': value must be between"));
+        assert!(!output[..warnings_at].contains("pool_size"));
+
+        let output = format_report(None, &warnings, &PrettyPrintOptions::no_color());
+        assert!(!output.contains("Configuration errors"));
+        assert!(output.contains("Configuration warnings (1):"));
+
+        let options = PrettyPrintOptions::no_color().with_warnings(false);
+        let output = format_report(Some(&errors), &warnings, &options);
+        assert!(!output.contains("Configuration warnings"));
+    }
+
     #[test]
     fn test_format_errors_contains_header() {
         let errors = create_test_errors();
@@ -827,18 +1013,33 @@ mod tests {
     }
 
     #[test]
-    fn test_is_sensitive_path() {
-        assert!(is_sensitive_path("password"));
-        assert!(is_sensitive_path("database.password"));
-        assert!(is_sensitive_path("api_key"));
-        assert!(is_sensitive_path("API_KEY"));
-        assert!(is_sensitive_path("secret_token"));
-        assert!(is_sensitive_path("aws_secret_access_key"));
-        assert!(is_sensitive_path("credential"));
-
-        assert!(!is_sensitive_path("host"));
-        assert!(!is_sensitive_path("port"));
-        assert!(!is_sensitive_path("timeout"));
+    fn test_redaction_policy() {
+        let errors = ConfigErrors::from_vec(vec![
+            ConfigError::ValidationError {
+                path: "database.dsn".to_string(),
+                source_location: None,
+                value: Some("host=db user=app".to_string()),
+                message: "invalid format".to_string(),
+            },
+            ConfigError::ValidationError {
+                path: "limits.token_bucket_size".to_string(),
+                source_location: None,
+                value: Some("-1".to_string()),
+                message: "must be positive".to_string(),
+            },
+        ])
+        .unwrap();
+
+        let default = errors.format(&PrettyPrintOptions::no_color());
+        assert!(default.contains("host=db user=app"));
+        assert!(!default.contains("-1"));
+
+        let policy = RedactionPolicy::default()
+            .deny("**.dsn")
+            .allow("**.token_bucket_size");
+        let output = errors.format(&PrettyPrintOptions::no_color().with_redaction_policy(policy));
+        assert!(!output.contains("host=db user=app"));
+        assert!(output.contains("'limits.token_bucket_size' = -1"));
     }
 
     #[test]
@@ -0,0 +1,161 @@
+//! Profile selection for sources that hold several profiles in one file.
+//!
+//! With a profile active, the top-level tables of a source marked
+//! `.with_profiles()` (`Toml`, `Json`, `Yaml`) are profile names. The `[default]` table applies
+//! to every profile and the active profile's table is layered over it; all
+//! other profiles are ignored. Top-level keys outside any table are base
+//! values that every profile, `[default]` included, can override.
+//!
+//! ```toml
+//! name = "app"
+//!
+//! [default]
+//! host = "localhost"
+//! port = 8080
+//!
+//! [production]
+//! host = "prod.example.com"
+//! ```
+
+use crate::source::ConfigValues;
+
+/// Name of the profile that every other profile is layered over.
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+/// Values from one profile table of a source, with the profile prefix stripped.
+#[derive(Debug, Clone)]
+pub(crate) struct ProfileLayer {
+    /// `None` for the base values outside any profile table
+    pub(crate) profile: Option<String>,
+    pub(crate) values: ConfigValues,
+}
+
+/// Pure function: split a source's values into the default and active profile layers.
+///
+/// Layers are returned lowest priority first: keys outside any profile table
+/// (if there are any), then the default and active profiles. Tables of
+/// inactive profiles are dropped.
+pub(crate) fn select_profile(values: &ConfigValues, active: &str) -> Vec<ProfileLayer> {
+    let mut base = ConfigValues::empty();
+    let mut default = ConfigValues::empty();
+    let mut selected = ConfigValues::empty();
+
+    for (path, value) in values.iter() {
+        let Some((profile, rest)) = split_profile(path) else {
+            base.insert(path.clone(), value.clone());
+            continue;
+        };
+        if profile == DEFAULT_PROFILE {
+            default.insert(rest.to_string(), value.clone());
+        } else if profile == active {
+            selected.insert(rest.to_string(), value.clone());
+        }
+    }
+
+    let mut layers = Vec::new();
+    if !base.is_empty() {
+        layers.push(ProfileLayer {
+            profile: None,
+            values: base,
+        });
+    }
+    layers.push(ProfileLayer {
+        profile: Some(DEFAULT_PROFILE.to_string()),
+        values: default,
+    });
+    if active != DEFAULT_PROFILE {
+        layers.push(ProfileLayer {
+            profile: Some(active.to_string()),
+            values: selected,
+        });
+    }
+    layers
+}
+
+/// Split `production.database.host` into `("production", "database.host")`.
+///
+/// Top-level keys (`port`, `hosts[0]`, `hosts.__len`) belong to no profile.
+fn split_profile(path: &str) -> Option<(&str, &str)> {
+    let (profile, rest) = path.split_once('.')?;
+    if profile.contains('[') || rest.is_empty() || rest.starts_with("__") {
+        return None;
+    }
+    Some((profile, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SourceLocation;
+    use crate::value::{ConfigValue, Value};
+
+    fn values(paths: &[(&str, i64)]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, n) in paths {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(Value::Integer(*n), SourceLocation::new("test")),
+            );
+        }
+        values
+    }
+
+    #[test]
+    fn test_select_profile_layers_default_then_active() {
+        let layers = select_profile(
+            &values(&[
+                ("default.port", 1),
+                ("default.hosts.__len", 0),
+                ("production.port", 2),
+                ("staging.port", 3),
+                ("stray", 4),
+            ]),
+            "production",
+        );
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[0].profile, None);
+        assert_eq!(
+            layers[0].values.get("stray").unwrap().value,
+            Value::Integer(4)
+        );
+        assert_eq!(layers[1].profile.as_deref(), Some("default"));
+        assert!(layers[1].values.contains("port"));
+        assert!(layers[1].values.contains("hosts.__len"));
+        assert_eq!(layers[2].profile.as_deref(), Some("production"));
+        assert_eq!(layers[2].values.len(), 1);
+        assert_eq!(
+            layers[2].values.get("port").unwrap().value,
+            Value::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_select_profile_keeps_top_level_arrays_as_base() {
+        let layers = select_profile(
+            &values(&[("tags[0]", 1), ("tags.__len", 1), ("default.port", 1)]),
+            DEFAULT_PROFILE,
+        );
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].values.len(), 2);
+        assert!(layers[0].values.contains("tags.__len"));
+        assert_eq!(layers[1].values.len(), 1);
+    }
+
+    #[test]
+    fn test_select_default_profile_only() {
+        let layers = select_profile(&values(&[("default.port", 1)]), DEFAULT_PROFILE);
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].values.len(), 1);
+        assert_eq!(layers[0].profile.as_deref(), Some(DEFAULT_PROFILE));
+    }
+
+    #[test]
+    fn test_split_profile() {
+        assert_eq!(split_profile("dev.a.b"), Some(("dev", "a.b")));
+        assert_eq!(split_profile("dev.hosts[0]"), Some(("dev", "hosts[0]")));
+        assert_eq!(split_profile("hosts[0].name"), None);
+        assert_eq!(split_profile("port"), None);
+        assert_eq!(split_profile("hosts.__len"), None);
+    }
+}
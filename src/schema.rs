@@ -0,0 +1,631 @@
+//! JSON Schema generation for configuration types.
+//!
+//! `#[derive(ConfigSchema)]` (with the `derive` feature) describes a config
+//! struct as a JSON Schema (draft 2020-12). Field validators become schema
+//! keywords, doc comments become descriptions, and serde's `rename`,
+//! `rename_all`, `default`, `skip`, and `flatten` attributes are honored, so
+//! the schema matches the files the struct is loaded from. Point an editor at
+//! the schema for autocompletion, or check files against it in CI.
+//!
+//! | Validator | Schema |
+//! |-----------|--------|
+//! | `non_empty` | `minLength: 1` (`minItems` for collections) |
+//! | `min_length(n)` / `max_length(n)` / `length(a..=b)` | `minLength` / `maxLength` (`minItems` / `maxItems`) |
+//! | `pattern("re")` | `pattern` |
+//! | `email` / `url` / `uuid` | `format: email` / `uri` / `uuid` |
+//! | `ip` | `format: ipv4` or `ipv6` |
+//! | `range(a..=b)` | `minimum` / `maximum` |
+//! | `positive` / `negative` | `exclusiveMinimum: 0` / `exclusiveMaximum: 0` |
+//! | `non_zero` | `not: {const: 0}` |
+//! | `extension("ext")` | `pattern: \.ext$` |
+//! | `each(v)` | `v` applied to `items` |
+//! | `#[sensitive]` | `writeOnly: true` |
+//!
+//! Validators without a schema equivalent (`file_exists`, `custom`, `when`,
+//! ...) are omitted.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use premortem::prelude::*;
+//!
+//! #[derive(Deserialize, DeriveValidate, DeriveConfigSchema)]
+//! struct ServerConfig {
+//!     /// Interface to bind to
+//!     #[validate(non_empty)]
+//!     host: String,
+//!     #[validate(range(1..=65535))]
+//!     port: u16,
+//! }
+//!
+//! let schema = Config::<ServerConfig>::json_schema();
+//! std::fs::write("config.schema.json", serde_json::to_string_pretty(&schema)?)?;
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map};
+
+/// JSON value type used for schemas (re-exported from `serde_json`).
+pub use serde_json::Value as JsonValue;
+
+use crate::config::Config;
+use crate::secret::{Secret, Zeroize};
+use crate::units::{ByteSize, Duration};
+
+/// URI of the JSON Schema dialect produced by [`Config::json_schema`].
+pub const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Types that can describe themselves as a JSON Schema.
+///
+/// Implemented for primitives, strings, paths, collections, and maps; derive
+/// it for config structs with `#[derive(ConfigSchema)]`.
+pub trait ConfigSchema {
+    /// The JSON Schema describing this type.
+    fn json_schema() -> JsonValue;
+
+    /// Whether a field of this type may be left out of the configuration.
+    ///
+    /// `true` for `Option<T>`, which keeps the field out of `required`.
+    fn is_optional() -> bool {
+        false
+    }
+
+    /// The fields of this type, for reference documentation.
+    ///
+    /// Empty for leaf types; derived structs list every deserialized field.
+    fn fields() -> Vec<FieldInfo> {
+        Vec::new()
+    }
+
+    /// The type's doc comment, used for fields that have none of their own.
+    fn description() -> Option<&'static str> {
+        None
+    }
+}
+
+/// Description of one field of a derived config struct.
+///
+/// Produced by `#[derive(ConfigSchema)]` and rendered by [`crate::docs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldInfo {
+    /// Key of the field in configuration files
+    pub name: &'static str,
+    /// Rust type of the field as written in the struct
+    pub rust_type: &'static str,
+    /// The field's doc comment
+    pub description: Option<&'static str>,
+    /// `#[validate(...)]` rules, as written
+    pub validators: &'static [&'static str],
+    /// Whether the field must be present
+    pub required: bool,
+    /// Whether serde fills the field in when it is missing (`#[serde(default)]`)
+    pub has_default: bool,
+    /// Whether the field is `#[sensitive]`
+    pub sensitive: bool,
+    /// Whether the field's own fields sit at this level (`#[serde(flatten)]`)
+    pub flatten: bool,
+    /// Fields of a nested struct
+    pub children: Vec<FieldInfo>,
+}
+
+impl<T: ConfigSchema> Config<T> {
+    /// The JSON Schema (draft 2020-12) for `T`, ready to be written out.
+    ///
+    /// ```ignore
+    /// let schema = Config::<AppConfig>::json_schema();
+    /// std::fs::write("config.schema.json", serde_json::to_string_pretty(&schema)?)?;
+    /// ```
+    pub fn json_schema() -> JsonValue {
+        let mut schema = Map::new();
+        schema.insert("$schema".to_string(), json!(DRAFT_2020_12));
+        if let JsonValue::Object(body) = T::json_schema() {
+            schema.extend(body);
+        }
+        JsonValue::Object(schema)
+    }
+}
+
+/// A validator, as seen by schema generation.
+///
+/// Emitted by `#[derive(ConfigSchema)]` from `#[validate(...)]` attributes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constraint {
+    /// `non_empty`
+    NonEmpty,
+    /// `min_length(n)`
+    MinLength(usize),
+    /// `max_length(n)`
+    MaxLength(usize),
+    /// `length(min..=max)`
+    Length(usize, usize),
+    /// `pattern("regex")`
+    Pattern(&'static str),
+    /// `email`
+    Email,
+    /// `url`
+    Url,
+    /// `ip`
+    Ip,
+    /// `uuid`
+    Uuid,
+    /// `range(min..=max)`, with bounds as numeric literals or as strings
+    /// like `"1s"` (which have no JSON Schema equivalent)
+    Range(&'static str, &'static str),
+    /// `positive`
+    Positive,
+    /// `negative`
+    Negative,
+    /// `non_zero`
+    NonZero,
+    /// `extension("ext")`
+    Extension(&'static str),
+    /// `each(validator)`
+    Each(&'static Constraint),
+}
+
+/// One field of a derived struct schema.
+#[derive(Debug, Clone)]
+pub struct Property {
+    /// Key of the field in configuration files
+    pub name: &'static str,
+    /// Schema of the field, with its constraints applied
+    pub schema: JsonValue,
+    /// Whether the field must be present
+    pub required: bool,
+    /// Whether the field's properties are merged into the parent (`#[serde(flatten)]`)
+    pub flatten: bool,
+}
+
+/// Build the schema of a field of type `T`.
+///
+/// Used by `#[derive(ConfigSchema)]`.
+pub fn field<T: ConfigSchema + ?Sized>(
+    description: Option<&str>,
+    constraints: &[Constraint],
+    sensitive: bool,
+) -> JsonValue {
+    let mut schema = T::json_schema();
+    for constraint in constraints {
+        apply_constraint(&mut schema, constraint);
+    }
+    if let JsonValue::Object(map) = &mut schema {
+        if let Some(description) = description {
+            map.insert("description".to_string(), json!(description));
+        }
+        if sensitive {
+            map.insert("writeOnly".to_string(), json!(true));
+        }
+    }
+    schema
+}
+
+/// Build the schema of an enum whose variants are written as strings.
+///
+/// Used by `#[derive(ConfigSchema)]` for enums of unit variants.
+pub fn string_enum(title: &str, description: Option<&str>, variants: &[&str]) -> JsonValue {
+    let mut schema = Map::new();
+    schema.insert("title".to_string(), json!(title));
+    if let Some(description) = description {
+        schema.insert("description".to_string(), json!(description));
+    }
+    schema.insert("type".to_string(), json!("string"));
+    schema.insert("enum".to_string(), json!(variants));
+    JsonValue::Object(schema)
+}
+
+/// Pure function: allow `null` in addition to what `schema` accepts.
+fn nullable(schema: JsonValue) -> JsonValue {
+    let JsonValue::Object(mut map) = schema else {
+        return schema;
+    };
+    // An empty schema already accepts null
+    if map.is_empty() {
+        return JsonValue::Object(map);
+    }
+    match map.get_mut("type") {
+        Some(JsonValue::Array(types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+        }
+        Some(single) => *single = json!([single.clone(), "null"]),
+        None => return json!({ "anyOf": [map, { "type": "null" }] }),
+    }
+    if let Some(JsonValue::Array(variants)) = map.get_mut("enum") {
+        if !variants.contains(&JsonValue::Null) {
+            variants.push(JsonValue::Null);
+        }
+    }
+    JsonValue::Object(map)
+}
+
+/// Build the schema of a struct from its properties.
+///
+/// Used by `#[derive(ConfigSchema)]`. Flattened properties contribute their
+/// own properties and required keys.
+pub fn object(
+    title: &str,
+    description: Option<&str>,
+    properties: Vec<Property>,
+    deny_unknown_fields: bool,
+) -> JsonValue {
+    let mut props = Map::new();
+    let mut required = Vec::new();
+
+    for property in properties {
+        if property.flatten {
+            if let Some(inner) = property
+                .schema
+                .get("properties")
+                .and_then(|p| p.as_object())
+            {
+                props.extend(inner.clone());
+            }
+            if let Some(inner) = property.schema.get("required").and_then(|r| r.as_array()) {
+                required.extend(inner.iter().cloned());
+            }
+            continue;
+        }
+        if property.required {
+            required.push(json!(property.name));
+        }
+        props.insert(property.name.to_string(), property.schema);
+    }
+
+    let mut schema = Map::new();
+    schema.insert("title".to_string(), json!(title));
+    if let Some(description) = description {
+        schema.insert("description".to_string(), json!(description));
+    }
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), JsonValue::Object(props));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), JsonValue::Array(required));
+    }
+    if deny_unknown_fields {
+        schema.insert("additionalProperties".to_string(), json!(false));
+    }
+    JsonValue::Object(schema)
+}
+
+/// Apply one constraint to a schema, choosing keywords by the schema's type.
+fn apply_constraint(schema: &mut JsonValue, constraint: &Constraint) {
+    let JsonValue::Object(map) = schema else {
+        return;
+    };
+    // A nullable schema (`["string", "null"]`) is constrained by its other type
+    let schema_type = match map.get("type") {
+        Some(JsonValue::Array(types)) => types
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .find(|t| *t != "null"),
+        Some(t) => t.as_str(),
+        None => None,
+    };
+    let is_array = schema_type == Some("array");
+    let (min_key, max_key) = match schema_type {
+        Some("array") => ("minItems", "maxItems"),
+        Some("object") => ("minProperties", "maxProperties"),
+        _ => ("minLength", "maxLength"),
+    };
+
+    match *constraint {
+        Constraint::NonEmpty => {
+            map.insert(min_key.to_string(), json!(1));
+        }
+        Constraint::MinLength(n) => {
+            map.insert(min_key.to_string(), json!(n));
+        }
+        Constraint::MaxLength(n) => {
+            map.insert(max_key.to_string(), json!(n));
+        }
+        Constraint::Length(min, max) => {
+            map.insert(min_key.to_string(), json!(min));
+            map.insert(max_key.to_string(), json!(max));
+        }
+        Constraint::Pattern(pattern) => {
+            map.insert("pattern".to_string(), json!(pattern));
+        }
+        Constraint::Email => {
+            map.insert("format".to_string(), json!("email"));
+        }
+        Constraint::Url => {
+            map.insert("format".to_string(), json!("uri"));
+        }
+        Constraint::Uuid => {
+            map.insert("format".to_string(), json!("uuid"));
+        }
+        Constraint::Ip => {
+            map.insert(
+                "anyOf".to_string(),
+                json!([{ "format": "ipv4" }, { "format": "ipv6" }]),
+            );
+        }
+        Constraint::Range(min, max) => {
+            if let Some(min) = parse_number(min) {
+                map.insert("minimum".to_string(), min);
+            }
+            if let Some(max) = parse_number(max) {
+                map.insert("maximum".to_string(), max);
+            }
+        }
+        Constraint::Positive => {
+            map.insert("exclusiveMinimum".to_string(), json!(0));
+        }
+        Constraint::Negative => {
+            map.insert("exclusiveMaximum".to_string(), json!(0));
+        }
+        Constraint::NonZero => {
+            map.insert("not".to_string(), json!({ "const": 0 }));
+        }
+        Constraint::Extension(ext) => {
+            let ext = ext.trim_start_matches('.');
+            map.insert(
+                "pattern".to_string(),
+                json!(format!("\\.{}$", regex::escape(ext))),
+            );
+        }
+        Constraint::Each(inner) => {
+            let key = if is_array {
+                "items"
+            } else {
+                "additionalProperties"
+            };
+            if let Some(items) = map.get_mut(key) {
+                apply_constraint(items, inner);
+            }
+        }
+    }
+}
+
+/// Parse a numeric literal (`1`, `-5`, `0.5`) into a JSON number.
+fn parse_number(literal: &str) -> Option<JsonValue> {
+    serde_json::from_str::<serde_json::Number>(literal)
+        .ok()
+        .map(JsonValue::Number)
+}
+
+macro_rules! impl_schema {
+    ($($ty:ty => $schema:tt),* $(,)?) => {
+        $(
+            impl ConfigSchema for $ty {
+                fn json_schema() -> JsonValue {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+impl_schema! {
+    bool => { "type": "boolean" },
+    String => { "type": "string" },
+    str => { "type": "string" },
+    char => { "type": "string", "minLength": 1, "maxLength": 1 },
+    PathBuf => { "type": "string" },
+    Path => { "type": "string" },
+    f32 => { "type": "number" },
+    f64 => { "type": "number" },
+    i8 => { "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX },
+    i16 => { "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX },
+    i32 => { "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX },
+    i64 => { "type": "integer" },
+    isize => { "type": "integer" },
+    u8 => { "type": "integer", "minimum": 0, "maximum": u8::MAX },
+    u16 => { "type": "integer", "minimum": 0, "maximum": u16::MAX },
+    u32 => { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+    u64 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "minimum": 0 },
+    IpAddr => { "type": "string", "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }] },
+    Ipv4Addr => { "type": "string", "format": "ipv4" },
+    Ipv6Addr => { "type": "string", "format": "ipv6" },
+    SocketAddr => { "type": "string" },
+    Duration => { "type": ["string", "integer"], "minimum": 0 },
+    ByteSize => { "type": ["string", "integer"], "minimum": 0 },
+    JsonValue => {},
+}
+
+/// An optional value may also be written as `null`.
+impl<T: ConfigSchema> ConfigSchema for Option<T> {
+    fn json_schema() -> JsonValue {
+        nullable(T::json_schema())
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+
+    fn fields() -> Vec<FieldInfo> {
+        T::fields()
+    }
+
+    fn description() -> Option<&'static str> {
+        T::description()
+    }
+}
+
+/// A secret is described by the value it wraps, marked `writeOnly`.
+impl<T: ConfigSchema + Zeroize> ConfigSchema for Secret<T> {
+    fn json_schema() -> JsonValue {
+        let mut schema = T::json_schema();
+        if let JsonValue::Object(map) = &mut schema {
+            map.insert("writeOnly".to_string(), json!(true));
+        }
+        schema
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+
+    fn fields() -> Vec<FieldInfo> {
+        T::fields()
+    }
+
+    fn description() -> Option<&'static str> {
+        T::description()
+    }
+}
+
+impl<T: ConfigSchema + ?Sized> ConfigSchema for Box<T> {
+    fn json_schema() -> JsonValue {
+        T::json_schema()
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+
+    fn fields() -> Vec<FieldInfo> {
+        T::fields()
+    }
+
+    fn description() -> Option<&'static str> {
+        T::description()
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for Vec<T> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "array", "items": T::json_schema() })
+    }
+}
+
+impl<T: ConfigSchema, const N: usize> ConfigSchema for [T; N] {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "array", "items": T::json_schema(), "minItems": N, "maxItems": N })
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for HashSet<T> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "array", "items": T::json_schema(), "uniqueItems": true })
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for BTreeSet<T> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "array", "items": T::json_schema(), "uniqueItems": true })
+    }
+}
+
+impl<V: ConfigSchema> ConfigSchema for HashMap<String, V> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "object", "additionalProperties": V::json_schema() })
+    }
+}
+
+impl<V: ConfigSchema> ConfigSchema for BTreeMap<String, V> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "object", "additionalProperties": V::json_schema() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints_pick_keywords_by_type() {
+        let name = field::<String>(Some("Name"), &[Constraint::Length(2, 8)], false);
+        assert_eq!(
+            name,
+            json!({ "type": "string", "minLength": 2, "maxLength": 8, "description": "Name" })
+        );
+
+        let hosts = field::<Vec<String>>(
+            None,
+            &[Constraint::NonEmpty, Constraint::Each(&Constraint::Email)],
+            false,
+        );
+        assert_eq!(hosts["minItems"], json!(1));
+        assert_eq!(hosts["items"]["format"], json!("email"));
+
+        let port = field::<u16>(None, &[Constraint::Range("1", "1024")], true);
+        assert_eq!(port["minimum"], json!(1));
+        assert_eq!(port["maximum"], json!(1024));
+        assert_eq!(port["writeOnly"], json!(true));
+
+        let ratio = field::<f64>(None, &[Constraint::Range("-0.5", "1.5")], false);
+        assert_eq!(ratio["minimum"], json!(-0.5));
+    }
+
+    #[test]
+    fn test_option_accepts_null() {
+        let email = field::<Option<String>>(None, &[Constraint::MaxLength(64)], false);
+        assert_eq!(
+            email,
+            json!({ "type": ["string", "null"], "maxLength": 64 })
+        );
+        assert_eq!(
+            Option::<Duration>::json_schema()["type"],
+            json!(["string", "integer", "null"])
+        );
+        assert_eq!(Option::<JsonValue>::json_schema(), json!({}));
+        assert_eq!(
+            nullable(json!({ "enum": ["a"] })),
+            json!({ "anyOf": [{ "enum": ["a"] }, { "type": "null" }] })
+        );
+    }
+
+    #[test]
+    fn test_string_enum() {
+        assert_eq!(
+            string_enum("Mode", None, &["ro", "rw"]),
+            json!({ "title": "Mode", "type": "string", "enum": ["ro", "rw"] })
+        );
+        assert_eq!(
+            nullable(string_enum("Mode", None, &["ro"]))["enum"],
+            json!(["ro", null])
+        );
+    }
+
+    #[test]
+    fn test_extension_becomes_escaped_pattern() {
+        let path = field::<PathBuf>(None, &[Constraint::Extension(".tar.gz")], false);
+        assert_eq!(path["pattern"], json!("\\.tar\\.gz$"));
+    }
+
+    #[test]
+    fn test_object_collects_required_and_flattened_properties() {
+        let inner = object(
+            "Inner",
+            None,
+            vec![Property {
+                name: "level",
+                schema: field::<String>(None, &[], false),
+                required: true,
+                flatten: false,
+            }],
+            false,
+        );
+        let schema = object(
+            "Outer",
+            Some("Outer config"),
+            vec![
+                Property {
+                    name: "port",
+                    schema: field::<Option<u16>>(None, &[], false),
+                    required: !<Option<u16>>::is_optional(),
+                    flatten: false,
+                },
+                Property {
+                    name: "inner",
+                    schema: inner,
+                    required: true,
+                    flatten: true,
+                },
+            ],
+            true,
+        );
+
+        assert_eq!(schema["required"], json!(["level"]));
+        assert!(schema["properties"]["port"].is_object());
+        assert!(schema["properties"]["level"].is_object());
+        assert_eq!(schema["additionalProperties"], json!(false));
+        assert_eq!(schema["description"], json!("Outer config"));
+    }
+}
@@ -0,0 +1,217 @@
+//! A wrapper for configuration values that must not leak.
+//!
+//! `#[sensitive]` keeps a value out of validation errors, but the value itself
+//! still shows up wherever the config struct is printed. Wrapping the field in
+//! [`Secret`] closes those gaps:
+//!
+//! - `Debug`, `Display` and `Serialize` print `[REDACTED]`
+//! - reading the value takes an explicit [`Secret::expose_secret`]
+//! - the value is zeroized when the secret is dropped
+//!
+//! ```ignore
+//! use premortem::Secret;
+//!
+//! #[derive(Debug, Deserialize, DeriveValidate)]
+//! struct DatabaseConfig {
+//!     host: String,
+//!     #[validate(min_length(16))]
+//!     password: Secret<String>,
+//! }
+//!
+//! let config = Config::<DatabaseConfig>::builder()
+//!     .source(Toml::file("config.toml"))
+//!     .build()?;
+//!
+//! println!("{:?}", config); // DatabaseConfig { host: "db", password: [REDACTED] }
+//! connect(&config.host, config.password.expose_secret());
+//! ```
+//!
+//! `Secret` deserializes transparently from the wrapped type. When it is
+//! deserialized by premortem, the paths it was loaded from are remembered:
+//! type errors at those paths leave out the offending value, and
+//! [`TracedConfig`](crate::TracedConfig) redacts them in trace reports,
+//! exports and diffs. Derived validators run against the exposed value and
+//! never include it in error messages.
+//!
+//! [`Defaults::from`](crate::Defaults::from) is the one place a secret is
+//! serialized with its value, so a `Secret` field keeps its real default.
+
+use std::cell::Cell;
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+use crate::redact::REDACTED;
+use crate::source::in_subtree;
+
+pub use zeroize::Zeroize;
+
+/// Newtype name `Secret` deserializes through, so premortem's deserializer
+/// can recognize the paths that hold secrets.
+pub(crate) const SECRET_NAME: &str = "premortem::Secret";
+
+thread_local! {
+    /// Set while defaults are serialized, so secrets keep their values.
+    static EXPOSE_SECRETS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` with secrets serializing their wrapped values instead of `[REDACTED]`.
+pub(crate) fn with_exposed_secrets<R>(f: impl FnOnce() -> R) -> R {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            EXPOSE_SECRETS.with(|expose| expose.set(self.0));
+        }
+    }
+
+    let _reset = Reset(EXPOSE_SECRETS.with(|expose| expose.replace(true)));
+    f()
+}
+
+/// A configuration value that is redacted whenever it is printed or
+/// serialized, and zeroized when dropped.
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    /// Wrap a value.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Read the wrapped value.
+    ///
+    /// Call this where the value is actually used, so uses are easy to audit.
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Zeroize + Default> Default for Secret<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Zeroize + Serialize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if EXPOSE_SECRETS.with(Cell::get) {
+            self.0.serialize(serializer)
+        } else {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+}
+
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SecretVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Zeroize + Deserialize<'de>> Visitor<'de> for SecretVisitor<T> {
+            type Value = Secret<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a secret value")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                T::deserialize(deserializer).map(Secret::new)
+            }
+        }
+
+        deserializer
+            .deserialize_newtype_struct(SECRET_NAME, SecretVisitor(std::marker::PhantomData))
+    }
+}
+
+/// Pure function: check whether `path` is, or lies beneath, one of `secrets`.
+pub(crate) fn is_secret_path(path: &str, secrets: &[String]) -> bool {
+    secrets.iter().any(|secret| in_subtree(path, secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize, Serialize)]
+    struct Credentials {
+        user: String,
+        token: Secret<String>,
+    }
+
+    #[test]
+    fn test_debug_display_and_serialize_are_redacted() {
+        let creds: Credentials =
+            serde_json::from_str(r#"{"user": "admin", "token": "hunter2"}"#).unwrap();
+
+        assert_eq!(creds.token.expose_secret(), "hunter2");
+        assert_eq!(
+            format!("{:?}", creds),
+            r#"Credentials { user: "admin", token: [REDACTED] }"#
+        );
+        assert_eq!(creds.token.to_string(), "[REDACTED]");
+        assert_eq!(
+            serde_json::to_string(&creds).unwrap(),
+            r#"{"user":"admin","token":"[REDACTED]"}"#
+        );
+    }
+
+    #[test]
+    fn test_exposed_secrets_serialize_their_value() {
+        let creds = Credentials {
+            user: "admin".to_string(),
+            token: Secret::new("hunter2".to_string()),
+        };
+        assert_eq!(
+            with_exposed_secrets(|| serde_json::to_string(&creds).unwrap()),
+            r#"{"user":"admin","token":"hunter2"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&creds).unwrap(),
+            r#"{"user":"admin","token":"[REDACTED]"}"#
+        );
+    }
+
+    #[test]
+    fn test_is_secret_path() {
+        let secrets = vec!["database.password".to_string(), "keys".to_string()];
+        assert!(is_secret_path("database.password", &secrets));
+        assert!(is_secret_path("keys[0]", &secrets));
+        assert!(!is_secret_path("database.password_hint", &secrets));
+        assert!(!is_secret_path("database.host", &secrets));
+    }
+}
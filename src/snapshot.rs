@@ -0,0 +1,458 @@
+//! Last-known-good snapshots of the merged configuration.
+//!
+//! With [`ConfigBuilder::snapshot_to`](crate::ConfigBuilder::snapshot_to),
+//! every successful build atomically writes its merged, validated values to a
+//! file. With [`fallback_to_snapshot`](crate::ConfigBuilder::fallback_to_snapshot),
+//! a build whose sources fail to load — a config service that is briefly down,
+//! a mount that is not there yet — is built from that snapshot instead of
+//! failing:
+//!
+//! ```ignore
+//! let (config, warnings) = Config::<AppConfig>::builder()
+//!     .source(Toml::file("/etc/app/config.toml"))
+//!     .source(Remote::new("https://config.internal/app"))
+//!     .snapshot_to("/var/lib/app/config.snapshot.json")
+//!     .fallback_to_snapshot()
+//!     .build_with_warnings()?;
+//! ```
+//!
+//! A build from the snapshot is still deserialized and validated, and reports
+//! a [`ConfigWarning::SnapshotUsed`] with the snapshot's age and the errors
+//! that caused the fallback. [`TracedConfig::snapshot`](crate::TracedConfig::snapshot)
+//! returns the same information as a [`SnapshotInfo`].
+//!
+//! The snapshot holds every value in plain text, secrets included. `RealEnv`
+//! creates it readable only by its owner on Unix.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Map, Number, Value as JsonValue};
+use stillwater::Semigroup;
+
+use crate::env::ConfigEnv;
+use crate::error::{ConfigError, ConfigErrors, ConfigWarning, SourceErrorKind, SourceLocation};
+use crate::source::ConfigValues;
+use crate::value::{ConfigValue, Datetime, Value};
+
+/// Version of the snapshot file format.
+const FORMAT_VERSION: u64 = 1;
+
+/// A snapshot a configuration was built from, because its sources failed to load.
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    /// Path of the snapshot file
+    pub path: PathBuf,
+    /// When the snapshot was written
+    pub written_at: SystemTime,
+    /// How old the snapshot was when it was loaded
+    pub age: Duration,
+    /// Why the sources could not be used
+    pub errors: ConfigErrors,
+}
+
+impl SnapshotInfo {
+    /// The warning reported for a build from this snapshot.
+    pub fn warning(&self) -> ConfigWarning {
+        ConfigWarning::SnapshotUsed {
+            snapshot: self.path.display().to_string(),
+            age: self.age,
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// Write `values` to the snapshot at `path`, returning a warning if that fails.
+pub(crate) fn write_snapshot(
+    env: &dyn ConfigEnv,
+    path: &Path,
+    values: &ConfigValues,
+) -> Option<ConfigWarning> {
+    let contents = render_snapshot(values, SystemTime::now());
+    env.write_file(path, &contents)
+        .err()
+        .map(|e| ConfigWarning::SnapshotNotWritten {
+            snapshot: path.display().to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// Read the snapshot at `path` in place of sources that failed with `errors`.
+///
+/// If the snapshot cannot be read either, `errors` are returned with the
+/// snapshot's own error appended.
+pub(crate) fn read_snapshot(
+    env: &dyn ConfigEnv,
+    path: &Path,
+    errors: ConfigErrors,
+) -> Result<(ConfigValues, SnapshotInfo), ConfigErrors> {
+    let name = path.display().to_string();
+    let parsed = env
+        .read_file(path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => SourceErrorKind::NotFound { path: name.clone() },
+            _ => SourceErrorKind::IoError {
+                message: e.to_string(),
+            },
+        })
+        .and_then(|content| parse_snapshot(&content, &name));
+
+    match parsed {
+        Ok((values, written_at)) => {
+            let age = SystemTime::now()
+                .duration_since(written_at)
+                .unwrap_or_default();
+            let info = SnapshotInfo {
+                path: path.to_path_buf(),
+                written_at,
+                age,
+                errors,
+            };
+            Ok((values, info))
+        }
+        Err(kind) => Err(
+            errors.combine(ConfigErrors::single(ConfigError::SourceError {
+                source_name: name,
+                kind,
+            })),
+        ),
+    }
+}
+
+/// Pure function: render `values` as a snapshot file.
+fn render_snapshot(values: &ConfigValues, written_at: SystemTime) -> String {
+    let flat: Map<String, JsonValue> = values
+        .iter()
+        .map(|(path, cv)| (path.clone(), encode_value(&cv.value)))
+        .collect();
+    let written_at = written_at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let snapshot = json!({
+        "version": FORMAT_VERSION,
+        "written_at": written_at,
+        "values": flat,
+    });
+    serde_json::to_string_pretty(&snapshot).unwrap_or_default()
+}
+
+/// Pure function: parse a snapshot file, attributing its values to `source`.
+fn parse_snapshot(
+    content: &str,
+    source: &str,
+) -> Result<(ConfigValues, SystemTime), SourceErrorKind> {
+    let invalid = |message: String| SourceErrorKind::ParseError {
+        message,
+        line: None,
+        column: None,
+    };
+
+    let snapshot: JsonValue =
+        serde_json::from_str(content).map_err(|e| invalid(format!("invalid snapshot: {}", e)))?;
+    match snapshot.get("version").and_then(JsonValue::as_u64) {
+        Some(FORMAT_VERSION) => {}
+        Some(version) => return Err(invalid(format!("unsupported snapshot version {}", version))),
+        None => return Err(invalid("snapshot has no version".to_string())),
+    }
+    let written_at = snapshot
+        .get("written_at")
+        .and_then(JsonValue::as_u64)
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .ok_or_else(|| invalid("snapshot has no written_at".to_string()))?;
+    let flat = snapshot
+        .get("values")
+        .and_then(JsonValue::as_object)
+        .ok_or_else(|| invalid("snapshot has no values".to_string()))?;
+
+    let mut values = ConfigValues::empty();
+    for (path, value) in flat {
+        values.insert(
+            path.clone(),
+            ConfigValue::new(
+                decode_value(value)
+                    .map_err(|message| invalid(format!("'{}': {}", path, message)))?,
+                SourceLocation::new(source),
+            ),
+        );
+    }
+    Ok((values, written_at))
+}
+
+/// Pure function: encode `value` as JSON, tagging what JSON cannot hold.
+///
+/// Datetimes, byte strings and non-finite floats become single-key objects
+/// (`{"$datetime": "..."}`, `{"$bytes": "<hex>"}`, `{"$float": "NaN"}`); a
+/// table with a key starting with `$` is wrapped in `{"$table": ...}` so it is
+/// not mistaken for one.
+fn encode_value(value: &Value) -> JsonValue {
+    let tagged = |tag: &str, value: JsonValue| json!({ tag: value });
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Bool(b) => JsonValue::Bool(*b),
+        Value::Integer(i) => JsonValue::from(*i),
+        Value::UInteger(u) => JsonValue::from(*u),
+        Value::Float(f) => match Number::from_f64(*f) {
+            Some(n) => JsonValue::Number(n),
+            None => tagged("$float", JsonValue::from(f.to_string())),
+        },
+        Value::String(s) => JsonValue::String(s.clone()),
+        Value::Datetime(dt) => tagged("$datetime", JsonValue::from(dt.to_string())),
+        Value::Bytes(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            tagged("$bytes", JsonValue::from(hex))
+        }
+        Value::Array(items) => JsonValue::Array(items.iter().map(encode_value).collect()),
+        Value::Table(table) => {
+            let map: Map<String, JsonValue> = table
+                .iter()
+                .map(|(k, v)| (k.clone(), encode_value(v)))
+                .collect();
+            if table.keys().any(|k| k.starts_with('$')) {
+                tagged("$table", JsonValue::Object(map))
+            } else {
+                JsonValue::Object(map)
+            }
+        }
+    }
+}
+
+/// Pure function: decode a value written by [`encode_value`].
+fn decode_value(json: &JsonValue) -> Result<Value, String> {
+    match json {
+        JsonValue::Null => Ok(Value::Null),
+        JsonValue::Bool(b) => Ok(Value::Bool(*b)),
+        JsonValue::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => Ok(Value::Integer(i)),
+            (None, Some(u), _) => Ok(Value::UInteger(u)),
+            (None, None, Some(f)) => Ok(Value::Float(f)),
+            _ => Err(format!("invalid number {}", n)),
+        },
+        JsonValue::String(s) => Ok(Value::String(s.clone())),
+        JsonValue::Array(items) => items
+            .iter()
+            .map(decode_value)
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        JsonValue::Object(map) => {
+            let tag = match map.iter().next() {
+                Some((tag, value)) if map.len() == 1 && tag.starts_with('$') => Some((tag, value)),
+                _ => None,
+            };
+            match tag {
+                Some((tag, value)) => decode_tagged(tag, value),
+                None => decode_table(map),
+            }
+        }
+    }
+}
+
+/// Pure function: decode the tagged value `{tag: value}`.
+fn decode_tagged(tag: &str, value: &JsonValue) -> Result<Value, String> {
+    let text = || {
+        value
+            .as_str()
+            .ok_or_else(|| format!("'{}' must be a string", tag))
+    };
+    match tag {
+        "$datetime" => {
+            let text = text()?;
+            Datetime::parse(text)
+                .map(Value::Datetime)
+                .ok_or_else(|| format!("invalid datetime '{}'", text))
+        }
+        "$bytes" => {
+            let hex = text()?;
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| {
+                    hex.get(i..i + 2)
+                        .and_then(|b| u8::from_str_radix(b, 16).ok())
+                })
+                .collect::<Option<_>>()
+                .map(Value::Bytes)
+                .ok_or_else(|| format!("invalid hex bytes '{}'", hex))
+        }
+        "$float" => {
+            let text = text()?;
+            text.parse()
+                .map(Value::Float)
+                .map_err(|_| format!("invalid float '{}'", text))
+        }
+        "$table" => value
+            .as_object()
+            .ok_or_else(|| "'$table' must be an object".to_string())
+            .and_then(decode_table),
+        _ => Err(format!("unknown tag '{}'", tag)),
+    }
+}
+
+/// Pure function: decode the entries of a table.
+fn decode_table(map: &Map<String, JsonValue>) -> Result<Value, String> {
+    map.iter()
+        .map(|(k, v)| decode_value(v).map(|v| (k.clone(), v)))
+        .collect::<Result<_, _>>()
+        .map(Value::Table)
+}
+
+/// Pure function: render an age in its two largest units, e.g. `3m 12s`.
+pub(crate) fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (days, hours, minutes, seconds) =
+        (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60, secs % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {}s", minutes, seconds),
+        (0, _, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::value::Value;
+
+    fn values() -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, value) in [
+            ("host", Value::from("db")),
+            ("port", Value::from(5432)),
+            ("ratio", Value::from(0.5)),
+            ("tags[0]", Value::from("a")),
+            ("tags.__len", Value::from(1)),
+        ] {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(value, SourceLocation::new("config.toml")),
+            );
+        }
+        values
+    }
+
+    fn source_errors() -> ConfigErrors {
+        ConfigErrors::single(ConfigError::SourceError {
+            source_name: "remote".to_string(),
+            kind: SourceErrorKind::ConnectionError {
+                message: "connection refused".to_string(),
+            },
+        })
+    }
+
+    #[test]
+    fn test_snapshot_round_trips() {
+        let written_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let content = render_snapshot(&values(), written_at);
+
+        let (parsed, parsed_at) = parse_snapshot(&content, "app.snapshot.json").unwrap();
+        assert_eq!(parsed_at, written_at);
+        assert_eq!(parsed.len(), 5);
+        for (path, cv) in values().iter() {
+            assert_eq!(parsed.get(path).unwrap().value, cv.value, "{}", path);
+        }
+        assert_eq!(
+            parsed.get("host").unwrap().source.source,
+            "app.snapshot.json"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_values_json_cannot_hold() {
+        let datetime = Datetime::parse("1979-05-27T07:32:00.5-07:00").unwrap();
+        let table: std::collections::BTreeMap<String, Value> =
+            [("$datetime".to_string(), Value::from("not a tag"))].into();
+        let mut values = ConfigValues::empty();
+        for (path, value) in [
+            ("started", Value::Datetime(datetime)),
+            ("key", Value::Bytes(vec![0, 15, 255])),
+            ("big", Value::from(u64::MAX)),
+            ("inf", Value::Float(f64::NEG_INFINITY)),
+            ("nan", Value::Float(f64::NAN)),
+            ("whole", Value::Float(2.0)),
+            ("escaped", Value::Table(table)),
+            ("nested", Value::Array(vec![Value::Bytes(vec![1])])),
+        ] {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(value, SourceLocation::new("config.toml")),
+            );
+        }
+
+        let content = render_snapshot(&values, UNIX_EPOCH);
+        let (parsed, _) = parse_snapshot(&content, "app.snapshot.json").unwrap();
+        for (path, cv) in values.iter().filter(|(path, _)| *path != "nan") {
+            assert_eq!(parsed.get(path).unwrap().value, cv.value, "{}", path);
+        }
+        assert!(matches!(
+            parsed.get("nan").unwrap().value,
+            Value::Float(f) if f.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_parse_snapshot_rejects_bad_tags() {
+        let parse = |value: &str| {
+            let content = format!(
+                r#"{{"version": 1, "written_at": 0, "values": {{"key": {}}}}}"#,
+                value
+            );
+            parse_snapshot(&content, "app.snapshot.json")
+        };
+        assert!(parse(r#"{"$bytes": "0g"}"#).is_err());
+        assert!(parse(r#"{"$datetime": 5}"#).is_err());
+        let err = parse(r#"{"$other": "x"}"#).unwrap_err();
+        assert!(err.to_string().contains("'key': unknown tag '$other'"));
+    }
+
+    #[test]
+    fn test_parse_snapshot_rejects_other_versions() {
+        let err = parse_snapshot(
+            r#"{"version": 2, "written_at": 0, "values": {}}"#,
+            "app.snapshot.json",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unsupported snapshot version 2"));
+        assert!(parse_snapshot("not json", "app.snapshot.json").is_err());
+    }
+
+    #[test]
+    fn test_read_snapshot_reports_age_and_cause() {
+        let env = MockEnv::new();
+        let path = Path::new("app.snapshot.json");
+        assert!(write_snapshot(&env, path, &values()).is_none());
+
+        let (values, info) = read_snapshot(&env, path, source_errors()).unwrap();
+        assert_eq!(values.get("port").unwrap().value, Value::Integer(5432));
+        assert_eq!(info.path, path);
+        assert!(info.age < Duration::from_secs(60));
+        assert_eq!(info.errors.len(), 1);
+        assert!(info
+            .warning()
+            .to_string()
+            .contains("using last-known-good snapshot written"));
+    }
+
+    #[test]
+    fn test_read_missing_snapshot_keeps_source_errors() {
+        let errors = read_snapshot(
+            &MockEnv::new(),
+            Path::new("app.snapshot.json"),
+            source_errors(),
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors.first(),
+            ConfigError::SourceError { source_name, .. } if source_name == "remote"
+        ));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(42)), "42s");
+        assert_eq!(format_age(Duration::from_secs(192)), "3m 12s");
+        assert_eq!(format_age(Duration::from_secs(7_500)), "2h 5m");
+        assert_eq!(format_age(Duration::from_secs(273_600)), "3d 4h");
+    }
+}
@@ -3,13 +3,19 @@
 //! This module provides the `Source` trait for loading configuration from
 //! various sources, and `ConfigValues` for intermediate value storage.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 #[cfg(feature = "watch")]
 use std::path::PathBuf;
 
+use serde::de::DeserializeOwned;
+use stillwater::Effect;
+
 use crate::env::ConfigEnv;
-use crate::error::ConfigErrors;
-use crate::value::ConfigValue;
+#[cfg(feature = "async")]
+use crate::env::{AsyncConfigEnv, BoxFuture};
+use crate::error::{prefix_path, ConfigErrors};
+use crate::sources::{Mounted, Trusted};
+use crate::value::{ConfigValue, Value};
 
 /// Intermediate representation of configuration values.
 ///
@@ -65,6 +71,166 @@ impl ConfigValues {
         self.values.keys()
     }
 
+    /// Pure function: the values under `prefix`, with the prefix stripped.
+    ///
+    /// `values.subtree("database")` turns `database.host` into `host`. For an
+    /// array, `servers[0].name` becomes `[0].name` and `servers.__len` becomes
+    /// `__len`. The value stored at `prefix` itself, if any, is not included.
+    pub fn subtree(&self, prefix: &str) -> ConfigValues {
+        if prefix.is_empty() {
+            return self.clone();
+        }
+        let values = self
+            .values
+            .iter()
+            .filter_map(|(path, value)| {
+                let rest = path.strip_prefix(prefix)?;
+                let rest = match rest.strip_prefix('.') {
+                    Some(key) => key,
+                    None => rest.starts_with('[').then_some(rest)?,
+                };
+                (!rest.is_empty()).then(|| (rest.to_string(), value.clone()))
+            })
+            .collect();
+        Self { values }
+    }
+
+    /// Pure function: the same values with every path moved under `prefix`.
+    pub(crate) fn mounted_at(&self, prefix: &str) -> ConfigValues {
+        if prefix.is_empty() {
+            return self.clone();
+        }
+        let values = self
+            .values
+            .iter()
+            .map(|(path, value)| (prefix_path(prefix, path), value.clone()))
+            .collect();
+        Self { values }
+    }
+
+    /// Remove the value at exactly `path`.
+    ///
+    /// Removing an array element (`servers[1]`) shifts the later elements down
+    /// and shortens the array's `__len`. Use [`remove_subtree`](Self::remove_subtree)
+    /// to remove an element stored as separate fields.
+    pub fn remove(&mut self, path: &str) -> Option<ConfigValue> {
+        let removed = self.values.remove(path)?;
+        self.close_gap(path);
+        Some(removed)
+    }
+
+    /// Remove the value at `prefix` and everything under it, returning what was removed.
+    ///
+    /// Removing an array also removes its `__len`; removing an array element
+    /// shifts the later elements down.
+    pub fn remove_subtree(&mut self, prefix: &str) -> ConfigValues {
+        let (removed, kept) = std::mem::take(&mut self.values)
+            .into_iter()
+            .partition(|(path, _)| in_subtree(path, prefix));
+        self.values = kept;
+        if !removed.is_empty() {
+            self.close_gap(prefix);
+        }
+        Self { values: removed }
+    }
+
+    /// Move everything under `from` to `to`, returning whether anything moved.
+    ///
+    /// Values already under `to` are replaced only where a moved path lands on them.
+    /// An empty `from` moves every value, mounting the whole tree at `to`.
+    pub fn rename_prefix(&mut self, from: &str, to: &str) -> bool {
+        let moved: Vec<String> = self
+            .values
+            .keys()
+            .filter(|path| from.is_empty() || in_subtree(path, from))
+            .cloned()
+            .collect();
+        for path in &moved {
+            if let Some(value) = self.values.remove(path) {
+                let rest = &path[from.len()..];
+                let rest = rest.strip_prefix('.').unwrap_or(rest);
+                let renamed = if to.is_empty() || rest.is_empty() || rest.starts_with('[') {
+                    format!("{}{}", to, rest)
+                } else {
+                    format!("{}.{}", to, rest)
+                };
+                self.values.insert(renamed, value);
+            }
+        }
+        !moved.is_empty()
+    }
+
+    /// Iterate over the values whose path matches `pattern`.
+    ///
+    /// `*` matches within one key or index (`servers[*].host`,
+    /// `database.*.port`) and `**` across any number of segments
+    /// (`**.password`). Internal metadata such as `__len` is never matched.
+    pub fn query(&self, pattern: &str) -> impl Iterator<Item = (&String, &ConfigValue)> {
+        let regex = glob_to_regex(pattern);
+        self.values
+            .iter()
+            .filter(move |(path, _)| !path.contains(".__") && regex.is_match(path))
+    }
+
+    /// Deserialize the single node at `path` into `T`.
+    ///
+    /// The node may be a scalar, an array or a table, stored flattened or
+    /// inline. Errors carry full paths; a path with nothing stored at or under
+    /// it deserializes as `None` for an `Option` and is otherwise reported as
+    /// `ConfigError::MissingField`.
+    ///
+    /// ```
+    /// # use premortem::{ConfigValue, ConfigValues, SourceLocation};
+    /// let mut values = ConfigValues::empty();
+    /// let source = SourceLocation::new("config.toml");
+    /// values.insert("servers[0].port".into(), ConfigValue::new(8080, source.clone()));
+    /// values.insert("servers[1].port".into(), ConfigValue::new(8081, source.clone()));
+    /// values.insert("servers.__len".into(), ConfigValue::new(2, source));
+    ///
+    /// let port: u16 = values.get_as("servers[1].port").unwrap();
+    /// assert_eq!(port, 8081);
+    /// ```
+    pub fn get_as<T: DeserializeOwned>(&self, path: &str) -> Result<T, ConfigErrors> {
+        crate::de::from_config_values_at(self, path)
+    }
+
+    /// After removing the element at `path`, shift the later elements down.
+    fn close_gap(&mut self, path: &str) {
+        let Some(open) = path.strip_suffix(']').and_then(|p| p.rfind('[')) else {
+            return;
+        };
+        let (array, index) = (&path[..open], &path[open + 1..path.len() - 1]);
+        let Ok(index) = index.parse::<usize>() else {
+            return;
+        };
+        if array.is_empty() || self.values.keys().any(|p| in_subtree(p, path)) {
+            return;
+        }
+
+        let mut later: Vec<(usize, String)> = self
+            .values
+            .keys()
+            .filter_map(|p| split_element(p, array).map(|(i, rest)| (i, rest.to_string())))
+            .filter(|(i, _)| *i > index)
+            .collect();
+        // Keys sort as text (`[10]` before `[2]`); shift in numeric order so
+        // no element lands on one that has not moved yet
+        later.sort();
+        for (i, rest) in later {
+            if let Some(value) = self.values.remove(&format!("{}[{}]{}", array, i, rest)) {
+                self.values
+                    .insert(format!("{}[{}]{}", array, i - 1, rest), value);
+            }
+        }
+
+        let len_path = format!("{}.__len", array);
+        if let Some(len) = self.values.get_mut(&len_path) {
+            if let Some(n) = len.value.as_integer().filter(|n| *n > index as i64) {
+                len.value = Value::Integer(n - 1);
+            }
+        }
+    }
+
     /// Convert the internal values to a nested structure for JSON serialization.
     ///
     /// Transforms flat paths like "database.host" into nested JSON:
@@ -77,10 +243,9 @@ impl ConfigValues {
         // First pass: handle empty arrays using __len metadata
         // Empty arrays have no [n] elements, so we need __len to know they exist
         for (path, config_value) in self.iter() {
-            if path.ends_with(".__len") {
+            if let Some(array_path) = array_of_len(path) {
                 if let Some(0) = config_value.value.as_integer() {
                     // This is an empty array - create it
-                    let array_path = &path[..path.len() - 6]; // Remove ".__len"
                     let segments = parse_path(array_path);
                     insert_value(&mut root, &segments, serde_json::Value::Array(Vec::new()));
                 }
@@ -90,7 +255,7 @@ impl ConfigValues {
         // Second pass: insert all actual values
         for (path, config_value) in self.iter() {
             // Skip internal metadata keys (e.g., "hosts.__len")
-            if path.contains(".__") {
+            if is_metadata(path) {
                 continue;
             }
 
@@ -102,9 +267,25 @@ impl ConfigValues {
     }
 }
 
+/// Pure function: the array whose length the metadata key `path` records.
+///
+/// `hosts.__len` belongs to `hosts`; a bare `__len`, as left by
+/// [`ConfigValues::subtree`] for an array prefix, to the root.
+pub(crate) fn array_of_len(path: &str) -> Option<&str> {
+    match path {
+        "__len" => Some(""),
+        path => path.strip_suffix(".__len"),
+    }
+}
+
+/// Pure function: check for internal metadata keys such as `hosts.__len`.
+pub(crate) fn is_metadata(path: &str) -> bool {
+    path.contains(".__") || path.starts_with("__")
+}
+
 /// A segment in a configuration path.
 #[derive(Debug, Clone, PartialEq)]
-enum PathSegment {
+pub(crate) enum PathSegment {
     /// Object key like "database" in "database.host"
     Key(String),
     /// Array index like 0 in "hosts\[0\]"
@@ -118,7 +299,7 @@ enum PathSegment {
 /// - "hosts\[0\]" -> \[Key("hosts"), Index(0)\]
 /// - "servers\[0\].host" -> \[Key("servers"), Index(0), Key("host")\]
 /// - "matrix\[0\]\[1\]" -> \[Key("matrix"), Index(0), Index(1)\]
-fn parse_path(path: &str) -> Vec<PathSegment> {
+pub(crate) fn parse_path(path: &str) -> Vec<PathSegment> {
     let mut segments = Vec::new();
     let mut current = String::new();
 
@@ -221,17 +402,22 @@ fn insert_value(root: &mut serde_json::Value, segments: &[PathSegment], value: s
 }
 
 /// Convert a Value to serde_json::Value.
-fn value_to_json(value: &crate::value::Value) -> serde_json::Value {
+pub(crate) fn value_to_json(value: &crate::value::Value) -> serde_json::Value {
     use crate::value::Value;
 
     match value {
         Value::Null => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(*b),
         Value::Integer(i) => serde_json::Value::Number((*i).into()),
+        Value::UInteger(u) => serde_json::Value::Number((*u).into()),
         Value::Float(f) => serde_json::Number::from_f64(*f)
             .map(serde_json::Value::Number)
             .unwrap_or(serde_json::Value::Null),
         Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Datetime(dt) => serde_json::Value::String(dt.to_string()),
+        Value::Bytes(bytes) => {
+            serde_json::Value::Array(bytes.iter().map(|b| (*b).into()).collect())
+        }
         Value::Array(arr) => serde_json::Value::Array(arr.iter().map(value_to_json).collect()),
         Value::Table(table) => {
             let map: serde_json::Map<String, serde_json::Value> = table
@@ -243,6 +429,35 @@ fn value_to_json(value: &crate::value::Value) -> serde_json::Value {
     }
 }
 
+/// Convert serde_json::Value to our Value type.
+pub(crate) fn json_to_value(json: &serde_json::Value) -> crate::value::Value {
+    use crate::value::Value;
+
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Bool(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::UInteger(u)
+            } else if let Some(f) = n.as_f64() {
+                Value::Float(f)
+            } else {
+                // Fallback for large numbers
+                Value::String(n.to_string())
+            }
+        }
+        serde_json::Value::String(s) => Value::String(s.clone()),
+        serde_json::Value::Array(arr) => Value::Array(arr.iter().map(json_to_value).collect()),
+        serde_json::Value::Object(map) => Value::Table(
+            map.iter()
+                .map(|(k, v)| (k.clone(), json_to_value(v)))
+                .collect(),
+        ),
+    }
+}
+
 /// Trait for configuration sources.
 ///
 /// Sources perform I/O through the `ConfigEnv` trait for testable dependency
@@ -279,6 +494,87 @@ pub trait Source: Send + Sync {
     /// Human-readable name of this source for error messages.
     fn name(&self) -> &str;
 
+    /// Whether this source's top-level tables are profiles (`[default]`, `[production]`).
+    ///
+    /// When the builder has a profile selected, values from such sources are
+    /// narrowed to the default and active profiles. Defaults to `false`.
+    fn has_profiles(&self) -> bool {
+        false
+    }
+
+    /// Path this source's values are mounted under, if any.
+    ///
+    /// Profile tables of a mounted source are looked for beneath this path
+    /// rather than at the root. Set by [`Source::at`].
+    fn mount_point(&self) -> Option<&str> {
+        None
+    }
+
+    /// Mount this source's values under `prefix`.
+    ///
+    /// Lets a file hold one section without repeating its top-level table:
+    ///
+    /// ```ignore
+    /// // database.toml holds `host = ...`, not `[database]\nhost = ...`
+    /// let config = Config::<AppConfig>::builder()
+    ///     .source(Toml::file("database.toml").at("database"))
+    ///     .source(Toml::file("cache.toml").at("cache"))
+    ///     .build()?;
+    /// ```
+    fn at(self, prefix: impl Into<String>) -> Mounted
+    where
+        Self: Sized + 'static,
+    {
+        Mounted::new(self, prefix)
+    }
+
+    /// How far this source's values are trusted. Defaults to `Trust::Normal`.
+    ///
+    /// A value from a lower-trust source that overrides a value from a
+    /// higher-trust one is reported as `ConfigWarning::LowerTrustOverride`.
+    fn trust(&self) -> Trust {
+        Trust::Normal
+    }
+
+    /// Assign a trust level to this source.
+    ///
+    /// ```ignore
+    /// let config = Config::<AppConfig>::builder()
+    ///     .source(Toml::file("/etc/app/config.toml").with_trust(Trust::High))
+    ///     .source(Env::prefix("APP_"))
+    ///     .build_with_warnings()?;
+    /// ```
+    fn with_trust(self, trust: Trust) -> Trusted
+    where
+        Self: Sized + 'static,
+    {
+        Trusted::new(self, trust)
+    }
+
+    /// Loading this source as a stillwater `Effect`.
+    ///
+    /// The effect runs against whatever `ConfigEnv` it is given, so it composes
+    /// with retries, timeouts, and `or_else` fallbacks like any other effect.
+    ///
+    /// ```ignore
+    /// use premortem::{Effect, EffectExt};
+    ///
+    /// let values = Toml::file("config.toml")
+    ///     .load_effect()
+    ///     .or_else(|_| Toml::file("/etc/app/config.toml").load_effect())
+    ///     .run(&RealEnv::new())
+    ///     .await?;
+    /// ```
+    fn load_effect<'a, E>(
+        &'a self,
+    ) -> impl Effect<Output = ConfigValues, Error = ConfigErrors, Env = E> + 'a
+    where
+        Self: Sized,
+        E: ConfigEnv + Clone + 'a,
+    {
+        stillwater::from_fn(move |env: &E| self.load(env))
+    }
+
     /// Path to watch for hot reload, if applicable.
     #[cfg(feature = "watch")]
     fn watch_path(&self) -> Option<PathBuf> {
@@ -290,6 +586,133 @@ pub trait Source: Send + Sync {
     /// Required for hot reload to rebuild configuration from the same sources.
     #[cfg(feature = "watch")]
     fn clone_box(&self) -> Box<dyn Source>;
+
+    /// The async implementation behind this source, if it has one.
+    ///
+    /// Async builds await it instead of calling `load`. Only sources added
+    /// with `ConfigBuilder::async_source` return `Some`.
+    #[cfg(feature = "async")]
+    fn as_async(&self) -> Option<&dyn AsyncSource> {
+        None
+    }
+}
+
+/// A configuration source that loads asynchronously.
+///
+/// Use this for sources that do slow I/O (remote config services, secret
+/// managers) so `ConfigBuilder::build_async()` can load them concurrently
+/// without blocking the runtime. Existing `Source`s need no changes: they are
+/// loaded alongside async sources in the same build.
+///
+/// A synchronous build (`build()`) blocks on the future with
+/// `futures::executor::block_on`, so it must not run on an async runtime's
+/// own thread when the future relies on that runtime.
+///
+/// Only available with the `async` feature enabled.
+///
+/// # Example
+///
+/// ```ignore
+/// use premortem::{AsyncConfigEnv, AsyncSource, BoxFuture, ConfigErrors, ConfigValues};
+///
+/// struct Remote { url: String }
+///
+/// impl AsyncSource for Remote {
+///     fn load<'a>(
+///         &'a self,
+///         env: &'a dyn AsyncConfigEnv,
+///     ) -> BoxFuture<'a, Result<ConfigValues, ConfigErrors>> {
+///         Box::pin(async move { fetch_values(&self.url).await })
+///     }
+///
+///     fn name(&self) -> &str {
+///         &self.url
+///     }
+/// }
+/// ```
+#[cfg(feature = "async")]
+pub trait AsyncSource: Send + Sync {
+    /// Load configuration values from this source.
+    fn load<'a>(
+        &'a self,
+        env: &'a dyn AsyncConfigEnv,
+    ) -> BoxFuture<'a, Result<ConfigValues, ConfigErrors>>;
+
+    /// Human-readable name of this source for error messages.
+    fn name(&self) -> &str;
+
+    /// Whether this source's top-level tables are profiles. Defaults to `false`.
+    fn has_profiles(&self) -> bool {
+        false
+    }
+}
+
+/// Adapter that stores an `AsyncSource` in the builder's source list.
+///
+/// Synchronous builds block on the source's future. That deadlocks when the
+/// future needs the runtime of the thread calling the build; see
+/// `ConfigBuilder::async_source`.
+#[cfg(feature = "async")]
+#[derive(Clone)]
+pub(crate) struct AsyncSourceAdapter(pub(crate) std::sync::Arc<dyn AsyncSource>);
+
+#[cfg(feature = "async")]
+impl Source for AsyncSourceAdapter {
+    fn load(&self, env: &dyn ConfigEnv) -> Result<ConfigValues, ConfigErrors> {
+        futures::executor::block_on(self.0.load(&SyncEnv(env)))
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn has_profiles(&self) -> bool {
+        self.0.has_profiles()
+    }
+
+    #[cfg(feature = "watch")]
+    fn clone_box(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    fn as_async(&self) -> Option<&dyn AsyncSource> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// Async view of a borrowed synchronous environment.
+#[cfg(feature = "async")]
+struct SyncEnv<'e>(&'e dyn ConfigEnv);
+
+#[cfg(feature = "async")]
+impl ConfigEnv for SyncEnv<'_> {
+    fn read_file(&self, path: &std::path::Path) -> std::io::Result<String> {
+        self.0.read_file(path)
+    }
+
+    fn file_exists(&self, path: &std::path::Path) -> bool {
+        self.0.file_exists(path)
+    }
+
+    fn is_directory(&self, path: &std::path::Path) -> bool {
+        self.0.is_directory(path)
+    }
+
+    fn get_env(&self, name: &str) -> Option<String> {
+        self.0.get_env(name)
+    }
+
+    fn env_vars_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        self.0.env_vars_with_prefix(prefix)
+    }
+
+    fn all_env_vars(&self) -> Vec<(String, String)> {
+        self.0.all_env_vars()
+    }
+
+    fn write_file(&self, path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+        self.0.write_file(path, contents)
+    }
 }
 
 /// Pure function: merge multiple ConfigValues by priority.
@@ -297,21 +720,331 @@ pub trait Source: Send + Sync {
 /// Later values override earlier values. This is the core merge logic
 /// that combines configuration from multiple sources.
 pub fn merge_config_values(all_values: Vec<ConfigValues>) -> ConfigValues {
+    merge_config_values_with(all_values, &BTreeMap::new())
+}
+
+/// How values from a later source are combined with a subtree that earlier
+/// sources already populated.
+///
+/// Strategies are registered per path (e.g. `"servers"`) and apply to the
+/// whole subtree under that path rather than to individual leaves.
+///
+/// # Example
+///
+/// ```ignore
+/// use premortem::{Config, MergeStrategy};
+///
+/// let config = Config::<AppConfig>::builder()
+///     .source(Toml::file("config.toml"))
+///     .source(Env::prefix("APP_"))
+///     .merge_strategy("hosts", MergeStrategy::Replace)
+///     .merge_strategy("servers", MergeStrategy::MergeByKey("name".into()))
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Later sources override individual leaf paths (the default).
+    #[default]
+    Deep,
+    /// A later source that sets anything under the path replaces the whole
+    /// subtree, including nested paths that have strategies of their own.
+    Replace,
+    /// Array elements from later sources are appended after existing elements.
+    Append,
+    /// Array elements are matched by the value of the given key field.
+    ///
+    /// Matching elements are merged leaf by leaf; unmatched elements are appended.
+    MergeByKey(String),
+}
+
+/// How far a source's values are trusted, from lowest to highest.
+///
+/// Precedence still follows source order; trust only decides which overrides
+/// are reported as warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Trust {
+    /// Values that may be set by less trusted parties (e.g. per-user overrides).
+    Low,
+    /// The default for every source.
+    #[default]
+    Normal,
+    /// Values managed by operators (e.g. a system-wide file).
+    High,
+}
+
+/// What happened to a value when it was merged into the accumulated values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeAction {
+    /// The value was set at its path, overriding any earlier value.
+    #[default]
+    Set,
+    /// The value was appended to an array by `Append` or `MergeByKey`.
+    Appended,
+    /// The value was discarded because a later source replaced its subtree.
+    Replaced,
+}
+
+/// Result of merging one source's values into the accumulated values.
+///
+/// Used by tracing to record where values actually landed after
+/// merge strategies re-indexed or replaced them.
+#[derive(Debug, Default)]
+pub(crate) struct MergeStep {
+    /// Subtrees that were cleared before this source's values were applied.
+    pub(crate) replaced: Vec<String>,
+    /// Values as they were inserted, with their final paths.
+    pub(crate) applied: Vec<(String, ConfigValue, MergeAction)>,
+}
+
+/// Pure function: merge multiple ConfigValues by priority using per-path strategies.
+///
+/// Paths not covered by any strategy are merged leaf by leaf, exactly like
+/// `merge_config_values`. When strategies are nested, the longest matching
+/// path wins.
+pub fn merge_config_values_with(
+    all_values: Vec<ConfigValues>,
+    strategies: &BTreeMap<String, MergeStrategy>,
+) -> ConfigValues {
     let mut merged = ConfigValues::empty();
 
     for values in all_values {
-        for (path, value) in values.values {
+        merge_source_values(&mut merged, values, strategies);
+    }
+
+    merged
+}
+
+/// Merge a single source's values into `merged`, reporting what was applied.
+pub(crate) fn merge_source_values(
+    merged: &mut ConfigValues,
+    values: ConfigValues,
+    strategies: &BTreeMap<String, MergeStrategy>,
+) -> MergeStep {
+    let mut step = MergeStep::default();
+
+    // A `Replace` subtree this source writes into is cleared as a whole,
+    // nested strategy subtrees included, before any value is applied
+    for (prefix, strategy) in strategies {
+        let covered = step.replaced.iter().any(|outer| in_subtree(prefix, outer));
+        if *strategy != MergeStrategy::Replace
+            || covered
+            || !values.values.keys().any(|path| in_subtree(path, prefix))
+        {
+            continue;
+        }
+        merged.values.retain(|path, _| !in_subtree(path, prefix));
+        step.replaced.push(prefix.clone());
+    }
+
+    // Within this source the most specific strategy path governs, so a
+    // `Deep` path nested in a `Replace` one is merged leaf by leaf
+    let governing = |path: &str| {
+        strategies
+            .iter()
+            .filter(|(prefix, _)| in_subtree(path, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .filter(|(_, strategy)| **strategy != MergeStrategy::Deep)
+            .map(|(prefix, _)| prefix.as_str())
+    };
+
+    // Partition this source's values by the strategy path that governs them
+    let mut grouped: BTreeMap<&str, ConfigValues> = BTreeMap::new();
+    for (path, value) in values.values {
+        if let Some(prefix) = governing(&path) {
+            grouped.entry(prefix).or_default().insert(path, value);
+        } else {
+            step.applied
+                .push((path.clone(), value.clone(), MergeAction::Set));
             merged.values.insert(path, value);
         }
     }
 
-    merged
+    for (prefix, group) in grouped {
+        match &strategies[prefix] {
+            MergeStrategy::Deep => unreachable!("deep paths are merged directly"),
+            MergeStrategy::Replace => {
+                for (path, value) in group.values {
+                    step.applied
+                        .push((path.clone(), value.clone(), MergeAction::Set));
+                    merged.values.insert(path, value);
+                }
+            }
+            MergeStrategy::Append => {
+                let offset = array_len(merged, prefix);
+                merge_elements(merged, group, prefix, &mut step, |_, _| None, offset);
+            }
+            MergeStrategy::MergeByKey(key) => {
+                let offset = array_len(merged, prefix);
+                let existing: Vec<(usize, Value)> = (0..offset)
+                    .filter_map(|i| element_field(merged, prefix, i, key).map(|v| (i, v)))
+                    .collect();
+                let find_match = |group: &ConfigValues, j: usize| {
+                    let wanted = element_field(group, prefix, j, key)?;
+                    existing.iter().find(|(_, v)| *v == wanted).map(|(i, _)| *i)
+                };
+                merge_elements(merged, group, prefix, &mut step, find_match, offset);
+            }
+        }
+    }
+
+    step
+}
+
+/// Move array elements from `group` into `merged` under `prefix`.
+///
+/// `find_match` maps a source element index to an existing element index it
+/// should be merged into; elements without a match are appended after `offset`
+/// in index order, without the gaps a sparse source may leave between them.
+fn merge_elements(
+    merged: &mut ConfigValues,
+    group: ConfigValues,
+    prefix: &str,
+    step: &mut MergeStep,
+    find_match: impl Fn(&ConfigValues, usize) -> Option<usize>,
+    offset: usize,
+) {
+    let len_path = format!("{}.__len", prefix);
+
+    // Decide where each source element lands before moving any values.
+    // Elements landing in a previously empty array are plain sets, not appends.
+    let new_element = if offset == 0 {
+        MergeAction::Set
+    } else {
+        MergeAction::Appended
+    };
+    let indexes: BTreeSet<usize> = group
+        .values
+        .keys()
+        .filter_map(|path| split_element(path, prefix))
+        .map(|(j, _)| j)
+        .collect();
+    let mut next = offset;
+    let targets: BTreeMap<usize, (usize, MergeAction)> = indexes
+        .into_iter()
+        .map(|j| match find_match(&group, j) {
+            Some(i) => (j, (i, MergeAction::Set)),
+            None => {
+                next += 1;
+                (j, (next - 1, new_element))
+            }
+        })
+        .collect();
+
+    let len_source = group
+        .values
+        .get(&len_path)
+        .or_else(|| merged.values.get(&len_path))
+        .map(|cv| cv.source.clone());
+
+    for (path, value) in group.values {
+        if path == len_path {
+            continue;
+        }
+
+        let target = split_element(&path, prefix)
+            .and_then(|(j, rest)| targets.get(&j).map(|target| (*target, rest)));
+        let (path, action) = match target {
+            Some(((target, action), rest)) => (format!("{}[{}]{}", prefix, target, rest), action),
+            None => (path, MergeAction::Set),
+        };
+
+        step.applied.push((path.clone(), value.clone(), action));
+        merged.values.insert(path, value);
+    }
+
+    // Keep the length metadata in sync with the merged element count
+    if let Some(source) = len_source {
+        let len = ConfigValue::new(Value::Integer(next as i64), source);
+        step.applied
+            .push((len_path.clone(), len.clone(), MergeAction::Set));
+        merged.values.insert(len_path, len);
+    }
+}
+
+/// Check whether `path` is `prefix` itself or lies underneath it.
+pub(crate) fn in_subtree(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.') || rest.starts_with('['),
+        None => false,
+    }
+}
+
+/// Split `prefix[i]rest` into the element index and the remaining path.
+fn split_element<'a>(path: &'a str, prefix: &str) -> Option<(usize, &'a str)> {
+    let rest = path.strip_prefix(prefix)?.strip_prefix('[')?;
+    let close = rest.find(']')?;
+    let index = rest[..close].parse().ok()?;
+    Some((index, &rest[close + 1..]))
+}
+
+/// Pure function: translate a path glob into an anchored regex.
+///
+/// `*` and `?` match within a single key or index, `**` across any number of
+/// segments.
+pub(crate) fn glob_to_regex(glob: &str) -> regex::Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**.` also matches nothing, so `**.dsn` matches a top-level `dsn`
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                    pattern.push_str(r"(?:.*\.)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str(r"[^.\[\]]*"),
+            '?' => pattern.push_str(r"[^.\[\]]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    regex::Regex::new(&pattern).expect("escaped glob is a valid regex")
+}
+
+/// Number of elements in the array at `prefix`.
+///
+/// Uses the `__len` metadata when present, and the highest element index
+/// otherwise (environment variables don't record lengths).
+fn array_len(values: &ConfigValues, prefix: &str) -> usize {
+    let recorded = values
+        .get(&format!("{}.__len", prefix))
+        .and_then(|cv| cv.value.as_integer())
+        .map(|len| len.max(0) as usize)
+        .unwrap_or(0);
+
+    let indexed = values
+        .values
+        .keys()
+        .filter_map(|path| split_element(path, prefix))
+        .map(|(index, _)| index + 1)
+        .max()
+        .unwrap_or(0);
+
+    recorded.max(indexed)
+}
+
+/// Look up a field of an array element, whether it was flattened
+/// (`servers[0].name`) or stored as an inline table (`servers[0]`).
+fn element_field(values: &ConfigValues, prefix: &str, index: usize, key: &str) -> Option<Value> {
+    let element = format!("{}[{}]", prefix, index);
+    if let Some(cv) = values.get(&format!("{}.{}", element, key)) {
+        return Some(cv.value.clone());
+    }
+    values
+        .get(&element)
+        .and_then(|cv| cv.value.as_table())
+        .and_then(|table| table.get(key))
+        .cloned()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::SourceLocation;
+    use crate::error::{ConfigError, SourceLocation};
     use crate::value::Value;
 
     #[test]
@@ -335,6 +1068,62 @@ mod tests {
         assert_eq!(val.value.as_str(), Some("localhost"));
     }
 
+    #[test]
+    fn test_config_values_subtree_and_mount_round_trip() {
+        let mut values = ConfigValues::empty();
+        for path in [
+            "api.port",
+            "api.hosts[0]",
+            "api.hosts.__len",
+            "apiary",
+            "worker.port",
+        ] {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(Value::Integer(1), SourceLocation::new("test").with_line(3)),
+            );
+        }
+
+        let api = values.subtree("api");
+        let paths: Vec<_> = api.paths().cloned().collect();
+        assert_eq!(paths, vec!["hosts.__len", "hosts[0]", "port"]);
+        assert_eq!(api.get("port").unwrap().source.line, Some(3));
+
+        let mounted = api.mounted_at("services.api");
+        assert!(mounted.contains("services.api.hosts[0]"));
+        assert_eq!(mounted.subtree("services.api").len(), 3);
+    }
+
+    #[test]
+    fn test_config_values_subtree_of_array() {
+        let mut values = ConfigValues::empty();
+        for (path, value) in [
+            ("servers[0].name", Value::from("api")),
+            ("servers[1].name", Value::from("admin")),
+            ("servers.__len", Value::from(2)),
+            ("serverside", Value::from(true)),
+            ("empty.__len", Value::from(0)),
+        ] {
+            values.insert(
+                path.to_string(),
+                ConfigValue::new(value, SourceLocation::new("test")),
+            );
+        }
+
+        let servers = values.subtree("servers");
+        let paths: Vec<_> = servers.paths().cloned().collect();
+        assert_eq!(paths, vec!["[0].name", "[1].name", "__len"]);
+        assert_eq!(
+            servers.to_json(),
+            serde_json::json!([{"name": "api"}, {"name": "admin"}])
+        );
+        assert_eq!(values.subtree("empty").to_json(), serde_json::json!([]));
+
+        let mounted = servers.mounted_at("upstream");
+        assert!(mounted.contains("upstream[1].name"));
+        assert!(mounted.contains("upstream.__len"));
+    }
+
     #[test]
     fn test_merge_config_values() {
         let mut v1 = ConfigValues::empty();
@@ -620,4 +1409,363 @@ mod tests {
         assert!(json["config"]["items"].is_array());
         assert!(json["config"]["items"].as_array().unwrap().is_empty());
     }
+
+    fn cv(value: impl Into<Value>, source: &str) -> ConfigValue {
+        ConfigValue::new(value, SourceLocation::new(source))
+    }
+
+    fn hosts(source: &str, hosts: &[&str]) -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (i, host) in hosts.iter().enumerate() {
+            values.insert(format!("hosts[{}]", i), cv(*host, source));
+        }
+        values.insert("hosts.__len".to_string(), cv(hosts.len() as i64, source));
+        values
+    }
+
+    #[test]
+    fn test_merge_deep_mixes_array_elements() {
+        let mut env = ConfigValues::empty();
+        env.insert("hosts[0]".to_string(), cv("env-host", "env"));
+
+        let merged = merge_config_values(vec![hosts("file", &["a", "b", "c"]), env]);
+
+        let json = merged.to_json();
+        assert_eq!(json["hosts"], serde_json::json!(["env-host", "b", "c"]));
+    }
+
+    #[test]
+    fn test_merge_strategy_replace() {
+        let mut env = ConfigValues::empty();
+        env.insert("hosts[0]".to_string(), cv("env-host", "env"));
+
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Replace)]);
+        let merged =
+            merge_config_values_with(vec![hosts("file", &["a", "b", "c"]), env], &strategies);
+
+        let json = merged.to_json();
+        assert_eq!(json["hosts"], serde_json::json!(["env-host"]));
+        assert!(!merged.contains("hosts.__len"));
+    }
+
+    #[test]
+    fn test_merge_strategy_replace_clears_nested_strategies() {
+        let mut file = ConfigValues::empty();
+        file.insert("database.host".to_string(), cv("db", "file"));
+        file.insert("database.pool.size".to_string(), cv(10, "file"));
+        file.insert("database.pool.timeout".to_string(), cv(30, "file"));
+        file.insert("database.replicas[0]".to_string(), cv("r1", "file"));
+        file.insert("database.replicas.__len".to_string(), cv(1, "file"));
+        let mut env = ConfigValues::empty();
+        env.insert("database.port".to_string(), cv(5432, "env"));
+        env.insert("database.pool.size".to_string(), cv(20, "env"));
+        env.insert("database.replicas[0]".to_string(), cv("r2", "env"));
+
+        let strategies = BTreeMap::from([
+            ("database".to_string(), MergeStrategy::Replace),
+            ("database.pool".to_string(), MergeStrategy::Deep),
+            ("database.replicas".to_string(), MergeStrategy::Append),
+        ]);
+        let step = {
+            let mut merged = ConfigValues::empty();
+            merge_source_values(&mut merged, file.clone(), &strategies);
+            merge_source_values(&mut merged, env.clone(), &strategies)
+        };
+        let merged = merge_config_values_with(vec![file, env], &strategies);
+
+        assert_eq!(step.replaced, vec!["database".to_string()]);
+        assert!(!merged.contains("database.host"));
+        assert!(!merged.contains("database.pool.timeout"));
+        assert_eq!(
+            merged.get("database.port").unwrap().value,
+            Value::Integer(5432)
+        );
+        assert_eq!(
+            merged.get("database.pool.size").unwrap().value,
+            Value::Integer(20)
+        );
+        assert_eq!(
+            merged.to_json()["database"]["replicas"],
+            serde_json::json!(["r2"])
+        );
+    }
+
+    #[test]
+    fn test_merge_strategy_replace_cleared_by_nested_values_only() {
+        let mut file = ConfigValues::empty();
+        file.insert("database.host".to_string(), cv("db", "file"));
+        file.insert("database.pool.size".to_string(), cv(10, "file"));
+        let mut env = ConfigValues::empty();
+        env.insert("database.pool.size".to_string(), cv(20, "env"));
+
+        let strategies = BTreeMap::from([
+            ("database".to_string(), MergeStrategy::Replace),
+            ("database.pool".to_string(), MergeStrategy::Deep),
+        ]);
+        let merged = merge_config_values_with(vec![file, env], &strategies);
+
+        assert!(!merged.contains("database.host"));
+        assert_eq!(
+            merged.get("database.pool.size").unwrap().source.source,
+            "env"
+        );
+    }
+
+    #[test]
+    fn test_merge_strategy_replace_untouched_when_source_is_silent() {
+        let mut other = ConfigValues::empty();
+        other.insert("name".to_string(), cv("app", "env"));
+
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Replace)]);
+        let merged = merge_config_values_with(vec![hosts("file", &["a", "b"]), other], &strategies);
+
+        assert_eq!(merged.to_json()["hosts"], serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_merge_strategy_append() {
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Append)]);
+        let merged = merge_config_values_with(
+            vec![hosts("file", &["a", "b"]), hosts("local", &["c"])],
+            &strategies,
+        );
+
+        assert_eq!(
+            merged.to_json()["hosts"],
+            serde_json::json!(["a", "b", "c"])
+        );
+        assert_eq!(
+            merged.get("hosts.__len").unwrap().value.as_integer(),
+            Some(3)
+        );
+        assert_eq!(merged.get("hosts[2]").unwrap().source.source, "local");
+    }
+
+    #[test]
+    fn test_merge_strategy_append_without_len_metadata() {
+        let mut env = ConfigValues::empty();
+        env.insert("hosts[0]".to_string(), cv("x", "env"));
+        env.insert("hosts[1]".to_string(), cv("y", "env"));
+
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Append)]);
+        let merged = merge_config_values_with(vec![hosts("file", &["a"]), env], &strategies);
+
+        assert_eq!(
+            merged.to_json()["hosts"],
+            serde_json::json!(["a", "x", "y"])
+        );
+        assert_eq!(
+            merged.get("hosts.__len").unwrap().value.as_integer(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_merge_strategy_append_compacts_sparse_indexes() {
+        let mut env = ConfigValues::empty();
+        env.insert("hosts[1]".to_string(), cv("x", "env"));
+        env.insert("hosts[4]".to_string(), cv("y", "env"));
+
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Append)]);
+        let merged = merge_config_values_with(vec![hosts("file", &["a", "b"]), env], &strategies);
+
+        assert_eq!(
+            merged.to_json()["hosts"],
+            serde_json::json!(["a", "b", "x", "y"])
+        );
+        assert_eq!(
+            merged.get("hosts.__len").unwrap().value.as_integer(),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_merge_strategy_merge_by_key() {
+        let mut base = ConfigValues::empty();
+        base.insert("servers[0].name".to_string(), cv("api", "file"));
+        base.insert("servers[0].port".to_string(), cv(8080i64, "file"));
+        base.insert("servers[1].name".to_string(), cv("admin", "file"));
+        base.insert("servers[1].port".to_string(), cv(9090i64, "file"));
+        base.insert("servers.__len".to_string(), cv(2i64, "file"));
+
+        let mut overlay = ConfigValues::empty();
+        overlay.insert("servers[0].name".to_string(), cv("admin", "local"));
+        overlay.insert("servers[0].port".to_string(), cv(9999i64, "local"));
+        overlay.insert("servers[1].name".to_string(), cv("metrics", "local"));
+        overlay.insert("servers[1].port".to_string(), cv(9100i64, "local"));
+        overlay.insert("servers.__len".to_string(), cv(2i64, "local"));
+
+        let strategies = BTreeMap::from([(
+            "servers".to_string(),
+            MergeStrategy::MergeByKey("name".to_string()),
+        )]);
+        let merged = merge_config_values_with(vec![base, overlay], &strategies);
+
+        let json = merged.to_json();
+        assert_eq!(
+            json["servers"],
+            serde_json::json!([
+                {"name": "api", "port": 8080},
+                {"name": "admin", "port": 9999},
+                {"name": "metrics", "port": 9100},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_source_values_reports_actions() {
+        let strategies = BTreeMap::from([("hosts".to_string(), MergeStrategy::Append)]);
+        let mut merged = ConfigValues::empty();
+        merge_source_values(&mut merged, hosts("file", &["a"]), &strategies);
+        let step = merge_source_values(&mut merged, hosts("local", &["b"]), &strategies);
+
+        assert!(step.replaced.is_empty());
+        assert!(step
+            .applied
+            .iter()
+            .any(|(path, _, action)| path == "hosts[1]" && *action == MergeAction::Appended));
+    }
+
+    #[test]
+    fn test_in_subtree() {
+        assert!(in_subtree("hosts", "hosts"));
+        assert!(in_subtree("hosts[0]", "hosts"));
+        assert!(in_subtree("hosts.__len", "hosts"));
+        assert!(in_subtree("database.pool.size", "database"));
+        assert!(!in_subtree("hostsfile", "hosts"));
+        assert!(!in_subtree("other", "hosts"));
+    }
+
+    fn servers() -> ConfigValues {
+        let mut values = ConfigValues::empty();
+        for (path, value) in [
+            ("servers[0].host", Value::from("a")),
+            ("servers[0].port", Value::from(80)),
+            ("servers[1].host", Value::from("b")),
+            ("servers[1].port", Value::from(81)),
+            ("servers[2].host", Value::from("c")),
+            ("servers[2].port", Value::from(82)),
+            ("servers.__len", Value::from(3)),
+            ("database.primary.port", Value::from(5432)),
+            ("database.replica.port", Value::from(5433)),
+            ("database.replica.host", Value::from("replica")),
+        ] {
+            values.insert(path.to_string(), cv(value, "config.toml"));
+        }
+        values
+    }
+
+    #[test]
+    fn test_remove_array_element_shifts_later_elements() {
+        let mut values = hosts("file", &["a", "b", "c"]);
+
+        assert_eq!(values.remove("hosts[0]").unwrap().value, Value::from("a"));
+        assert_eq!(values.to_json()["hosts"], serde_json::json!(["b", "c"]));
+        assert_eq!(values.get("hosts.__len").unwrap().value, Value::Integer(2));
+        assert_eq!(values.get("hosts.__len").unwrap().source.source, "file");
+        assert!(values.remove("hosts[5]").is_none());
+    }
+
+    #[test]
+    fn test_remove_shifts_elements_in_numeric_order() {
+        let names: Vec<String> = (0..12).map(|i| format!("h{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut values = hosts("file", &names);
+
+        assert_eq!(values.remove("hosts[1]").unwrap().value, Value::from("h1"));
+        let expected: Vec<&str> = names.iter().copied().filter(|n| *n != "h1").collect();
+        assert_eq!(values.to_json()["hosts"], serde_json::json!(expected));
+        assert_eq!(values.get("hosts.__len").unwrap().value, Value::Integer(11));
+    }
+
+    #[test]
+    fn test_remove_subtree() {
+        let mut values = servers();
+
+        let removed = values.remove_subtree("servers[1]");
+        assert_eq!(removed.len(), 2);
+        assert!(removed.contains("servers[1].host"));
+        assert_eq!(
+            values.to_json()["servers"],
+            serde_json::json!([{"host": "a", "port": 80}, {"host": "c", "port": 82}])
+        );
+        assert_eq!(
+            values.get("servers.__len").unwrap().value,
+            Value::Integer(2)
+        );
+
+        let removed = values.remove_subtree("servers");
+        assert!(removed.contains("servers.__len"));
+        assert!(!values.paths().any(|p| in_subtree(p, "servers")));
+        assert!(values.remove_subtree("missing").is_empty());
+    }
+
+    #[test]
+    fn test_rename_prefix_moves_array_metadata() {
+        let mut values = servers();
+
+        assert!(values.rename_prefix("servers", "upstream.servers"));
+        assert_eq!(
+            values.get("upstream.servers.__len").unwrap().value,
+            Value::Integer(3)
+        );
+        assert!(values.contains("upstream.servers[2].port"));
+        assert!(!values.contains("servers[0].host"));
+        assert!(values.rename_prefix("upstream", ""));
+        assert!(values.contains("servers[0].host"));
+        assert!(!values.rename_prefix("missing", "other"));
+
+        assert!(values.rename_prefix("", "upstream"));
+        assert!(values.contains("upstream.servers[0].host"));
+        assert!(values.contains("upstream.servers.__len"));
+        assert!(!values.paths().any(|p| p.starts_with("upstreamservers")));
+    }
+
+    #[test]
+    fn test_query_matches_globs() {
+        let values = servers();
+        let paths = |pattern: &str| -> Vec<String> {
+            values.query(pattern).map(|(p, _)| p.clone()).collect()
+        };
+
+        assert_eq!(
+            paths("servers[*].host"),
+            vec!["servers[0].host", "servers[1].host", "servers[2].host"]
+        );
+        assert_eq!(
+            paths("database.*.port"),
+            vec!["database.primary.port", "database.replica.port"]
+        );
+        assert_eq!(paths("**.host").len(), 4);
+        assert!(paths("servers.*").is_empty());
+    }
+
+    #[test]
+    fn test_get_as_deserializes_one_node() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        let values = servers();
+        let server: Server = values.get_as("servers[1]").unwrap();
+        assert_eq!(server.host, "b");
+        assert_eq!(values.get_as::<Vec<Server>>("servers").unwrap().len(), 3);
+        assert_eq!(values.get_as::<u16>("database.primary.port").unwrap(), 5432);
+        assert_eq!(
+            values.get_as::<Option<u16>>("database.backup").unwrap(),
+            None
+        );
+
+        let errors = values.get_as::<u8>("database.replica.port").unwrap_err();
+        assert_eq!(errors.first().path(), Some("database.replica.port"));
+        let errors = values.get_as::<Server>("database.primary").unwrap_err();
+        assert_eq!(errors.first().path(), Some("database.primary.host"));
+        let errors = values.get_as::<u16>("database.backup.port").unwrap_err();
+        assert!(matches!(
+            errors.first(),
+            ConfigError::MissingField { path, .. } if path == "database.backup.port"
+        ));
+    }
 }
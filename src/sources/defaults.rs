@@ -46,7 +46,8 @@ use serde::Serialize;
 
 use crate::env::ConfigEnv;
 use crate::error::{ConfigError, ConfigErrors, SourceErrorKind, SourceLocation};
-use crate::source::{ConfigValues, Source};
+use crate::secret::with_exposed_secrets;
+use crate::source::{json_to_value, ConfigValues, Source};
 use crate::value::{ConfigValue, Value};
 
 /// The internal source type for defaults.
@@ -311,8 +312,8 @@ fn serialize_to_config_values<T: Serialize>(
     value: &T,
     source_name: &str,
 ) -> Result<ConfigValues, ConfigErrors> {
-    // Use serde_json as intermediate format
-    let json = serde_json::to_value(value).map_err(|e| {
+    // Use serde_json as intermediate format; secrets are defaults like any other value
+    let json = with_exposed_secrets(|| serde_json::to_value(value)).map_err(|e| {
         ConfigErrors::single(ConfigError::SourceError {
             source_name: source_name.to_string(),
             kind: SourceErrorKind::Other {
@@ -367,31 +368,6 @@ fn flatten_json(
     }
 }
 
-/// Convert serde_json::Value to our Value type.
-fn json_to_value(json: &serde_json::Value) -> Value {
-    match json {
-        serde_json::Value::Null => Value::Null,
-        serde_json::Value::Bool(b) => Value::Bool(*b),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                Value::Integer(i)
-            } else if let Some(f) = n.as_f64() {
-                Value::Float(f)
-            } else {
-                // Fallback for large numbers
-                Value::String(n.to_string())
-            }
-        }
-        serde_json::Value::String(s) => Value::String(s.clone()),
-        serde_json::Value::Array(arr) => Value::Array(arr.iter().map(json_to_value).collect()),
-        serde_json::Value::Object(map) => Value::Table(
-            map.iter()
-                .map(|(k, v)| (k.clone(), json_to_value(v)))
-                .collect(),
-        ),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,6 +439,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_defaults_from_keeps_secret_values() {
+        use crate::{Config, ConfigValidation, Secret, Validate};
+
+        #[derive(Clone, Default, Serialize, Deserialize)]
+        struct SecretConfig {
+            password: Secret<String>,
+            pin: Secret<u32>,
+        }
+
+        impl Validate for SecretConfig {
+            fn validate(&self) -> ConfigValidation<()> {
+                stillwater::Validation::Success(())
+            }
+        }
+
+        let defaults = SecretConfig {
+            password: Secret::new("changeme".to_string()),
+            pin: Secret::new(1234),
+        };
+        let config = Config::<SecretConfig>::builder()
+            .source(Defaults::from(defaults))
+            .build_with_env(&MockEnv::new())
+            .expect("secret defaults should deserialize");
+
+        assert_eq!(config.password.expose_secret(), "changeme");
+        assert_eq!(*config.pin.expose_secret(), 1234);
+
+        let config = Config::<SecretConfig>::builder()
+            .source(Defaults::from(SecretConfig::default()))
+            .build_with_env(&MockEnv::new())
+            .expect("default secrets should deserialize");
+        assert_eq!(config.password.expose_secret(), "");
+        assert_eq!(*config.pin.expose_secret(), 0);
+    }
+
     #[test]
     fn test_defaults_from_closure() {
         let env = MockEnv::new();
@@ -23,12 +23,18 @@
 //! let config = Config::<AppConfig>::builder()
 //!     .source(Env::prefix("APP_").map("DB_HOST", "database.host"))
 //!     .build()?;
+//!
+//! // Map variables to the fields of AppConfig (APP_MAX_CONNECTIONS -> max_connections)
+//! let config = Config::<AppConfig>::builder()
+//!     .source(Env::prefix("APP_").for_type::<AppConfig>())
+//!     .build()?;
 //! ```
 
 use std::collections::{HashMap, HashSet};
 
 use crate::env::ConfigEnv;
 use crate::error::{ConfigErrors, SourceLocation};
+use crate::schema::{ConfigSchema, FieldInfo};
 use crate::source::{ConfigValues, Source};
 use crate::value::{ConfigValue, Value};
 
@@ -65,6 +71,7 @@ pub struct Env {
     custom_mappings: HashMap<String, String>,
     excluded: HashSet<String>,
     required_vars: HashSet<String>,
+    fields: Option<Vec<FieldInfo>>,
 }
 
 impl Env {
@@ -91,6 +98,7 @@ impl Env {
             custom_mappings: HashMap::new(),
             excluded: HashSet::new(),
             required_vars: HashSet::new(),
+            fields: None,
         }
     }
 
@@ -148,6 +156,34 @@ impl Env {
         self
     }
 
+    /// Map variables to the fields of `T` instead of splitting at every separator.
+    ///
+    /// With the default `_` separator, `APP_MAX_CONNECTIONS` becomes
+    /// `max.connections`. With `for_type`, each variable is resolved against
+    /// the field tree of `T` (from `#[derive(ConfigSchema)]`), so it becomes
+    /// `max_connections` when that is the field that exists.
+    ///
+    /// A variable that matches more than one field (`max_connections` and
+    /// `max.connections` both exist) fails the load; add a [`map`](Self::map)
+    /// to choose. A variable that matches no field keeps the default mapping,
+    /// which [`build_with_warnings`](crate::ConfigBuilder::build_with_warnings)
+    /// reports as [`ConfigWarning::UnmatchedEnvVar`](crate::ConfigWarning::UnmatchedEnvVar)
+    /// if nothing consumes it. Custom mappings take precedence.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::Env;
+    ///
+    /// let source = Env::prefix("APP_").for_type::<AppConfig>();
+    /// // APP_MAX_CONNECTIONS -> max_connections
+    /// // APP_DATABASE_POOL_SIZE -> database.pool_size
+    /// ```
+    pub fn for_type<T: ConfigSchema>(mut self) -> Self {
+        self.fields = Some(T::fields());
+        self
+    }
+
     /// Exclude specific environment variables.
     ///
     /// Excluded variables will not be loaded even if they match the prefix.
@@ -255,6 +291,102 @@ impl Env {
         }
         self
     }
+
+    /// The environment variable that sets the config value at `path`.
+    ///
+    /// Custom mappings are honored. Returns `None` when no variable can reach
+    /// the path, i.e. a path segment contains the separator (`pool_size` with
+    /// the default `_` separator) and neither a mapping nor
+    /// [`for_type`](Self::for_type) resolves it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::Env;
+    ///
+    /// let env = Env::prefix("APP_");
+    /// assert_eq!(env.var_name("database.host").as_deref(), Some("APP_DATABASE_HOST"));
+    /// assert_eq!(env.var_name("hosts[0]").as_deref(), Some("APP_HOSTS_0"));
+    /// ```
+    pub fn var_name(&self, path: &str) -> Option<String> {
+        let mapped = self
+            .custom_mappings
+            .iter()
+            .filter(|(_, target)| target.as_str() == path)
+            .map(|(suffix, _)| suffix.clone())
+            .min();
+
+        let suffix = match mapped {
+            Some(suffix) => suffix,
+            None => {
+                let mut parts = Vec::new();
+                let mut ambiguous = false;
+                for segment in path.split('.') {
+                    let (name, indices) = match segment.find('[') {
+                        Some(open) => segment.split_at(open),
+                        None => (segment, ""),
+                    };
+                    if name.is_empty() {
+                        return None;
+                    }
+                    let name = match self.fields {
+                        Some(_) => name.replace('-', "_"),
+                        None => name.to_string(),
+                    };
+                    ambiguous |= name.contains(self.separator.as_str());
+                    parts.push(name.to_uppercase());
+                    parts.extend(
+                        indices
+                            .split(['[', ']'])
+                            .filter(|index| !index.is_empty())
+                            .map(str::to_string),
+                    );
+                }
+                let suffix = parts.join(&self.separator);
+                // The variable must lead back to this path; a separator inside
+                // a name only does when the field tree resolves it
+                let reaches = match self.resolve(&suffix) {
+                    Ok(Some(resolved)) => resolved == path,
+                    Ok(None) => !ambiguous,
+                    Err(_) => false,
+                };
+                if !reaches {
+                    return None;
+                }
+                suffix
+            }
+        };
+
+        // A prefix that already ends in a delimiter (`APP_`) is used as is
+        if self.prefix.is_empty() || !self.prefix.ends_with(|c: char| c.is_alphanumeric()) {
+            Some(format!("{}{}", self.prefix, suffix))
+        } else {
+            Some(format!("{}{}{}", self.prefix, self.separator, suffix))
+        }
+    }
+
+    /// The config path for a variable suffix, from the custom mappings or the
+    /// field tree, or `None` when neither covers it.
+    ///
+    /// Fails with every candidate path when the field tree has several.
+    fn resolve(&self, suffix: &str) -> Result<Option<String>, Vec<String>> {
+        if let Some(mapped) = self.custom_mappings.get(suffix) {
+            return Ok(Some(mapped.clone()));
+        }
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+        let lower = suffix.to_lowercase();
+        let tokens: Vec<&str> = lower.split(self.separator.as_str()).collect();
+        let mut candidates = field_paths(&tokens, fields, &self.separator);
+        candidates.sort();
+        candidates.dedup();
+        match candidates.len() {
+            0 => Ok(None),
+            1 => Ok(candidates.pop()),
+            _ => Err(candidates),
+        }
+    }
 }
 
 #[cfg(feature = "watch")]
@@ -266,7 +398,7 @@ impl Source for Env {
     /// The `ConfigEnv` parameter enables dependency injection for testing.
     /// In production, use `RealEnv`; in tests, use `MockEnv`.
     fn load(&self, env: &dyn ConfigEnv) -> Result<ConfigValues, ConfigErrors> {
-        use crate::error::ConfigError;
+        use crate::error::{ConfigError, SourceErrorKind};
 
         // FIRST: Check all required environment variables
         let mut errors = Vec::new();
@@ -278,8 +410,9 @@ impl Source for Env {
             };
 
             if env.get_env(&full_name).is_none() {
+                let path = self.resolve(var_name).ok().flatten();
                 errors.push(ConfigError::MissingField {
-                    path: suffix_to_path(var_name, &self.separator),
+                    path: path.unwrap_or_else(|| suffix_to_path(var_name, &self.separator)),
                     source_location: Some(SourceLocation::env(&full_name)),
                     searched_sources: vec!["environment".to_string()],
                 });
@@ -326,12 +459,25 @@ impl Source for Env {
             let suffix = &key[self.prefix.len()..];
             let suffix = suffix.strip_prefix(&self.separator).unwrap_or(suffix);
 
-            // Check for custom mapping
-            let path = if let Some(mapped) = self.custom_mappings.get(suffix) {
-                mapped.clone()
-            } else {
+            // Check for custom mapping or a field of the target type
+            let path = match self.resolve(suffix) {
+                Ok(Some(path)) => path,
                 // Convert suffix to config path
-                suffix_to_path(suffix, &self.separator)
+                Ok(None) => suffix_to_path(suffix, &self.separator),
+                Err(candidates) => {
+                    errors.push(ConfigError::SourceError {
+                        source_name: self.name().to_string(),
+                        kind: SourceErrorKind::Other {
+                            message: format!(
+                                "{} matches several fields ({}); add Env::map(\"{}\", ...) to choose one",
+                                key,
+                                candidates.join(", "),
+                                suffix
+                            ),
+                        },
+                    });
+                    continue;
+                }
             };
 
             // Parse value (pure function)
@@ -348,7 +494,10 @@ impl Source for Env {
             );
         }
 
-        Ok(values)
+        match ConfigErrors::from_vec(errors) {
+            Some(errors) => Err(errors),
+            None => Ok(values),
+        }
     }
 
     fn name(&self) -> &str {
@@ -391,6 +540,50 @@ fn suffix_to_path(suffix: &str, separator: &str) -> String {
     path_parts.join(".")
 }
 
+/// Pure function: every path in `fields` that the lowercase suffix `tokens`
+/// can spell.
+///
+/// A field name may span several tokens (`max_connections` is `MAX` and
+/// `CONNECTIONS` with the `_` separator); numeric tokens after a field are
+/// array indices. Flattened fields are matched at their parent's level, and
+/// `-` in a field name matches `_`.
+fn field_paths(tokens: &[&str], fields: &[FieldInfo], separator: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for field in fields {
+        if field.flatten {
+            paths.extend(field_paths(tokens, &field.children, separator));
+            continue;
+        }
+        // Variable names cannot hold `-`, so `max-conns` is spelled `MAX_CONNS`
+        let name = field.name.to_lowercase().replace('-', "_");
+        for end in 1..=tokens.len() {
+            if tokens[..end].join(separator) != name {
+                continue;
+            }
+            let rest = &tokens[end..];
+            let indices = rest
+                .iter()
+                .take_while(|t| t.parse::<usize>().is_ok())
+                .count();
+            let mut path = field.name.to_string();
+            for index in &rest[..indices] {
+                path.push_str(&format!("[{}]", index));
+            }
+            let rest = &rest[indices..];
+            if rest.is_empty() {
+                if field.children.is_empty() {
+                    paths.push(path);
+                }
+            } else {
+                for child in field_paths(rest, &field.children, separator) {
+                    paths.push(format!("{}.{}", path, child));
+                }
+            }
+        }
+    }
+    paths
+}
+
 /// Pure function: parse environment variable value.
 ///
 /// Supports list parsing and type inference.
@@ -410,11 +603,14 @@ fn parse_env_value(value: &str, list_separator: Option<&str>) -> Value {
 ///
 /// Attempts to parse in order: integer, boolean, float, then string.
 /// Note: "0" and "1" are parsed as integers, not booleans.
-fn parse_scalar(value: &str) -> Value {
+pub(crate) fn parse_scalar(value: &str) -> Value {
     // Try integer first (before boolean, since "0" and "1" are commonly integers)
     if let Ok(i) = value.parse::<i64>() {
         return Value::Integer(i);
     }
+    if let Ok(u) = value.parse::<u64>() {
+        return Value::UInteger(u);
+    }
 
     // Try boolean (only for explicit boolean strings, not "0"/"1")
     match value.to_lowercase().as_str() {
@@ -752,6 +948,144 @@ mod tests {
         assert_eq!(source.name(), "environment");
     }
 
+    #[test]
+    fn test_var_name_round_trips_paths() {
+        let env = Env::prefix("APP_");
+        assert_eq!(
+            env.var_name("database.host").as_deref(),
+            Some("APP_DATABASE_HOST")
+        );
+        assert_eq!(env.var_name("hosts[1]").as_deref(), Some("APP_HOSTS_1"));
+        assert_eq!(env.var_name("database.pool_size"), None);
+        assert_eq!(
+            suffix_to_path("DATABASE_HOST", "_"),
+            "database.host".to_string()
+        );
+
+        let env = Env::prefix("APP")
+            .separator("__")
+            .map("DB_HOST", "database.host");
+        assert_eq!(
+            env.var_name("database.host").as_deref(),
+            Some("APP__DB_HOST")
+        );
+        assert_eq!(
+            env.var_name("database.pool_size").as_deref(),
+            Some("APP__DATABASE__POOL_SIZE")
+        );
+
+        let env = Env::prefix("APP_").separator("__");
+        assert_eq!(
+            env.var_name("database.host").as_deref(),
+            Some("APP_DATABASE__HOST")
+        );
+    }
+
+    fn field(name: &'static str, children: Vec<FieldInfo>) -> FieldInfo {
+        FieldInfo {
+            name,
+            rust_type: "",
+            description: None,
+            validators: &[],
+            required: true,
+            has_default: false,
+            sensitive: false,
+            flatten: false,
+            children,
+        }
+    }
+
+    struct ServiceSchema;
+
+    impl ConfigSchema for ServiceSchema {
+        fn json_schema() -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn fields() -> Vec<FieldInfo> {
+            vec![
+                field("max_connections", vec![]),
+                field("allowed-hosts", vec![]),
+                field(
+                    "database",
+                    vec![field("pool_size", vec![]), field("host", vec![])],
+                ),
+                FieldInfo {
+                    flatten: true,
+                    ..field("limits", vec![field("request_timeout", vec![])])
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn test_for_type_resolves_multi_word_fields() {
+        let env = MockEnv::new()
+            .with_env("APP_MAX_CONNECTIONS", "100")
+            .with_env("APP_ALLOWED_HOSTS_1", "b.com")
+            .with_env("APP_DATABASE_POOL_SIZE", "10")
+            .with_env("APP_REQUEST_TIMEOUT", "30")
+            .with_env("APP_DATABSE_HOST", "typo");
+
+        let source = Env::prefix("APP_").for_type::<ServiceSchema>();
+        let values = source.load(&env).expect("should load successfully");
+
+        let integer = |path: &str| values.get(path).and_then(|v| v.value.as_integer());
+        assert_eq!(integer("max_connections"), Some(100));
+        assert_eq!(integer("database.pool_size"), Some(10));
+        assert_eq!(integer("request_timeout"), Some(30));
+        assert!(values.get("allowed-hosts[1]").is_some());
+        // No field matches, so the default mapping is kept
+        assert!(values.get("databse.host").is_some());
+
+        assert_eq!(
+            source.var_name("database.pool_size").as_deref(),
+            Some("APP_DATABASE_POOL_SIZE")
+        );
+        assert_eq!(source.var_name("database.pool.size"), None);
+        assert_eq!(
+            source.var_name("allowed-hosts[0]").as_deref(),
+            Some("APP_ALLOWED_HOSTS_0")
+        );
+    }
+
+    #[test]
+    fn test_for_type_reports_ambiguous_variables() {
+        struct Ambiguous;
+
+        impl ConfigSchema for Ambiguous {
+            fn json_schema() -> serde_json::Value {
+                serde_json::json!({ "type": "object" })
+            }
+
+            fn fields() -> Vec<FieldInfo> {
+                vec![
+                    field("max_connections", vec![]),
+                    field("max", vec![field("connections", vec![])]),
+                ]
+            }
+        }
+
+        let env = MockEnv::new()
+            .with_env("APP_MAX_CONNECTIONS", "100")
+            .with_env("APP_MAX", "1");
+        let errors = Env::prefix("APP_")
+            .for_type::<Ambiguous>()
+            .load(&env)
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let message = errors.first().to_string();
+        assert!(message.contains("APP_MAX_CONNECTIONS matches several fields"));
+        assert!(message.contains("max.connections, max_connections"));
+
+        let values = Env::prefix("APP_")
+            .for_type::<Ambiguous>()
+            .map("MAX_CONNECTIONS", "max_connections")
+            .load(&env)
+            .unwrap();
+        assert!(values.get("max_connections").is_some());
+    }
+
     #[test]
     fn test_suffix_to_path_simple() {
         assert_eq!(suffix_to_path("HOST", "_"), "host");
@@ -804,6 +1138,10 @@ mod tests {
     #[test]
     fn test_parse_scalar_float() {
         assert_eq!(parse_scalar("3.25"), Value::Float(3.25));
+        assert_eq!(
+            parse_scalar("18446744073709551615"),
+            Value::UInteger(u64::MAX)
+        );
         assert_eq!(parse_scalar("-2.5"), Value::Float(-2.5));
     }
 
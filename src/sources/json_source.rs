@@ -51,6 +51,7 @@ pub struct Json {
     source: JsonSource,
     required: bool,
     name: Option<String>,
+    profiles: bool,
 }
 
 impl Json {
@@ -69,6 +70,7 @@ impl Json {
             source: JsonSource::File(path.into()),
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -89,6 +91,7 @@ impl Json {
             },
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -139,6 +142,24 @@ impl Json {
         self
     }
 
+    /// Treat this source's top-level tables as profiles (`[default]`, `[production]`).
+    ///
+    /// When the builder selects a profile, only the `[default]` table and the
+    /// active profile's table of this source are loaded. Sources without this
+    /// keep all their tables whichever profile is selected.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::Json;
+    ///
+    /// let source = Json::file("config.json").with_profiles();
+    /// ```
+    pub fn with_profiles(mut self) -> Self {
+        self.profiles = true;
+        self
+    }
+
     /// Get the source name for error messages.
     fn source_name(&self) -> String {
         if let Some(ref name) = self.name {
@@ -203,6 +224,10 @@ impl Source for Json {
         }
     }
 
+    fn has_profiles(&self) -> bool {
+        self.profiles
+    }
+
     #[cfg(feature = "watch")]
     fn watch_path(&self) -> Option<PathBuf> {
         match &self.source {
@@ -317,6 +342,8 @@ fn json_to_value(json: &serde_json::Value) -> Value {
             // Try integer first, fall back to float
             if let Some(i) = n.as_i64() {
                 Value::Integer(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::UInteger(u)
             } else if let Some(f) = n.as_f64() {
                 Value::Float(f)
             } else {
@@ -356,6 +383,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_json_u64_above_i64_max_is_lossless() {
+        let env = MockEnv::new().with_file(
+            "ids.json",
+            r#"{"snowflake": 18446744073709551615, "small": 7}"#,
+        );
+
+        let values = Json::file("ids.json").load(&env).unwrap();
+        assert_eq!(
+            values.get("snowflake").unwrap().value,
+            Value::UInteger(u64::MAX)
+        );
+        assert_eq!(values.get("small").unwrap().value, Value::Integer(7));
+    }
+
     #[test]
     fn test_json_file_missing_required() {
         let env = MockEnv::new();
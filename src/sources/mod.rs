@@ -7,17 +7,22 @@ mod defaults;
 mod env_source;
 #[cfg(feature = "json")]
 mod json_source;
+mod mounted;
 #[cfg(feature = "toml")]
 mod toml_source;
+mod trusted;
 #[cfg(feature = "yaml")]
 mod yaml_source;
 
 pub use defaults::{Defaults, PartialDefaults};
+pub(crate) use env_source::parse_scalar;
 pub use env_source::Env;
 #[cfg(feature = "json")]
 pub use json_source::Json;
+pub use mounted::Mounted;
 #[cfg(feature = "toml")]
 pub use toml_source::Toml;
+pub use trusted::Trusted;
 #[cfg(feature = "yaml")]
 pub use yaml_source::Yaml;
 
@@ -0,0 +1,136 @@
+//! Mounting a source under a sub-path.
+//!
+//! Every source writes to root paths. `Mounted` moves them under a prefix, so a
+//! file dedicated to one section doesn't have to repeat its top-level table.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // database.toml:
+//! //   host = "localhost"
+//! //   port = 5432
+//!
+//! let config = Config::<AppConfig>::builder()
+//!     .source(Toml::file("database.toml").at("database"))
+//!     .source(Env::prefix("APP_DB_").at("database"))
+//!     .build()?;
+//! ```
+
+#[cfg(feature = "watch")]
+use std::path::PathBuf;
+
+use crate::env::ConfigEnv;
+use crate::error::ConfigErrors;
+use crate::source::{ConfigValues, Source, Trust};
+
+/// A source whose values are mounted under a path prefix.
+///
+/// Created with [`Source::at`]. Source locations are untouched, so errors
+/// still point at the original file and line; error paths gain the prefix.
+pub struct Mounted {
+    inner: Box<dyn Source>,
+    prefix: String,
+    mount_point: String,
+}
+
+impl Mounted {
+    /// Mount `source` under `prefix` (e.g. `"database"` or `"services.api"`).
+    pub fn new(source: impl Source + 'static, prefix: impl Into<String>) -> Self {
+        Self::from_boxed(Box::new(source), prefix.into())
+    }
+
+    fn from_boxed(inner: Box<dyn Source>, prefix: String) -> Self {
+        let prefix = prefix.trim_matches('.').to_string();
+        let mount_point = match inner.mount_point() {
+            Some(inner_point) if !prefix.is_empty() => format!("{}.{}", prefix, inner_point),
+            Some(inner_point) => inner_point.to_string(),
+            None => prefix.clone(),
+        };
+        Self {
+            inner,
+            prefix,
+            mount_point,
+        }
+    }
+
+    /// The prefix this source is mounted under.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+impl Source for Mounted {
+    fn load(&self, env: &dyn ConfigEnv) -> Result<ConfigValues, ConfigErrors> {
+        match self.inner.load(env) {
+            Ok(values) => Ok(values.mounted_at(&self.prefix)),
+            Err(errors) if self.prefix.is_empty() => Err(errors),
+            Err(errors) => Err(errors.with_path_prefix(&self.prefix)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn has_profiles(&self) -> bool {
+        self.inner.has_profiles()
+    }
+
+    fn mount_point(&self) -> Option<&str> {
+        (!self.mount_point.is_empty()).then_some(self.mount_point.as_str())
+    }
+
+    fn trust(&self) -> Trust {
+        self.inner.trust()
+    }
+
+    #[cfg(feature = "watch")]
+    fn watch_path(&self) -> Option<PathBuf> {
+        self.inner.watch_path()
+    }
+
+    #[cfg(feature = "watch")]
+    fn clone_box(&self) -> Box<dyn Source> {
+        Box::new(Self::from_boxed(
+            self.inner.clone_box(),
+            self.prefix.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::sources::Defaults;
+
+    #[test]
+    fn test_mounts_values_under_prefix() {
+        let source = Defaults::partial()
+            .set("host", "localhost")
+            .set("port", 5432)
+            .at("services.db");
+
+        let values = source.load(&MockEnv::new()).unwrap();
+        assert!(values.contains("services.db.host"));
+        assert!(values.contains("services.db.port"));
+        assert!(!values.contains("host"));
+    }
+
+    #[test]
+    fn test_nested_mounts_combine_mount_points() {
+        let source = Defaults::partial().set("host", "x").at("db").at("services");
+        assert_eq!(source.mount_point(), Some("services.db"));
+        assert_eq!(source.prefix(), "services");
+
+        let values = source.load(&MockEnv::new()).unwrap();
+        assert!(values.contains("services.db.host"));
+    }
+
+    #[test]
+    fn test_empty_prefix_is_identity() {
+        let source = Defaults::partial().set("host", "x").at("");
+        assert_eq!(source.mount_point(), None);
+        assert!(source.load(&MockEnv::new()).unwrap().contains("host"));
+    }
+}
@@ -36,7 +36,7 @@ use crate::env::ConfigEnv;
 use crate::error::{ConfigError, ConfigErrors, SourceErrorKind, SourceLocation};
 use crate::source::{ConfigValues, Source};
 use crate::sources::line_from_offset;
-use crate::value::{ConfigValue, Value};
+use crate::value::{ConfigValue, Date, Datetime, Offset, Time, Value};
 
 /// The source type for TOML configuration.
 #[derive(Debug, Clone)]
@@ -56,6 +56,7 @@ pub struct Toml {
     source: TomlSource,
     required: bool,
     name: Option<String>,
+    profiles: bool,
 }
 
 impl Toml {
@@ -74,6 +75,7 @@ impl Toml {
             source: TomlSource::File(path.into()),
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -97,6 +99,7 @@ impl Toml {
             },
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -147,6 +150,24 @@ impl Toml {
         self
     }
 
+    /// Treat this source's top-level tables as profiles (`[default]`, `[production]`).
+    ///
+    /// When the builder selects a profile, only the `[default]` table and the
+    /// active profile's table of this source are loaded. Sources without this
+    /// keep all their tables whichever profile is selected.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::Toml;
+    ///
+    /// let source = Toml::file("config.toml").with_profiles();
+    /// ```
+    pub fn with_profiles(mut self) -> Self {
+        self.profiles = true;
+        self
+    }
+
     /// Get the source name for error messages.
     fn source_name(&self) -> String {
         if let Some(ref name) = self.name {
@@ -211,6 +232,10 @@ impl Source for Toml {
         }
     }
 
+    fn has_profiles(&self) -> bool {
+        self.profiles
+    }
+
     #[cfg(feature = "watch")]
     fn watch_path(&self) -> Option<PathBuf> {
         match &self.source {
@@ -359,7 +384,7 @@ fn toml_edit_value_to_value(v: &toml_edit::Value) -> Value {
         toml_edit::Value::Integer(i) => Value::Integer(*i.value()),
         toml_edit::Value::Float(f) => Value::Float(*f.value()),
         toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
-        toml_edit::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml_edit::Value::Datetime(dt) => Value::Datetime(datetime(dt.value())),
         toml_edit::Value::Array(arr) => {
             Value::Array(arr.iter().map(toml_edit_value_to_value).collect())
         }
@@ -371,6 +396,27 @@ fn toml_edit_value_to_value(v: &toml_edit::Value) -> Value {
     }
 }
 
+/// Convert a TOML date, time or date-time, keeping which parts it has.
+fn datetime(dt: &toml_edit::Datetime) -> Datetime {
+    Datetime {
+        date: dt.date.map(|d| Date {
+            year: d.year,
+            month: d.month,
+            day: d.day,
+        }),
+        time: dt.time.map(|t| Time {
+            hour: t.hour,
+            minute: t.minute,
+            second: t.second,
+            nanosecond: t.nanosecond,
+        }),
+        offset: dt.offset.map(|o| match o {
+            toml_edit::Offset::Z => Offset::Z,
+            toml_edit::Offset::Custom { minutes } => Offset::Custom { minutes },
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -621,6 +667,28 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_toml_datetimes_keep_their_parts() {
+        let source = Toml::string(
+            r#"
+released = 1979-05-27T07:32:00.5Z
+meeting = 1979-05-27T07:32:00
+born = 1979-05-27
+alarm = 07:32:00
+"#,
+        );
+        let values = source.load(&MockEnv::new()).unwrap();
+        let datetime = |path: &str| *values.get(path).unwrap().value.as_datetime().unwrap();
+
+        let released = datetime("released");
+        assert_eq!(released.offset, Some(Offset::Z));
+        assert_eq!(released.time.unwrap().nanosecond, 500_000_000);
+        assert_eq!(released.to_string(), "1979-05-27T07:32:00.5Z");
+        assert!(datetime("meeting").offset.is_none());
+        assert!(datetime("born").time.is_none());
+        assert_eq!(datetime("alarm").to_string(), "07:32:00");
+    }
+
     #[test]
     fn test_toml_inline_tables() {
         let env = MockEnv::new().with_file(
@@ -0,0 +1,92 @@
+//! Assigning a trust level to a source.
+//!
+//! Precedence between sources follows the order they are added in. When some
+//! sources are managed more carefully than others, `Trusted` records that, so a
+//! build can warn when a less trusted source overrides a value from a more
+//! trusted one.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let (config, warnings) = Config::<AppConfig>::builder()
+//!     .source(Toml::file("/etc/app/config.toml").with_trust(Trust::High))
+//!     .source(Toml::file("~/.app.toml").optional().with_trust(Trust::Low))
+//!     .build_with_warnings()?;
+//! ```
+
+#[cfg(feature = "watch")]
+use std::path::PathBuf;
+
+use crate::env::ConfigEnv;
+use crate::error::ConfigErrors;
+use crate::source::{ConfigValues, Source, Trust};
+
+/// A source with an explicit trust level.
+///
+/// Created with [`Source::with_trust`]. Loading is delegated unchanged.
+pub struct Trusted {
+    inner: Box<dyn Source>,
+    trust: Trust,
+}
+
+impl Trusted {
+    /// Assign `trust` to `source`.
+    pub fn new(source: impl Source + 'static, trust: Trust) -> Self {
+        Self {
+            inner: Box::new(source),
+            trust,
+        }
+    }
+}
+
+impl Source for Trusted {
+    fn load(&self, env: &dyn ConfigEnv) -> Result<ConfigValues, ConfigErrors> {
+        self.inner.load(env)
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn has_profiles(&self) -> bool {
+        self.inner.has_profiles()
+    }
+
+    fn mount_point(&self) -> Option<&str> {
+        self.inner.mount_point()
+    }
+
+    fn trust(&self) -> Trust {
+        self.trust
+    }
+
+    #[cfg(feature = "watch")]
+    fn watch_path(&self) -> Option<PathBuf> {
+        self.inner.watch_path()
+    }
+
+    #[cfg(feature = "watch")]
+    fn clone_box(&self) -> Box<dyn Source> {
+        Box::new(Self {
+            inner: self.inner.clone_box(),
+            trust: self.trust,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::Defaults;
+
+    #[test]
+    fn test_trust_is_kept_through_mounting() {
+        let source = Defaults::partial()
+            .set("host", "x")
+            .with_trust(Trust::High)
+            .at("db");
+        assert_eq!(source.trust(), Trust::High);
+        assert_eq!(Defaults::partial().trust(), Trust::Normal);
+        assert!(Trust::Low < Trust::Normal && Trust::Normal < Trust::High);
+    }
+}
@@ -51,6 +51,7 @@ pub struct Yaml {
     source: YamlSource,
     required: bool,
     name: Option<String>,
+    profiles: bool,
 }
 
 impl Yaml {
@@ -69,6 +70,7 @@ impl Yaml {
             source: YamlSource::File(path.into()),
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -89,6 +91,7 @@ impl Yaml {
             },
             required: true,
             name: None,
+            profiles: false,
         }
     }
 
@@ -139,6 +142,24 @@ impl Yaml {
         self
     }
 
+    /// Treat this source's top-level tables as profiles (`[default]`, `[production]`).
+    ///
+    /// When the builder selects a profile, only the `[default]` table and the
+    /// active profile's table of this source are loaded. Sources without this
+    /// keep all their tables whichever profile is selected.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::Yaml;
+    ///
+    /// let source = Yaml::file("config.yaml").with_profiles();
+    /// ```
+    pub fn with_profiles(mut self) -> Self {
+        self.profiles = true;
+        self
+    }
+
     /// Get the source name for error messages.
     fn source_name(&self) -> String {
         if let Some(ref name) = self.name {
@@ -203,6 +224,10 @@ impl Source for Yaml {
         }
     }
 
+    fn has_profiles(&self) -> bool {
+        self.profiles
+    }
+
     #[cfg(feature = "watch")]
     fn watch_path(&self) -> Option<PathBuf> {
         match &self.source {
@@ -330,6 +355,8 @@ fn yaml_to_value(yaml: &serde_yaml::Value) -> Value {
             // Try integer first, fall back to float
             if let Some(i) = n.as_i64() {
                 Value::Integer(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::UInteger(u)
             } else if let Some(f) = n.as_f64() {
                 Value::Float(f)
             } else {
@@ -41,7 +41,12 @@ use std::fmt;
 
 use crate::config::Config;
 use crate::error::SourceLocation;
-use crate::value::Value;
+use crate::export::{export_values, ExportOptions, Format};
+use crate::redact::{RedactionPolicy, REDACTED};
+use crate::secret::is_secret_path;
+use crate::snapshot::{format_age, SnapshotInfo};
+use crate::source::{in_subtree, ConfigValues, MergeAction};
+use crate::value::{ConfigValue, Value};
 
 /// A value with its source information.
 #[derive(Debug, Clone)]
@@ -52,6 +57,10 @@ pub struct TracedValue {
     pub source: SourceLocation,
     /// Whether this value was used (not overridden)
     pub is_final: bool,
+    /// How the value was merged (set, appended, or replaced by a later source)
+    pub action: MergeAction,
+    /// Profile table the value was taken from, if a profile was selected
+    pub profile: Option<String>,
 }
 
 impl TracedValue {
@@ -61,6 +70,37 @@ impl TracedValue {
             value,
             source,
             is_final,
+            action: MergeAction::Set,
+            profile: None,
+        }
+    }
+
+    /// Set the merge action recorded for this value.
+    pub fn with_action(mut self, action: MergeAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Set the profile this value was taken from.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Source location, including the profile if there is one, for reports.
+    fn origin(&self) -> String {
+        match &self.profile {
+            Some(profile) => format!("{} ({})", self.source, profile),
+            None => self.source.to_string(),
+        }
+    }
+
+    /// Short note describing a non-default merge action, for reports.
+    fn action_note(&self) -> &'static str {
+        match self.action {
+            MergeAction::Set => "",
+            MergeAction::Appended => " (appended)",
+            MergeAction::Replaced => " <- replaced",
         }
     }
 }
@@ -72,13 +112,15 @@ pub struct ValueTrace {
     pub final_value: TracedValue,
     /// All values from all sources, in priority order (lowest first)
     pub history: Vec<TracedValue>,
+    redacted: bool,
 }
 
 impl ValueTrace {
     /// Create a new value trace from a history of traced values.
     ///
     /// The last value in the history is considered the final value.
-    /// The `is_final` flag is automatically set on the last value.
+    /// The `is_final` flag is automatically set on the last value, unless
+    /// a later source replaced its whole subtree and the path no longer exists.
     pub fn new(mut history: Vec<TracedValue>) -> Option<Self> {
         if history.is_empty() {
             return None;
@@ -86,7 +128,7 @@ impl ValueTrace {
 
         // Mark the last value as final
         if let Some(last) = history.last_mut() {
-            last.is_final = true;
+            last.is_final = last.action != MergeAction::Replaced;
         }
 
         let final_value = history.last().cloned()?;
@@ -94,9 +136,29 @@ impl ValueTrace {
         Some(Self {
             final_value,
             history,
+            redacted: false,
         })
     }
 
+    /// Set whether `Display` hides the values of this trace.
+    pub fn with_redaction(mut self, redacted: bool) -> Self {
+        self.redacted = redacted;
+        self
+    }
+
+    /// Check if the values of this trace are hidden when printed.
+    pub fn is_redacted(&self) -> bool {
+        self.redacted
+    }
+
+    fn show(&self, value: &Value) -> String {
+        if self.redacted {
+            REDACTED.to_string()
+        } else {
+            format!("{:?}", value)
+        }
+    }
+
     /// Check if this value was overridden (has more than one source).
     pub fn was_overridden(&self) -> bool {
         self.history.len() > 1
@@ -106,21 +168,39 @@ impl ValueTrace {
     pub fn source_count(&self) -> usize {
         self.history.len()
     }
+
+    /// Check if this path was dropped because a later source replaced its subtree.
+    pub fn was_replaced(&self) -> bool {
+        self.final_value.action == MergeAction::Replaced
+    }
+
+    /// Check if the final value was appended to an array by a merge strategy.
+    pub fn was_appended(&self) -> bool {
+        self.final_value.action == MergeAction::Appended
+    }
 }
 
 impl fmt::Display for ValueTrace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
             f,
-            "Final: {:?} (from {})",
-            self.final_value.value, self.final_value.source
+            "Final: {} (from {})",
+            self.show(&self.final_value.value),
+            self.final_value.origin()
         )?;
 
         if self.history.len() > 1 {
             writeln!(f, "History:")?;
             for val in &self.history {
                 let marker = if val.is_final { "→" } else { " " };
-                writeln!(f, "  {} [{}] {:?}", marker, val.source, val.value)?;
+                writeln!(
+                    f,
+                    "  {} [{}] {}{}",
+                    marker,
+                    val.origin(),
+                    self.show(&val.value),
+                    val.action_note()
+                )?;
             }
         }
 
@@ -136,12 +216,148 @@ impl fmt::Display for ValueTrace {
 pub struct TracedConfig<T> {
     config: Config<T>,
     traces: BTreeMap<String, ValueTrace>,
+    profile: Option<String>,
+    values: ConfigValues,
+    secrets: Vec<String>,
+    redaction: RedactionPolicy,
+    snapshot: Option<SnapshotInfo>,
 }
 
 impl<T> TracedConfig<T> {
     /// Create a new traced config from a config and traces.
     pub fn new(config: Config<T>, traces: BTreeMap<String, ValueTrace>) -> Self {
-        Self { config, traces }
+        Self {
+            config,
+            traces,
+            profile: None,
+            values: ConfigValues::empty(),
+            secrets: Vec::new(),
+            redaction: RedactionPolicy::default(),
+            snapshot: None,
+        }
+        .mark_redacted()
+    }
+
+    /// Record the merged values the configuration was deserialized from.
+    pub fn with_values(mut self, values: ConfigValues) -> Self {
+        self.values = values;
+        self
+    }
+
+    /// Get the merged values the configuration was deserialized from.
+    pub fn values(&self) -> &ConfigValues {
+        &self.values
+    }
+
+    /// Record the paths that were deserialized into a [`Secret`](crate::Secret).
+    ///
+    /// Values at and beneath these paths are redacted in the trace report,
+    /// exports and diffs.
+    pub fn with_secret_paths(mut self, paths: Vec<String>) -> Self {
+        self.secrets = paths;
+        self.mark_redacted()
+    }
+
+    /// Get the paths that were deserialized into a `Secret`.
+    pub fn secret_paths(&self) -> &[String] {
+        &self.secrets
+    }
+
+    /// Check if the value at `path` belongs to a `Secret`.
+    pub fn is_secret(&self, path: &str) -> bool {
+        is_secret_path(path, &self.secrets)
+    }
+
+    /// Set the policy deciding which values are redacted when printed.
+    pub fn with_redaction_policy(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = policy;
+        self.mark_redacted()
+    }
+
+    /// Get the policy deciding which values are redacted when printed.
+    pub fn redaction_policy(&self) -> &RedactionPolicy {
+        &self.redaction
+    }
+
+    /// Flag the traces whose values are secret or redacted by the policy.
+    fn mark_redacted(mut self) -> Self {
+        for (path, trace) in self.traces.iter_mut() {
+            trace.redacted = is_secret_path(path, &self.secrets)
+                || trace
+                    .history
+                    .iter()
+                    .any(|val| self.redaction.redacts(path, &val.value));
+        }
+        self
+    }
+
+    /// Export the merged configuration in `format`.
+    ///
+    /// Each value is annotated with the source it came from, and secrets and
+    /// values the redaction policy covers are redacted. The output loads back through the matching source
+    /// (e.g. `Toml::string`), so it can serve as a frozen snapshot.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use premortem::export::Format;
+    ///
+    /// std::fs::write("effective.toml", traced.export(Format::Toml))?;
+    /// ```
+    pub fn export(&self, format: Format) -> String {
+        self.export_with(format, &ExportOptions::default())
+    }
+
+    /// Export the merged configuration with custom options.
+    pub fn export_with(&self, format: Format, options: &ExportOptions) -> String {
+        let origin = |path: &str, value: &ConfigValue| match self.traces.get(path) {
+            Some(trace) => trace.final_value.origin(),
+            None => value.source.to_string(),
+        };
+        export_values(
+            &self.values,
+            &origin,
+            &self.redaction,
+            &self.secrets,
+            format,
+            options,
+        )
+    }
+
+    /// Record the profile that was active when the configuration was built.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Get the profile that was active when the configuration was built, if any.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Record the snapshot the configuration was built from, if its sources
+    /// failed to load.
+    pub fn with_snapshot(mut self, snapshot: Option<SnapshotInfo>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Get the snapshot the configuration was built from, with its age and
+    /// the source errors that caused the fallback.
+    ///
+    /// `None` if the configuration was built from its sources.
+    pub fn snapshot(&self) -> Option<&SnapshotInfo> {
+        self.snapshot.as_ref()
+    }
+
+    /// Get the profile the final value at `path` was taken from.
+    ///
+    /// Returns `None` if no profile was active, the path is unknown, or the
+    /// value came from a source without profiles (such as environment variables).
+    pub fn profile_of(&self, path: &str) -> Option<&str> {
+        self.traces
+            .get(path)
+            .and_then(|t| t.final_value.profile.as_deref())
     }
 
     /// Get reference to the configuration.
@@ -190,6 +406,22 @@ impl<T> TracedConfig<T> {
             .map(|(k, _)| k.as_str())
     }
 
+    /// Get paths that were dropped because a later source replaced their subtree.
+    pub fn replaced_paths(&self) -> impl Iterator<Item = &str> {
+        self.traces
+            .iter()
+            .filter(|(_, t)| t.was_replaced())
+            .map(|(k, _)| k.as_str())
+    }
+
+    /// Get paths whose values were appended to an array by a merge strategy.
+    pub fn appended_paths(&self) -> impl Iterator<Item = &str> {
+        self.traces
+            .iter()
+            .filter(|(_, t)| t.was_appended())
+            .map(|(k, _)| k.as_str())
+    }
+
     /// Get all traced paths.
     pub fn paths(&self) -> impl Iterator<Item = &str> {
         self.traces.keys().map(|k| k.as_str())
@@ -201,18 +433,44 @@ impl<T> TracedConfig<T> {
     }
 
     /// Generate a human-readable trace report.
+    ///
+    /// Values of secrets, and values the redaction policy covers, are shown
+    /// as `[REDACTED]`. A configuration built from a snapshot starts with the
+    /// snapshot's age and the errors that caused the fallback.
     pub fn trace_report(&self) -> String {
         let mut report = String::new();
 
+        if let Some(snapshot) = &self.snapshot {
+            report.push_str(&format!(
+                "Built from snapshot {} (written {} ago); sources failed to load:\n",
+                snapshot.path.display(),
+                format_age(snapshot.age)
+            ));
+            for error in snapshot.errors.iter() {
+                report.push_str(&format!("  {}\n", error));
+            }
+            report.push('\n');
+        }
+
         for (path, trace) in &self.traces {
-            report.push_str(&format!("{} = {:?}\n", path, trace.final_value.value));
+            report.push_str(&format!(
+                "{} = {}\n",
+                path,
+                trace.show(&trace.final_value.value)
+            ));
 
             for val in &trace.history {
                 let marker = if val.is_final { "✓" } else { "○" };
-                let override_note = if !val.is_final { " <- overridden" } else { "" };
+                let note = match val.action {
+                    MergeAction::Set if !val.is_final => " <- overridden",
+                    _ => val.action_note(),
+                };
                 report.push_str(&format!(
-                    "  {} [{}] {:?}{}\n",
-                    marker, val.source, val.value, override_note
+                    "  {} [{}] {}{}\n",
+                    marker,
+                    val.origin(),
+                    trace.show(&val.value),
+                    note
                 ));
             }
             report.push('\n');
@@ -256,10 +514,48 @@ impl TraceBuilder {
 
     /// Add a value from a source.
     pub fn add_value(&mut self, path: String, value: Value, source: SourceLocation) {
-        self.values
-            .entry(path)
-            .or_default()
-            .push(TracedValue::new(value, source, false));
+        self.add_merged_value(path, value, source, MergeAction::Set);
+    }
+
+    /// Add a value from a source along with how it was merged.
+    pub fn add_merged_value(
+        &mut self,
+        path: String,
+        value: Value,
+        source: SourceLocation,
+        action: MergeAction,
+    ) {
+        self.add_traced_value(
+            path,
+            TracedValue::new(value, source, false).with_action(action),
+        );
+    }
+
+    /// Add an already-built traced value, e.g. one carrying a profile.
+    pub fn add_traced_value(&mut self, path: String, value: TracedValue) {
+        self.values.entry(path).or_default().push(value);
+    }
+
+    /// Replace the final value recorded for `path`, e.g. after interpolation.
+    pub fn resolve_value(&mut self, path: &str, value: Value) {
+        if let Some(last) = self.values.get_mut(path).and_then(|h| h.last_mut()) {
+            last.value = value;
+        }
+    }
+
+    /// Mark every value recorded so far under `prefix` as replaced.
+    ///
+    /// Called when a `MergeStrategy::Replace` subtree is overwritten by a later source.
+    pub fn mark_replaced(&mut self, prefix: &str) {
+        for (_, history) in self
+            .values
+            .iter_mut()
+            .filter(|(path, _)| in_subtree(path, prefix))
+        {
+            for val in history.iter_mut() {
+                val.action = MergeAction::Replaced;
+            }
+        }
     }
 
     /// Build the final traces map.
@@ -0,0 +1,543 @@
+//! Human-friendly durations and byte sizes.
+//!
+//! Instead of `timeout_ms: u64` and a comment explaining the unit, use
+//! [`Duration`] and [`ByteSize`]. Both deserialize from strings with units,
+//! from any source:
+//!
+//! ```ignore
+//! use premortem::{ByteSize, Duration};
+//!
+//! #[derive(Debug, Deserialize, DeriveValidate)]
+//! struct CacheConfig {
+//!     #[validate(range("1s"..="5m"))]
+//!     ttl: Duration,            // ttl = "1h30m", or APP_CACHE_TTL=90s
+//!     #[validate(range("1MiB"..="4GiB"))]
+//!     max_size: ByteSize,       // max_size = "512MiB"
+//! }
+//! ```
+//!
+//! Plain integers are read as seconds and bytes. Durations accept `ns`, `us`
+//! (or `µs`), `ms`, `s`, `m` (or `min`), `h` and `d`, combined as in `1h30m`.
+//! Sizes accept decimal (`kB`, `MB`, `GB`, `TB`, `PB`) and binary (`KiB`,
+//! `MiB`, `GiB`, `TiB`, `PiB`) units, in any case. Both accept fractions such as
+//! `1.5h` or `1.5GiB`; a size is rounded down to whole bytes.
+//!
+//! Both display in the same notation, so values round-trip through exports
+//! and defaults.
+//!
+//! In hand-written [`Validate`](crate::Validate) impls, check bounds with
+//! [`DurationRange`](crate::validators::DurationRange) and
+//! [`SizeRange`](crate::validators::SizeRange).
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Duration units, largest first, in nanoseconds.
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("d", 86_400 * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Size units, largest first within each system, in bytes.
+const BINARY_UNITS: &[(&str, u128)] = &[
+    ("PiB", 1 << 50),
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+];
+const DECIMAL_UNITS: &[(&str, u128)] = &[
+    ("PB", 1_000_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("kB", 1_000),
+];
+
+/// A string that is not a valid [`Duration`] or [`ByteSize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError {
+    message: String,
+}
+
+impl ParseUnitError {
+    fn new(kind: &str, input: &str, reason: impl fmt::Display) -> Self {
+        Self {
+            message: format!("invalid {} '{}': {}", kind, input, reason),
+        }
+    }
+}
+
+impl fmt::Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseUnitError {}
+
+/// A span of time written with units, like `30s` or `1h30m`.
+///
+/// Dereferences to [`std::time::Duration`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(std::time::Duration);
+
+impl Duration {
+    /// The empty duration.
+    pub const ZERO: Duration = Duration(std::time::Duration::ZERO);
+
+    /// A duration of `secs` seconds.
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(std::time::Duration::from_secs(secs))
+    }
+
+    /// A duration of `millis` milliseconds.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(std::time::Duration::from_millis(millis))
+    }
+
+    /// The equivalent `std::time::Duration`.
+    pub const fn as_std(&self) -> std::time::Duration {
+        self.0
+    }
+}
+
+impl Deref for Duration {
+    type Target = std::time::Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &dyn fmt::Display| ParseUnitError::new("duration", s, reason);
+        let nanos = parse_quantities(s, |unit| match unit {
+            "" => Some(NANOS_PER_SEC),
+            "µs" => Some(1_000),
+            "min" => Some(60 * NANOS_PER_SEC),
+            unit => lookup(DURATION_UNITS, unit),
+        })
+        .map_err(|reason| invalid(&reason))?;
+
+        let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| invalid(&"too long"))?;
+        let subsec = (nanos % NANOS_PER_SEC) as u32;
+        Ok(Self(std::time::Duration::new(secs, subsec)))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_zero() {
+            return f.write_str("0s");
+        }
+        let mut rest = self.0.as_nanos();
+        for (unit, nanos) in DURATION_UNITS {
+            if rest >= *nanos {
+                write!(f, "{}{}", rest / nanos, unit)?;
+                rest %= nanos;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DurationVisitor;
+
+        impl Visitor<'_> for DurationVisitor {
+            type Value = Duration;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a duration like \"30s\" or \"1h30m\", or a number of seconds")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Duration, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Duration, E> {
+                Ok(Duration::from_secs(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Duration, E> {
+                u64::try_from(v)
+                    .map(Duration::from_secs)
+                    .map_err(|_| E::custom(format!("invalid duration {}: negative", v)))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Duration, E> {
+                std::time::Duration::try_from_secs_f64(v)
+                    .map(Duration)
+                    .map_err(|e| E::custom(format!("invalid duration {}: {}", v, e)))
+            }
+        }
+
+        deserializer.deserialize_any(DurationVisitor)
+    }
+}
+
+/// An amount of memory or storage written with units, like `512MiB`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    /// A size of `bytes` bytes.
+    pub const fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    /// A size of `n` kibibytes, saturating at `u64::MAX` bytes.
+    pub const fn kib(n: u64) -> Self {
+        Self(n.saturating_mul(1 << 10))
+    }
+
+    /// A size of `n` mebibytes, saturating at `u64::MAX` bytes.
+    pub const fn mib(n: u64) -> Self {
+        Self(n.saturating_mul(1 << 20))
+    }
+
+    /// A size of `n` gibibytes, saturating at `u64::MAX` bytes.
+    pub const fn gib(n: u64) -> Self {
+        Self(n.saturating_mul(1 << 30))
+    }
+
+    /// The size in bytes.
+    pub const fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &dyn fmt::Display| ParseUnitError::new("size", s, reason);
+        let bytes = parse_quantities(s, |unit| match unit.to_ascii_lowercase().as_str() {
+            "" | "b" => Some(1),
+            "k" => Some(1_000),
+            "ki" => Some(1 << 10),
+            "m" => Some(1_000_000),
+            "mi" => Some(1 << 20),
+            "g" => Some(1_000_000_000),
+            "gi" => Some(1 << 30),
+            "t" => Some(1_000_000_000_000),
+            "ti" => Some(1 << 40),
+            "p" => Some(1_000_000_000_000_000),
+            "pi" => Some(1 << 50),
+            unit => {
+                let unit = unit.strip_suffix('b')?;
+                BINARY_UNITS
+                    .iter()
+                    .chain(DECIMAL_UNITS)
+                    .find(|(name, _)| name[..name.len() - 1].eq_ignore_ascii_case(unit))
+                    .map(|(_, bytes)| *bytes)
+            }
+        })
+        .map_err(|reason| invalid(&reason))?;
+
+        u64::try_from(bytes)
+            .map(Self)
+            .map_err(|_| invalid(&"too large"))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = u128::from(self.0);
+        let unit = BINARY_UNITS
+            .iter()
+            .chain(DECIMAL_UNITS)
+            .find(|(_, size)| bytes >= *size && bytes % size == 0);
+        match unit {
+            Some((unit, size)) => write!(f, "{}{}", bytes / size, unit),
+            None => write!(f, "{}B", bytes),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteSizeVisitor;
+
+        impl Visitor<'_> for ByteSizeVisitor {
+            type Value = ByteSize;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a size like \"512MiB\" or \"10GB\", or a number of bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<ByteSize, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<ByteSize, E> {
+                Ok(ByteSize(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<ByteSize, E> {
+                u64::try_from(v)
+                    .map(ByteSize)
+                    .map_err(|_| E::custom(format!("invalid size {}: negative", v)))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<ByteSize, E> {
+                // Rounded down to whole bytes, like fractional sizes in strings
+                if v.is_nan() {
+                    Err(E::custom("invalid size NaN"))
+                } else if v < 0.0 {
+                    Err(E::custom(format!("invalid size {}: negative", v)))
+                } else if v >= 18_446_744_073_709_551_616.0 {
+                    Err(E::custom(format!("invalid size {}: too large", v)))
+                } else {
+                    Ok(ByteSize(v as u64))
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ByteSizeVisitor)
+    }
+}
+
+/// Look up a unit by exact name.
+fn lookup(units: &[(&str, u128)], unit: &str) -> Option<u128> {
+    units
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, scale)| *scale)
+}
+
+/// Pure function: sum quantities like `1h30m` or `1.5 GiB`, scaling each by
+/// its unit with `scale`.
+///
+/// A number without a unit is allowed only on its own. Fractions are exact
+/// down to the smallest unit and rounded down below it.
+fn parse_quantities(s: &str, scale: impl Fn(&str) -> Option<u128>) -> Result<u128, String> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return Err("empty value".to_string());
+    }
+
+    let mut total: u128 = 0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        if number.is_empty() {
+            return Err(format!("expected a number before '{}'", tail));
+        }
+        let tail = tail.trim_start();
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        if unit.is_empty() && (rest.len() != s.trim().len() || !tail.is_empty()) {
+            return Err(format!("missing unit after '{}'", number));
+        }
+
+        let multiplier = scale(unit).ok_or_else(|| format!("unknown unit '{}'", unit))?;
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let whole: u128 = match whole {
+            "" => 0,
+            digits => digits
+                .parse()
+                .map_err(|_| "number is too large".to_string())?,
+        };
+        if number == "." || fraction.contains('.') || fraction.len() > 18 {
+            return Err(format!("invalid number '{}'", number));
+        }
+        let fraction_scale = 10u128.pow(fraction.len() as u32);
+        let fraction: u128 = fraction.parse().unwrap_or(0);
+
+        total = whole
+            .checked_mul(multiplier)
+            .and_then(|whole| whole.checked_add(fraction * multiplier / fraction_scale))
+            .and_then(|amount| total.checked_add(amount))
+            .ok_or_else(|| "number is too large".to_string())?;
+        rest = tail.trim_start();
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duration(s: &str) -> Duration {
+        s.parse().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn size(s: &str) -> ByteSize {
+        s.parse().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(duration("30s"), Duration::from_secs(30));
+        assert_eq!(duration("1h30m"), Duration::from_secs(5_400));
+        assert_eq!(duration("1h 30m"), Duration::from_secs(5_400));
+        assert_eq!(duration("250ms"), Duration::from_millis(250));
+        assert_eq!(duration("1.5h"), Duration::from_secs(5_400));
+        assert_eq!(duration("2min"), Duration::from_secs(120));
+        assert_eq!(duration("10µs"), duration("10us"));
+        assert_eq!(duration("45"), Duration::from_secs(45));
+        assert_eq!(duration("0"), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        let err = "5x".parse::<Duration>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid duration '5x': unknown unit 'x'");
+        assert!("".parse::<Duration>().is_err());
+        assert!("h".parse::<Duration>().is_err());
+        assert!("1.2.3s".parse::<Duration>().is_err());
+        assert!(".s".parse::<Duration>().is_err());
+        let err = "1h30".parse::<Duration>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid duration '1h30': missing unit after '30'"
+        );
+        assert!("-5s".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    fn test_duration_display_round_trips() {
+        for s in ["0s", "30s", "1h30m", "1d2h", "1s500ms", "1m0s1ns"] {
+            let parsed = duration(s);
+            assert_eq!(duration(&parsed.to_string()), parsed, "{}", s);
+        }
+        assert_eq!(Duration::from_secs(5_400).to_string(), "1h30m");
+        assert_eq!(Duration::from_millis(1_500).to_string(), "1s500ms");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(size("512MiB"), ByteSize::mib(512));
+        assert_eq!(size("512 mib"), ByteSize::mib(512));
+        assert_eq!(size("10GB"), ByteSize::new(10_000_000_000));
+        assert_eq!(size("1.5KiB"), ByteSize::new(1_536));
+        assert_eq!(size("4k"), ByteSize::new(4_000));
+        assert_eq!(size("2Gi"), ByteSize::gib(2));
+        assert_eq!(size("100"), ByteSize::new(100));
+        assert_eq!(size("100B"), ByteSize::new(100));
+        assert!("5 parsecs".parse::<ByteSize>().is_err());
+        assert!("100000PiB".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn test_size_display() {
+        assert_eq!(ByteSize::mib(512).to_string(), "512MiB");
+        assert_eq!(ByteSize::new(1_000_000).to_string(), "1MB");
+        assert_eq!(ByteSize::new(1_500).to_string(), "1500B");
+        assert_eq!(ByteSize::new(0).to_string(), "0B");
+        assert_eq!(
+            size(&ByteSize::new(3_000).to_string()),
+            ByteSize::new(3_000)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_from_strings_and_integers() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Limits {
+            timeout: Duration,
+            retry: Duration,
+            max_body: ByteSize,
+            buffer: ByteSize,
+        }
+
+        let limits: Limits = serde_json::from_str(
+            r#"{"timeout": "1h30m", "retry": 5, "max_body": "1MiB", "buffer": 4096}"#,
+        )
+        .unwrap();
+        assert_eq!(limits.timeout, Duration::from_secs(5_400));
+        assert_eq!(limits.retry, Duration::from_secs(5));
+        assert_eq!(limits.max_body, ByteSize::mib(1));
+        assert_eq!(limits.buffer, ByteSize::kib(4));
+
+        let err = serde_json::from_str::<Duration>("-1").unwrap_err();
+        assert!(err.to_string().contains("negative"));
+        assert_eq!(
+            serde_json::to_string(&ByteSize::mib(1)).unwrap(),
+            r#""1MiB""#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_size_from_floats() {
+        assert_eq!(
+            serde_json::from_str::<ByteSize>("1536.9").unwrap(),
+            ByteSize::new(1_536)
+        );
+        assert!(serde_json::from_str::<ByteSize>("-1.5")
+            .unwrap_err()
+            .to_string()
+            .contains("negative"));
+        assert!(serde_json::from_str::<ByteSize>("1e30").is_err());
+
+        // Environment variables like APP_BUF=1.5 are read as floats
+        let env = crate::MockEnv::new().with_env("APP_BUF", "1.5");
+        let values = crate::Source::load(&crate::Env::prefix("APP_"), &env).unwrap();
+        let buf: ByteSize = values.get_as("buf").unwrap();
+        assert_eq!(buf, ByteSize::new(1));
+    }
+
+    #[test]
+    fn test_size_constructors_saturate() {
+        assert_eq!(ByteSize::kib(u64::MAX), ByteSize::new(u64::MAX));
+        assert_eq!(ByteSize::mib(1 << 50), ByteSize::new(u64::MAX));
+        assert_eq!(ByteSize::gib(3), ByteSize::new(3 << 30));
+    }
+}
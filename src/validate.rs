@@ -39,7 +39,10 @@ use std::collections::HashMap;
 
 use stillwater::Validation;
 
-use crate::error::{ConfigError, ConfigErrors, ConfigValidation, SourceLocation};
+use crate::alias::KeyAlias;
+use crate::error::{ConfigError, ConfigErrors, ConfigValidation, ConfigWarning, SourceLocation};
+use crate::secret::{Secret, Zeroize};
+use crate::units::{ByteSize, Duration};
 
 // ============================================================================
 // Validation Context (for source location lookup)
@@ -77,6 +80,7 @@ impl ValidationContext {
 thread_local! {
     static VALIDATION_CONTEXT: RefCell<Option<ValidationContext>> = const { RefCell::new(None) };
     static PATH_PREFIX: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static WARNINGS: RefCell<Option<Vec<ConfigWarning>>> = const { RefCell::new(None) };
 }
 
 /// Run a function with a validation context set.
@@ -122,6 +126,49 @@ pub fn current_source_location(path: &str) -> Option<SourceLocation> {
     })
 }
 
+/// Run a function, collecting the warnings raised by warn-level validators.
+///
+/// `ConfigBuilder` validates inside this, so warn-level failures end up with
+/// the build's other warnings. Outside of it, they are discarded.
+///
+/// # Example
+///
+/// ```ignore
+/// let (result, warnings) = collect_warnings(|| config.validate());
+/// ```
+pub fn collect_warnings<F, R>(f: F) -> (R, Vec<ConfigWarning>)
+where
+    F: FnOnce() -> R,
+{
+    let outer = WARNINGS.with(|cell| cell.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let warnings = WARNINGS.with(|cell| std::mem::replace(&mut *cell.borrow_mut(), outer));
+    (result, warnings.unwrap_or_default())
+}
+
+/// Turn a validation failure into warnings, for rules with `severity = "warn"`.
+///
+/// Each error is recorded with the full path of any nested validation in
+/// progress, and the result is always `Success`.
+pub fn warn_on_failure(result: ConfigValidation<()>) -> ConfigValidation<()> {
+    if let Validation::Failure(errors) = result {
+        let prefix = PATH_PREFIX.with(|cell| cell.borrow().join("."));
+        WARNINGS.with(|cell| {
+            if let Some(warnings) = cell.borrow_mut().as_mut() {
+                warnings.extend(errors.into_iter().map(|error| {
+                    let warning = ConfigWarning::from_validation_error(error);
+                    if prefix.is_empty() {
+                        warning
+                    } else {
+                        warning.with_path_prefix(&prefix)
+                    }
+                }));
+            }
+        });
+    }
+    Validation::Success(())
+}
+
 /// Push a path prefix for nested validation.
 ///
 /// Used by `validate_at` to track the current path context during nested struct validation.
@@ -180,6 +227,18 @@ pub trait Validate {
 
         result.map_err(|errors| errors.with_path_prefix(path))
     }
+
+    /// Old key names that are still accepted for this type's fields.
+    ///
+    /// `#[derive(Validate)]` returns the `#[config(alias = "...")]` aliases of
+    /// the struct and of its `#[validate(nested)]` fields. Paths are relative
+    /// to this type.
+    fn aliases() -> Vec<KeyAlias>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
 }
 
 /// Blanket implementation for types that don't need validation.
@@ -202,6 +261,23 @@ impl<T: Validate> Validate for Option<T> {
             None => Validation::Success(()),
         }
     }
+
+    fn aliases() -> Vec<KeyAlias> {
+        T::aliases()
+    }
+}
+
+/// Implementation for `Secret<T>` where T: Validate.
+///
+/// Validates the wrapped value.
+impl<T: Validate + Zeroize> Validate for Secret<T> {
+    fn validate(&self) -> ConfigValidation<()> {
+        self.expose_secret().validate()
+    }
+
+    fn aliases() -> Vec<KeyAlias> {
+        T::aliases()
+    }
 }
 
 /// Implementation for `Vec<T>` where T: Validate.
@@ -237,7 +313,8 @@ macro_rules! impl_validate_noop {
 }
 
 impl_validate_noop!(
-    bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char, String
+    bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char, String,
+    Duration, ByteSize
 );
 
 impl Validate for &str {
@@ -441,6 +518,7 @@ pub mod validators {
     use std::fmt::Display;
     use std::ops::RangeInclusive;
     use std::path::Path;
+    use std::str::FromStr;
 
     // ========================================================================
     // String Validators
@@ -691,6 +769,104 @@ pub mod validators {
         }
     }
 
+    impl<T> Range<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        /// A range whose bounds are parsed from strings, like `"1s"` and
+        /// `"5m"` for a [`Duration`].
+        ///
+        /// # Panics
+        ///
+        /// Panics if either bound does not parse as `T`.
+        pub fn parse(min: &str, max: &str) -> Self {
+            let bound = |s: &str| {
+                s.parse()
+                    .unwrap_or_else(|e| panic!("invalid range bound {:?}: {}", s, e))
+            };
+            Range(bound(min)..=bound(max))
+        }
+    }
+
+    /// Validates that a duration is within a range (inclusive).
+    ///
+    /// Works on both [`Duration`] and `std::time::Duration` fields.
+    #[derive(Debug, Clone)]
+    pub struct DurationRange(pub RangeInclusive<Duration>);
+
+    impl DurationRange {
+        /// A range with bounds like `"1s"` and `"5m"`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if either bound is not a valid duration.
+        pub fn new(min: &str, max: &str) -> Self {
+            DurationRange(Range::parse(min, max).0)
+        }
+    }
+
+    impl Validator<Duration> for DurationRange {
+        fn validate(&self, value: &Duration, path: &str) -> ConfigValidation<()> {
+            if self.0.contains(value) {
+                Validation::Success(())
+            } else {
+                fail(ConfigError::ValidationError {
+                    path: path.to_string(),
+                    source_location: None,
+                    value: Some(value.to_string()),
+                    message: format!(
+                        "duration {} is not in range {}..={}",
+                        value,
+                        self.0.start(),
+                        self.0.end()
+                    ),
+                })
+            }
+        }
+    }
+
+    impl Validator<std::time::Duration> for DurationRange {
+        fn validate(&self, value: &std::time::Duration, path: &str) -> ConfigValidation<()> {
+            self.validate(&Duration::from(*value), path)
+        }
+    }
+
+    /// Validates that a byte size is within a range (inclusive).
+    #[derive(Debug, Clone)]
+    pub struct SizeRange(pub RangeInclusive<ByteSize>);
+
+    impl SizeRange {
+        /// A range with bounds like `"1MiB"` and `"4GiB"`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if either bound is not a valid size.
+        pub fn new(min: &str, max: &str) -> Self {
+            SizeRange(Range::parse(min, max).0)
+        }
+    }
+
+    impl Validator<ByteSize> for SizeRange {
+        fn validate(&self, value: &ByteSize, path: &str) -> ConfigValidation<()> {
+            if self.0.contains(value) {
+                Validation::Success(())
+            } else {
+                fail(ConfigError::ValidationError {
+                    path: path.to_string(),
+                    source_location: None,
+                    value: Some(value.to_string()),
+                    message: format!(
+                        "size {} is not in range {}..={}",
+                        value,
+                        self.0.start(),
+                        self.0.end()
+                    ),
+                })
+            }
+        }
+    }
+
     /// Validates that a numeric value is positive (> 0).
     #[derive(Debug, Clone, Copy)]
     pub struct Positive;
@@ -1303,6 +1479,44 @@ where
     }
 }
 
+// ============================================================================
+// Warn-Level Validation
+// ============================================================================
+
+/// Validator whose failures are warnings instead of errors.
+///
+/// The derive macro's `severity = "warn"` uses the same mechanism. Failures
+/// are collected by [`collect_warnings`], and by `ConfigBuilder` during builds.
+///
+/// # Example
+///
+/// ```ignore
+/// use premortem::validate::{validate_field, Warn, validators::*};
+///
+/// // A pool this large is unusual, but allowed
+/// validate_field(&self.pool_size, "pool_size", &[&Positive, &Warn::new(Range(1..=200))])
+/// ```
+pub struct Warn<V> {
+    validator: V,
+}
+
+impl<V> Warn<V> {
+    /// Downgrade the failures of `validator` to warnings.
+    pub fn new(validator: V) -> Self {
+        Self { validator }
+    }
+}
+
+impl<V, T> Validator<T> for Warn<V>
+where
+    V: Validator<T>,
+    T: ?Sized,
+{
+    fn validate(&self, value: &T, path: &str) -> ConfigValidation<()> {
+        warn_on_failure(self.validator.validate(value, path))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::validators::*;
@@ -1389,6 +1603,47 @@ mod tests {
         }
     }
 
+    // ========================================================================
+    // Warn-Level Validation Tests
+    // ========================================================================
+
+    struct PoolConfig {
+        size: i64,
+    }
+
+    impl Validate for PoolConfig {
+        fn validate(&self) -> ConfigValidation<()> {
+            validate_field(&self.size, "size", &[&Positive, &Warn::new(Range(1..=200))])
+        }
+    }
+
+    #[test]
+    fn test_warn_validator_records_warning_instead_of_error() {
+        let config = PoolConfig { size: 500 };
+        let (result, warnings) = collect_warnings(|| config.validate_at("pool"));
+
+        assert!(result.is_success());
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            ConfigWarning::ValidationWarning { path, value: Some(v), .. }
+                if path == "pool.size" && v == "500"
+        ));
+
+        // Error-level rules still fail
+        let config = PoolConfig { size: -1 };
+        let (result, warnings) = collect_warnings(|| config.validate());
+        assert!(result.is_failure());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_warnings_outside_collector_are_discarded() {
+        assert!(PoolConfig { size: 500 }.validate().is_success());
+        let (_, warnings) = collect_warnings(|| ());
+        assert!(warnings.is_empty());
+    }
+
     // ========================================================================
     // String Validator Tests
     // ========================================================================
@@ -1505,6 +1760,57 @@ mod tests {
         assert!(result.is_failure());
     }
 
+    #[test]
+    fn test_range_parse() {
+        let range = Range::<Duration>::parse("1s", "5m");
+        assert!(range.validate(&Duration::from_secs(90), "ttl").is_success());
+        let Validation::Failure(errors) = range.validate(&Duration::from_secs(600), "ttl") else {
+            panic!("expected failure");
+        };
+        assert!(errors
+            .first()
+            .to_string()
+            .contains("value 10m is not in range 1s..=5m"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid range bound \"soon\"")]
+    fn test_range_parse_rejects_invalid_bounds() {
+        Range::<Duration>::parse("soon", "5m");
+    }
+
+    #[test]
+    fn test_duration_range() {
+        let range = DurationRange::new("100ms", "30s");
+        assert!(range
+            .validate(&Duration::from_secs(1), "timeout")
+            .is_success());
+        assert!(range
+            .validate(&std::time::Duration::from_millis(50), "timeout")
+            .is_failure());
+        let Validation::Failure(errors) = range.validate(&Duration::from_secs(60), "timeout")
+        else {
+            panic!("expected failure");
+        };
+        assert!(errors
+            .first()
+            .to_string()
+            .contains("duration 1m is not in range 100ms..=30s"));
+    }
+
+    #[test]
+    fn test_size_range() {
+        let range = SizeRange::new("1MiB", "4GiB");
+        assert!(range.validate(&ByteSize::mib(512), "cache").is_success());
+        let Validation::Failure(errors) = range.validate(&ByteSize::kib(4), "cache") else {
+            panic!("expected failure");
+        };
+        assert!(errors
+            .first()
+            .to_string()
+            .contains("size 4KiB is not in range 1MiB..=4GiB"));
+    }
+
     #[test]
     fn test_positive_success() {
         assert!(Positive.validate(&42i32, "field").is_success());
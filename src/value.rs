@@ -4,8 +4,10 @@
 //! in an intermediate format before deserialization into the target type.
 
 use std::collections::BTreeMap;
+use std::fmt;
 
 use crate::error::SourceLocation;
+use crate::source::{parse_path, PathSegment};
 
 /// Raw value representation for configuration data.
 ///
@@ -21,10 +23,19 @@ pub enum Value {
     Bool(bool),
     /// Integer value
     Integer(i64),
+    /// Unsigned integer above `i64::MAX`
+    ///
+    /// Smaller unsigned integers are stored as `Integer`, so the same number
+    /// compares equal whichever source it came from.
+    UInteger(u64),
     /// Floating-point value
     Float(f64),
     /// String value
     String(String),
+    /// Date, time or date-time, as found in TOML
+    Datetime(Datetime),
+    /// Byte string
+    Bytes(Vec<u8>),
     /// Array of values
     Array(Vec<Value>),
     /// Table/object of key-value pairs
@@ -53,11 +64,21 @@ impl Value {
         }
     }
 
+    /// Try to get this value as an unsigned integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Integer(i) => u64::try_from(*i).ok(),
+            Value::UInteger(u) => Some(*u),
+            _ => None,
+        }
+    }
+
     /// Try to get this value as a float.
     pub fn as_float(&self) -> Option<f64> {
         match self {
             Value::Float(f) => Some(*f),
             Value::Integer(i) => Some(*i as f64),
+            Value::UInteger(u) => Some(*u as f64),
             _ => None,
         }
     }
@@ -70,6 +91,22 @@ impl Value {
         }
     }
 
+    /// Try to get this value as a date, time or date-time.
+    pub fn as_datetime(&self) -> Option<&Datetime> {
+        match self {
+            Value::Datetime(dt) => Some(dt),
+            _ => None,
+        }
+    }
+
+    /// Try to get this value as a byte string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
     /// Try to get this value as an array.
     pub fn as_array(&self) -> Option<&[Value]> {
         match self {
@@ -86,24 +123,27 @@ impl Value {
         }
     }
 
-    /// Get a value by dot-notation path (e.g., "database.host").
+    /// Get a value by dot-notation path (e.g., "database.host" or "servers\[0\].port").
     ///
     /// Returns `None` for empty paths. If you need the root value, use it directly
     /// rather than calling `get_path("")`.
     pub fn get_path(&self, path: &str) -> Option<&Value> {
-        let parts: Vec<&str> = path.split('.').collect();
-        self.get_path_parts(&parts)
+        if path.is_empty() {
+            return None;
+        }
+        self.get_path_segments(&parse_path(path))
     }
 
-    fn get_path_parts(&self, parts: &[&str]) -> Option<&Value> {
-        if parts.is_empty() {
+    fn get_path_segments(&self, segments: &[PathSegment]) -> Option<&Value> {
+        let Some((first, rest)) = segments.split_first() else {
             return Some(self);
-        }
+        };
 
-        match self {
-            Value::Table(table) => table
-                .get(parts[0])
-                .and_then(|v| v.get_path_parts(&parts[1..])),
+        match (first, self) {
+            (PathSegment::Key(key), Value::Table(table)) => table.get(key)?.get_path_segments(rest),
+            (PathSegment::Index(index), Value::Array(items)) => {
+                items.get(*index)?.get_path_segments(rest)
+            }
             _ => None,
         }
     }
@@ -113,9 +153,11 @@ impl Value {
         match self {
             Value::Null => "null",
             Value::Bool(_) => "boolean",
-            Value::Integer(_) => "integer",
+            Value::Integer(_) | Value::UInteger(_) => "integer",
             Value::Float(_) => "float",
             Value::String(_) => "string",
+            Value::Datetime(_) => "datetime",
+            Value::Bytes(_) => "bytes",
             Value::Array(_) => "array",
             Value::Table(_) => "table",
         }
@@ -140,6 +182,15 @@ impl From<i32> for Value {
     }
 }
 
+impl From<u64> for Value {
+    fn from(u: u64) -> Self {
+        match i64::try_from(u) {
+            Ok(i) => Value::Integer(i),
+            Err(_) => Value::UInteger(u),
+        }
+    }
+}
+
 impl From<f64> for Value {
     fn from(f: f64) -> Self {
         Value::Float(f)
@@ -158,6 +209,12 @@ impl From<&str> for Value {
     }
 }
 
+impl From<Datetime> for Value {
+    fn from(dt: Datetime) -> Self {
+        Value::Datetime(dt)
+    }
+}
+
 impl<T: Into<Value>> From<Vec<T>> for Value {
     fn from(v: Vec<T>) -> Self {
         Value::Array(v.into_iter().map(Into::into).collect())
@@ -170,6 +227,194 @@ impl<T: Into<Value>> From<BTreeMap<String, T>> for Value {
     }
 }
 
+/// A TOML date, time or date-time.
+///
+/// An offset date-time has all three parts, a local date-time has no offset,
+/// and a local date or local time has only its own part. Displays as
+/// RFC 3339, which is what `chrono` and `time` parse when deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Datetime {
+    /// Calendar date, absent for a local time
+    pub date: Option<Date>,
+    /// Time of day, absent for a local date
+    pub time: Option<Time>,
+    /// Offset from UTC, present only for an offset date-time
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    /// Year
+    pub year: u16,
+    /// Month, from 1 to 12
+    pub month: u8,
+    /// Day of the month, from 1 to 31
+    pub day: u8,
+}
+
+/// A time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    /// Hour, from 0 to 23
+    pub hour: u8,
+    /// Minute, from 0 to 59
+    pub minute: u8,
+    /// Second, from 0 to 60 (leap second)
+    pub second: u8,
+    /// Fraction of the second in nanoseconds
+    pub nanosecond: u32,
+}
+
+/// Offset of a date-time from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    /// UTC, written `Z`
+    Z,
+    /// Minutes east of UTC
+    Custom {
+        /// Offset in minutes, negative west of UTC
+        minutes: i16,
+    },
+}
+
+impl Datetime {
+    /// Pure function: parse a date-time as written by `Display`.
+    ///
+    /// Accepts the RFC 3339 forms TOML uses, with `T`, `t` or a space
+    /// between the date and the time.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let (date, rest) = match s.as_bytes().get(4) {
+            Some(b'-') => (Some(parse_date(s.get(..10)?)?), &s[10..]),
+            _ => (None, s),
+        };
+        let rest = match date {
+            Some(_) if rest.is_empty() => {
+                return Some(Self {
+                    date,
+                    time: None,
+                    offset: None,
+                })
+            }
+            Some(_) => rest.strip_prefix(['T', 't', ' '])?,
+            None => rest,
+        };
+
+        let (time, rest) = parse_time(rest)?;
+        let offset = match rest {
+            "" => None,
+            "Z" | "z" => Some(Offset::Z),
+            _ => {
+                let sign = match rest.as_bytes()[0] {
+                    b'+' => 1,
+                    b'-' => -1,
+                    _ => return None,
+                };
+                let (hours, minutes) = rest[1..].split_once(':')?;
+                let minutes = hours.parse::<i16>().ok()? * 60 + minutes.parse::<i16>().ok()?;
+                Some(Offset::Custom {
+                    minutes: sign * minutes,
+                })
+            }
+        };
+        Some(Self {
+            date,
+            time: Some(time),
+            offset,
+        })
+    }
+}
+
+/// Pure function: parse `YYYY-MM-DD`.
+fn parse_date(s: &str) -> Option<Date> {
+    let mut parts = s.splitn(3, '-');
+    Some(Date {
+        year: parts.next()?.parse().ok()?,
+        month: parts.next()?.parse().ok()?,
+        day: parts.next()?.parse().ok()?,
+    })
+}
+
+/// Pure function: parse `HH:MM:SS[.fraction]`, returning what follows it.
+fn parse_time(s: &str) -> Option<(Time, &str)> {
+    let field = |range: std::ops::Range<usize>| s.get(range)?.parse::<u8>().ok();
+    if s.get(2..3) != Some(":") || s.get(5..6) != Some(":") {
+        return None;
+    }
+    let (hour, minute, second) = (field(0..2)?, field(3..5)?, field(6..8)?);
+
+    let mut rest = &s[8..];
+    let mut nanosecond = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+        nanosecond = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+    Some((
+        Time {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        },
+        rest,
+    ))
+}
+
+impl fmt::Display for Datetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(date) = &self.date {
+            write!(f, "{}", date)?;
+        }
+        if let Some(time) = &self.time {
+            if self.date.is_some() {
+                write!(f, "T")?;
+            }
+            write!(f, "{}", time)?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, "{}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond != 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Offset::Z => write!(f, "Z"),
+            Offset::Custom { minutes } => {
+                let sign = if *minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
 /// A configuration value with source tracking.
 ///
 /// This struct wraps a `Value` with information about where it originated,
@@ -237,6 +482,28 @@ mod tests {
         );
         assert!(value.get_path("database.password").is_none());
         assert!(value.get_path("other").is_none());
+        assert!(value.get_path("").is_none());
+    }
+
+    #[test]
+    fn test_value_get_path_with_indices() {
+        let mut server = BTreeMap::new();
+        server.insert("port".to_string(), Value::Integer(8080));
+        let mut root = BTreeMap::new();
+        root.insert(
+            "servers".to_string(),
+            Value::Array(vec![Value::Null, Value::Table(server)]),
+        );
+        let value = Value::Table(root);
+
+        assert_eq!(
+            value
+                .get_path("servers[1].port")
+                .and_then(|v| v.as_integer()),
+            Some(8080)
+        );
+        assert!(value.get_path("servers[2]").is_none());
+        assert!(value.get_path("servers.port").is_none());
     }
 
     #[test]
@@ -244,6 +511,7 @@ mod tests {
         assert_eq!(Value::Null.type_name(), "null");
         assert_eq!(Value::Bool(true).type_name(), "boolean");
         assert_eq!(Value::Integer(42).type_name(), "integer");
+        assert_eq!(Value::UInteger(u64::MAX).type_name(), "integer");
         assert_eq!(Value::Float(2.71).type_name(), "float");
         assert_eq!(Value::String("test".to_string()).type_name(), "string");
         assert_eq!(Value::Array(vec![]).type_name(), "array");
@@ -259,6 +527,63 @@ mod tests {
         let _: Value = "hello".into();
         let _: Value = String::from("hello").into();
         let _: Value = vec![1i64, 2, 3].into();
+        assert_eq!(Value::from(42u64), Value::Integer(42));
+        assert_eq!(Value::from(u64::MAX), Value::UInteger(u64::MAX));
+        assert_eq!(Value::from(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(Value::Integer(-1).as_u64(), None);
+    }
+
+    #[test]
+    fn test_datetime_display() {
+        let date = Date {
+            year: 1979,
+            month: 5,
+            day: 27,
+        };
+        let time = Time {
+            hour: 7,
+            minute: 32,
+            second: 0,
+            nanosecond: 999_000_000,
+        };
+        let offset_datetime = Datetime {
+            date: Some(date),
+            time: Some(time),
+            offset: Some(Offset::Custom { minutes: -420 }),
+        };
+        assert_eq!(offset_datetime.to_string(), "1979-05-27T07:32:00.999-07:00");
+
+        let local_date = Datetime {
+            date: Some(date),
+            time: None,
+            offset: None,
+        };
+        assert_eq!(local_date.to_string(), "1979-05-27");
+
+        let utc = Datetime {
+            offset: Some(Offset::Z),
+            ..offset_datetime
+        };
+        assert!(utc.to_string().ends_with(".999Z"));
+    }
+
+    #[test]
+    fn test_datetime_parse_round_trips_display() {
+        for text in [
+            "1979-05-27T07:32:00.999-07:00",
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T07:32:00",
+            "1979-05-27",
+            "07:32:00.000001",
+        ] {
+            let parsed = Datetime::parse(text).unwrap();
+            assert_eq!(parsed.to_string(), text);
+        }
+
+        let spaced = Datetime::parse("1979-05-27 07:32:00+05:30").unwrap();
+        assert_eq!(spaced.offset, Some(Offset::Custom { minutes: 330 }));
+        assert!(Datetime::parse("yesterday").is_none());
+        assert!(Datetime::parse("1979-05-27T07:32").is_none());
     }
 
     #[test]
@@ -44,7 +44,7 @@ use std::time::{Duration, Instant};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::de::DeserializeOwned;
 
-use crate::config::ConfigBuilder;
+use crate::config::{BuildOptions, ConfigBuilder};
 use crate::env::{ConfigEnv, RealEnv};
 use crate::error::{ConfigError, ConfigErrors, SourceErrorKind};
 use crate::source::Source;
@@ -228,6 +228,7 @@ impl Drop for ConfigWatcher {
 struct ReloadState<T> {
     current: Arc<RwLock<Arc<T>>>,
     sources: Vec<Box<dyn Source>>,
+    options: BuildOptions,
     event_sender: EventSender,
     stop_signal: Arc<AtomicBool>,
     debounce_duration: Duration,
@@ -239,6 +240,7 @@ struct ReloadState<T> {
 struct Reloader<T> {
     current: Arc<RwLock<Arc<T>>>,
     sources: Arc<Vec<Box<dyn Source>>>,
+    options: BuildOptions,
     event_sender: EventSender,
 }
 
@@ -247,6 +249,7 @@ impl<T> Clone for Reloader<T> {
         Self {
             current: Arc::clone(&self.current),
             sources: Arc::clone(&self.sources),
+            options: self.options.clone(),
             event_sender: self.event_sender.clone(),
         }
     }
@@ -262,7 +265,7 @@ where
     /// On failure, the old configuration is preserved.
     fn reload(&self) -> Result<(), ConfigErrors> {
         let env = RealEnv::new();
-        let mut builder = ConfigBuilder::<T>::new();
+        let mut builder = ConfigBuilder::<T>::with_options(self.options.clone());
         for source in self.sources.iter() {
             builder = builder.source(SourceWrapper(source.clone_box()));
         }
@@ -295,6 +298,18 @@ pub fn build_watched<T>(
     sources: Vec<Box<dyn Source>>,
     env: &dyn ConfigEnv,
 ) -> Result<(WatchedConfig<T>, ConfigWatcher), ConfigErrors>
+where
+    T: DeserializeOwned + Validate + Send + Sync + 'static,
+{
+    build_watched_with_options(sources, BuildOptions::default(), env)
+}
+
+/// Build a watched configuration, reusing the builder's settings on every reload.
+pub(crate) fn build_watched_with_options<T>(
+    sources: Vec<Box<dyn Source>>,
+    options: BuildOptions,
+    env: &dyn ConfigEnv,
+) -> Result<(WatchedConfig<T>, ConfigWatcher), ConfigErrors>
 where
     T: DeserializeOwned + Validate + Send + Sync + 'static,
 {
@@ -302,13 +317,19 @@ where
     let sources_for_reload: Vec<Box<dyn Source>> = sources.iter().map(|s| s.clone_box()).collect();
 
     // First, do initial build using the original sources
-    let mut builder = ConfigBuilder::<T>::new();
+    let mut builder = ConfigBuilder::<T>::with_options(options.clone());
     for source in sources {
         builder = builder.source(SourceWrapper(source));
     }
 
     let config = builder.build_with_env(env)?;
 
+    // A failed reload keeps the current config instead of the snapshot
+    let options = BuildOptions {
+        snapshot_fallback: false,
+        ..options
+    };
+
     // Create watched config wrapper
     let current = Arc::new(RwLock::new(Arc::new(config.into_inner())));
     let watched = WatchedConfig {
@@ -368,6 +389,7 @@ where
     let reloader = Reloader {
         current: Arc::clone(&current),
         sources: Arc::clone(&sources_arc),
+        options: options.clone(),
         event_sender: event_sender.clone(),
     };
 
@@ -375,6 +397,7 @@ where
     let state = ReloadState {
         current,
         sources: sources_arc.iter().map(|s| s.clone_box()).collect(),
+        options,
         event_sender: event_sender.clone(),
         stop_signal: Arc::clone(&stop_signal),
         debounce_duration: Duration::from_millis(100),
@@ -422,7 +445,7 @@ where
 
                     // Attempt reload
                     let env = RealEnv::new();
-                    let mut builder = ConfigBuilder::<T>::new();
+                    let mut builder = ConfigBuilder::<T>::with_options(state.options.clone());
                     for source in &state.sources {
                         builder = builder.source(SourceWrapper(source.clone_box()));
                     }
@@ -466,6 +489,18 @@ impl Source for SourceWrapper {
         self.0.name()
     }
 
+    fn has_profiles(&self) -> bool {
+        self.0.has_profiles()
+    }
+
+    fn mount_point(&self) -> Option<&str> {
+        self.0.mount_point()
+    }
+
+    fn trust(&self) -> crate::source::Trust {
+        self.0.trust()
+    }
+
     fn watch_path(&self) -> Option<PathBuf> {
         self.0.watch_path()
     }
@@ -620,6 +655,7 @@ mod tests {
         let reloader: Reloader<SerializableConfig> = Reloader {
             current: Arc::clone(&current),
             sources: Arc::new(sources),
+            options: BuildOptions::default(),
             event_sender,
         };
 
@@ -233,6 +233,57 @@ fn test_non_zero_failure() {
     assert!(config.validate().is_failure());
 }
 
+#[derive(DeriveValidate)]
+struct UnitConfig {
+    #[validate(range("1s"..="5m"))]
+    ttl: premortem::Duration,
+
+    #[validate(range(..="4GiB"))]
+    cache: premortem::ByteSize,
+
+    // Valid as durations and as sizes; the field type decides
+    #[validate(range(..="5m"))]
+    poll: std::time::Duration,
+
+    #[validate(range("1m"..="10m"))]
+    chunk: premortem::ByteSize,
+}
+
+#[test]
+fn test_unit_range() {
+    let config = UnitConfig {
+        ttl: premortem::Duration::from_secs(90),
+        cache: premortem::ByteSize::mib(512),
+        poll: std::time::Duration::from_secs(300),
+        chunk: premortem::ByteSize::new(1_000_000),
+    };
+    assert!(config.validate().is_success());
+
+    let config = UnitConfig {
+        ttl: premortem::Duration::from_secs(600),
+        cache: premortem::ByteSize::gib(8),
+        poll: std::time::Duration::from_secs(301),
+        chunk: premortem::ByteSize::mib(10),
+    };
+    let Validation::Failure(errors) = config.validate() else {
+        panic!("expected failure");
+    };
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(messages.len(), 4, "{:?}", messages);
+    for expected in [
+        "duration 10m is not in range 1s..=5m",
+        "size 8GiB is not in range 0B..=4GiB",
+        "duration 5m1s is not in range 0s..=5m",
+        "size 10MiB is not in range 1MB..=10MB",
+    ] {
+        assert!(
+            messages.iter().any(|m| m.contains(expected)),
+            "{:?}",
+            messages
+        );
+    }
+}
+
 // ============================================================================
 // Nested Validation Tests
 // ============================================================================
@@ -682,3 +733,189 @@ fn test_complex_nested_failure() {
         assert_eq!(errors.len(), 2, "Expected 2 errors, got {}", errors.len());
     }
 }
+
+// ============================================================================
+// Key Alias Tests
+// ============================================================================
+
+#[derive(Debug, serde::Deserialize, DeriveValidate)]
+struct PoolSettings {
+    #[config(alias = "max_conns", deprecated_since = "0.7")]
+    max_connections: u32,
+}
+
+#[derive(Debug, serde::Deserialize, DeriveValidate)]
+struct AliasedConfig {
+    #[config(alias = "db_url")]
+    #[validate(non_empty)]
+    connection_url: String,
+
+    #[validate(nested)]
+    pool: PoolSettings,
+}
+
+#[test]
+fn test_derived_aliases_include_nested_fields() {
+    let aliases = AliasedConfig::aliases();
+    assert_eq!(aliases.len(), 2);
+    assert_eq!(
+        aliases[0],
+        premortem::KeyAlias::new("db_url", "connection_url")
+    );
+    assert_eq!(
+        aliases[1],
+        premortem::KeyAlias::new("pool.max_conns", "pool.max_connections").deprecated_since("0.7")
+    );
+
+    // Aliases only rename keys; the fields and their validation are unchanged
+    let config = AliasedConfig {
+        connection_url: String::new(),
+        pool: PoolSettings { max_connections: 5 },
+    };
+    assert_eq!(config.pool.max_connections, 5);
+    assert!(config.validate().is_failure());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_derived_aliases_rename_keys_when_building() {
+    use premortem::{Config, ConfigWarning, MockEnv, Toml};
+
+    let env = MockEnv::new().with_file(
+        "config.toml",
+        "db_url = \"postgres://localhost\"\n[pool]\nmax_conns = 5\n",
+    );
+
+    let (config, warnings) = Config::<AliasedConfig>::builder()
+        .source(Toml::file("config.toml"))
+        .build_with_warnings_with_env(&env)
+        .unwrap();
+
+    assert_eq!(config.connection_url, "postgres://localhost");
+    assert_eq!(config.pool.max_connections, 5);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|w| matches!(w, ConfigWarning::DeprecatedKey { .. })));
+    assert!(warnings.iter().any(|w| w.to_string()
+        == "[config.toml:3] 'pool.max_conns' is deprecated; use 'pool.max_connections' \
+                instead (deprecated since 0.7)"));
+}
+
+// ============================================================================
+// Severity Tests
+// ============================================================================
+
+#[derive(Debug, serde::Deserialize, DeriveValidate)]
+struct AdvisoryConfig {
+    #[validate(positive)]
+    #[validate(range(1..=200), severity = "warn", message = "pool_size above 200 is unusual")]
+    pool_size: i64,
+}
+
+#[test]
+fn test_warn_severity_does_not_fail_validation() {
+    let config = AdvisoryConfig { pool_size: 500 };
+    let (result, warnings) = premortem::collect_warnings(|| config.validate());
+
+    assert!(result.is_success());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path(), "pool_size");
+    assert_eq!(
+        warnings[0].to_string(),
+        "'pool_size': pool_size above 200 is unusual"
+    );
+
+    // Error-level rules on the same field still fail
+    let config = AdvisoryConfig { pool_size: -1 };
+    let (result, _) = premortem::collect_warnings(|| config.validate());
+    assert!(result.is_failure());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_warn_severity_is_reported_by_the_builder() {
+    use premortem::{Config, ConfigWarning, MockEnv, Toml};
+
+    let env = MockEnv::new().with_file("config.toml", "pool_size = 500\n");
+
+    let (config, warnings) = Config::<AdvisoryConfig>::builder()
+        .source(Toml::file("config.toml"))
+        .build_with_warnings_with_env(&env)
+        .unwrap();
+    assert_eq!(config.pool_size, 500);
+    assert!(matches!(
+        &warnings[..],
+        [ConfigWarning::ValidationWarning { source_location: Some(loc), .. }] if loc.line == Some(1)
+    ));
+
+    let errors = Config::<AdvisoryConfig>::builder()
+        .source(Toml::file("config.toml"))
+        .warnings_as_errors()
+        .build_with_env(&env)
+        .unwrap_err();
+    assert_eq!(errors.first().path(), Some("pool_size"));
+}
+
+// ============================================================================
+// Secret Tests
+// ============================================================================
+
+#[derive(Debug, serde::Deserialize, DeriveValidate)]
+struct VaultConfig {
+    #[validate(non_empty)]
+    address: String,
+    #[validate(min_length(12))]
+    token: premortem::Secret<String>,
+}
+
+#[test]
+fn test_secret_fields_are_validated_and_redacted() {
+    let config = VaultConfig {
+        address: "https://vault".to_string(),
+        token: premortem::Secret::new("s.short".to_string()),
+    };
+    assert_eq!(
+        format!("{:?}", config),
+        r#"VaultConfig { address: "https://vault", token: [REDACTED] }"#
+    );
+
+    let Validation::Failure(errors) = config.validate() else {
+        panic!("short token should fail validation");
+    };
+    assert_eq!(errors.len(), 1);
+    match errors.first() {
+        ConfigError::ValidationError { path, value, .. } => {
+            assert_eq!(path, "token");
+            assert_eq!(value, &None);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(!errors.to_string().contains("s.short"));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_secret_type_errors_leave_out_the_value() {
+    use premortem::{Config, MockEnv, Toml};
+
+    #[derive(Debug, serde::Deserialize, DeriveValidate)]
+    struct PinConfig {
+        pin: premortem::Secret<u32>,
+    }
+
+    let env = MockEnv::new().with_file("config.toml", "pin = \"12ab\"\n");
+    let errors = Config::<PinConfig>::builder()
+        .source(Toml::file("config.toml"))
+        .build_with_env(&env)
+        .unwrap_err();
+    assert_eq!(errors.first().path(), Some("pin"));
+    assert!(!errors.to_string().contains("12ab"), "{}", errors);
+
+    let env = MockEnv::new().with_file("config.toml", "pin = 1234\n");
+    let config = Config::<PinConfig>::builder()
+        .source(Toml::file("config.toml"))
+        .build_with_env(&env)
+        .unwrap();
+    assert_eq!(*config.pin.expose_secret(), 1234);
+}
@@ -296,10 +296,15 @@ mod value_roundtrips {
             Value::Null => serde_json::Value::Null,
             Value::Bool(b) => serde_json::Value::Bool(*b),
             Value::Integer(i) => serde_json::Value::Number((*i).into()),
+            Value::UInteger(u) => serde_json::Value::Number((*u).into()),
             Value::Float(f) => serde_json::Number::from_f64(*f)
                 .map(serde_json::Value::Number)
                 .unwrap_or(serde_json::Value::Null),
             Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::Datetime(dt) => serde_json::Value::String(dt.to_string()),
+            Value::Bytes(bytes) => {
+                serde_json::Value::Array(bytes.iter().map(|b| (*b).into()).collect())
+            }
             Value::Array(arr) => serde_json::Value::Array(arr.iter().map(value_to_json).collect()),
             Value::Table(table) => {
                 let map: serde_json::Map<String, serde_json::Value> = table
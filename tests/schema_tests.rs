@@ -0,0 +1,234 @@
+//! Integration tests for the ConfigSchema derive macro.
+
+use std::collections::HashMap;
+
+use premortem::prelude::*;
+use premortem_derive::ConfigSchema as DeriveConfigSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Connection settings for the primary database.
+#[derive(Debug, Deserialize, DeriveConfigSchema)]
+#[allow(dead_code)]
+struct DatabaseConfig {
+    /// Hostname or IP address.
+    #[validate(non_empty)]
+    host: String,
+
+    #[validate(range(1..=65535))]
+    port: u16,
+
+    #[serde(default)]
+    #[validate(range(1..=100))]
+    pool_size: u32,
+
+    #[sensitive]
+    #[validate(min_length(8))]
+    password: String,
+}
+
+#[derive(Debug, Deserialize, DeriveConfigSchema)]
+#[allow(dead_code)]
+struct Limits {
+    #[validate(positive)]
+    max_requests: i64,
+}
+
+#[derive(Debug, Deserialize, DeriveConfigSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[allow(dead_code)]
+struct AppConfig {
+    #[validate(nested)]
+    database: DatabaseConfig,
+
+    #[validate(email)]
+    admin_email: Option<String>,
+
+    #[validate(non_empty, each(url))]
+    upstream_urls: Vec<String>,
+
+    #[validate(extension("toml"))]
+    include_file: std::path::PathBuf,
+
+    #[validate(pattern(r"^[a-z]+$"))]
+    #[serde(rename = "env")]
+    environment: String,
+
+    labels: HashMap<String, String>,
+
+    #[serde(flatten)]
+    limits: Limits,
+
+    #[serde(skip)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn test_schema_root_is_draft_2020_12() {
+    let schema = Config::<AppConfig>::json_schema();
+    assert_eq!(
+        schema["$schema"],
+        json!("https://json-schema.org/draft/2020-12/schema")
+    );
+    assert_eq!(schema["title"], json!("AppConfig"));
+    assert_eq!(schema["type"], json!("object"));
+    assert_eq!(schema["additionalProperties"], json!(false));
+}
+
+#[test]
+fn test_schema_validators_become_keywords() {
+    let schema = Config::<AppConfig>::json_schema();
+    let props = &schema["properties"];
+
+    let database = &props["database"];
+    assert_eq!(
+        database["description"],
+        json!("Connection settings for the primary database.")
+    );
+    assert_eq!(
+        database["properties"]["host"],
+        json!({ "type": "string", "minLength": 1, "description": "Hostname or IP address." })
+    );
+    assert_eq!(database["properties"]["port"]["minimum"], json!(1));
+    assert_eq!(database["properties"]["port"]["maximum"], json!(65535));
+    assert_eq!(database["properties"]["password"]["minLength"], json!(8));
+    assert_eq!(database["properties"]["password"]["writeOnly"], json!(true));
+    assert_eq!(database["required"], json!(["host", "port", "password"]));
+
+    assert_eq!(props["admin-email"]["format"], json!("email"));
+    assert_eq!(props["admin-email"]["type"], json!(["string", "null"]));
+    assert_eq!(props["upstream-urls"]["minItems"], json!(1));
+    assert_eq!(props["upstream-urls"]["items"]["format"], json!("uri"));
+    assert_eq!(props["include-file"]["pattern"], json!("\\.toml$"));
+    assert_eq!(props["env"]["pattern"], json!("^[a-z]+$"));
+    assert_eq!(
+        props["labels"]["additionalProperties"],
+        json!({ "type": "string" })
+    );
+    assert_eq!(props["max_requests"]["exclusiveMinimum"], json!(0));
+}
+
+#[test]
+fn test_schema_required_skip_and_flatten() {
+    let schema = Config::<AppConfig>::json_schema();
+
+    assert_eq!(
+        schema["required"],
+        json!([
+            "database",
+            "upstream-urls",
+            "include-file",
+            "env",
+            "labels",
+            "max_requests"
+        ])
+    );
+    assert!(schema["properties"].get("cache").is_none());
+    assert!(schema["properties"].get("limits").is_none());
+}
+
+#[test]
+fn test_docs_render_nested_sections() {
+    let markdown = ConfigDocs::of::<AppConfig>()
+        .env(&Env::prefix("APP_").separator("__"))
+        .to_markdown();
+
+    assert!(markdown.starts_with("# AppConfig\n"));
+    assert!(markdown.contains("\n## database\n\nConnection settings for the primary database.\n"));
+    assert!(markdown.contains(
+        "| `database.host` | `String` | yes |  | `non_empty` | `APP_DATABASE__HOST` | Hostname or IP address. |"
+    ));
+    assert!(markdown.contains("| `database.pool_size` | `u32` | no |  | `range(1..=100)` |"));
+    assert!(markdown.contains("| `admin-email` | `Option<String>` | no |  | `email` |"));
+    assert!(markdown
+        .contains("| `upstream-urls` | `Vec<String>` | yes |  | `non_empty`, `each(url)` |"));
+    // Flattened fields sit in the parent table
+    assert!(markdown.contains("| `max_requests` | `i64` |"));
+    assert!(!markdown.contains("cache"));
+}
+
+#[test]
+fn test_docs_html() {
+    let html = ConfigDocs::of::<AppConfig>().to_html();
+    assert!(html.contains("<h2 id=\"database\">database</h2>"));
+    assert!(html.contains("<td><code>HashMap&lt;String, String&gt;</code></td>"));
+}
+
+#[derive(Debug, Deserialize, DeriveValidate, DeriveConfigSchema)]
+struct PoolConfig {
+    max_connections: u32,
+    idle_timeout: u32,
+}
+
+#[derive(Debug, Deserialize, DeriveValidate, DeriveConfigSchema)]
+struct ServiceConfig {
+    max_connections: u32,
+    #[validate(nested)]
+    database: PoolConfig,
+}
+
+#[test]
+fn test_env_for_type_maps_multi_word_fields() {
+    let env = MockEnv::new()
+        .with_env("APP_MAX_CONNECTIONS", "100")
+        .with_env("APP_DATABASE_MAX_CONNECTIONS", "10")
+        .with_env("APP_DATABASE_IDLE_TIMEOUT", "30")
+        .with_env("APP_DATABASE_IDLE_TIMOUT", "typo");
+
+    let (config, warnings) = Config::<ServiceConfig>::builder()
+        .source(Env::prefix("APP_").for_type::<ServiceConfig>())
+        .build_with_warnings_with_env(&env)
+        .unwrap();
+
+    assert_eq!(config.max_connections, 100);
+    assert_eq!(config.database.max_connections, 10);
+    assert_eq!(config.database.idle_timeout, 30);
+    assert!(warnings.iter().any(|w| matches!(
+        w,
+        ConfigWarning::UnmatchedEnvVar { var, .. } if var == "APP_DATABASE_IDLE_TIMOUT"
+    )));
+
+    let markdown = ConfigDocs::of::<ServiceConfig>()
+        .env(&Env::prefix("APP_").for_type::<ServiceConfig>())
+        .to_markdown();
+    assert!(markdown.contains("`APP_DATABASE_IDLE_TIMEOUT`"));
+}
+
+/// How the service may touch its data.
+#[derive(Debug, Deserialize, DeriveConfigSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+enum AccessMode {
+    ReadOnly,
+    ReadWrite,
+    #[serde(rename = "off")]
+    Disabled,
+}
+
+#[derive(Debug, Deserialize, DeriveConfigSchema)]
+#[allow(dead_code)]
+struct AccessConfig {
+    mode: AccessMode,
+    fallback: Option<AccessMode>,
+}
+
+#[test]
+fn test_schema_unit_enum_is_string_enum() {
+    let schema = Config::<AccessConfig>::json_schema();
+    let props = &schema["properties"];
+    assert_eq!(
+        props["mode"],
+        json!({
+            "title": "AccessMode",
+            "description": "How the service may touch its data.",
+            "type": "string",
+            "enum": ["read_only", "read_write", "off"]
+        })
+    );
+    assert_eq!(props["fallback"]["type"], json!(["string", "null"]));
+    assert_eq!(
+        props["fallback"]["enum"],
+        json!(["read_only", "read_write", "off", null])
+    );
+    assert_eq!(schema["required"], json!(["mode"]));
+}